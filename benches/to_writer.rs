@@ -0,0 +1,64 @@
+//! Compares writing a document straight to a file with `to_writer` against
+//! `to_writer_buffered`.
+
+use std::fs::File;
+use std::io::{Seek, SeekFrom};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use serde_nbt::binary::{to_writer, to_writer_buffered};
+use serde_nbt::{Compound, List, Value};
+
+/// A document of about 2 MB with 20,000 entries, each a small compound of
+/// mixed scalars.
+fn document() -> Compound {
+    Compound::from_iter((0..20_000).map(|i| {
+        let entry = Compound::from_iter([
+            ("id".to_owned(), Value::Int(i)),
+            ("name".to_owned(), Value::String(format!("entry_{i}"))),
+            ("pos".to_owned(), List::Double(vec![1.0, 64.0, -3.5]).into()),
+            ("flags".to_owned(), Value::ByteArray(vec![0; 40])),
+        ]);
+
+        (format!("e{i}"), entry.into())
+    }))
+}
+
+fn bench_to_writer(c: &mut Criterion) {
+    let doc = document();
+    let mut file = tempfile();
+
+    let mut group = c.benchmark_group("file");
+    group.sample_size(10);
+
+    group.bench_function("to_writer", |b| {
+        b.iter(|| {
+            file.seek(SeekFrom::Start(0)).unwrap();
+            to_writer(&mut file, &doc).unwrap();
+        })
+    });
+
+    group.bench_function("to_writer_buffered", |b| {
+        b.iter(|| {
+            file.seek(SeekFrom::Start(0)).unwrap();
+            to_writer_buffered(&mut file, &doc).unwrap();
+        })
+    });
+
+    group.finish();
+}
+
+fn tempfile() -> File {
+    let path = std::env::temp_dir().join(format!("serde_nbt_bench_{}", std::process::id()));
+    let file = File::options()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&path)
+        .unwrap();
+    let _ = std::fs::remove_file(&path);
+    file
+}
+
+criterion_group!(benches, bench_to_writer);
+criterion_main!(benches);
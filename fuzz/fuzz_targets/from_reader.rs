@@ -0,0 +1,12 @@
+//! Feeds arbitrary bytes to the deserializer. Malformed input must produce an
+//! error, never a panic.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use serde_nbt::binary::from_reader;
+use serde_nbt::Value;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = from_reader::<_, Value>(data);
+});
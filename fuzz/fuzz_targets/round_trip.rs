@@ -0,0 +1,95 @@
+//! Generates arbitrary NBT values, writes them to binary, and checks that
+//! reading them back produces the same value.
+
+#![no_main]
+
+use arbitrary::{Result, Unstructured};
+use libfuzzer_sys::fuzz_target;
+use serde_nbt::binary::{from_slice_exact, to_vec};
+use serde_nbt::{Compound, List, Value};
+
+/// Limits the nesting of lists and compounds in generated values.
+const MAX_DEPTH: usize = 8;
+
+fuzz_target!(|data: &[u8]| {
+    let mut u = Unstructured::new(data);
+
+    let Ok(compound) = arb_compound(&mut u, 0) else {
+        return;
+    };
+
+    // Strings longer than `u16::MAX` bytes cannot be encoded.
+    let Ok(bytes) = to_vec(&compound) else {
+        return;
+    };
+
+    let parsed: Value = from_slice_exact(&bytes).unwrap();
+
+    // Compare the encodings rather than the values so that floats are compared
+    // bit for bit and NaNs are equal to themselves.
+    assert_eq!(to_vec(&parsed).unwrap(), bytes);
+});
+
+fn arb_value(u: &mut Unstructured, depth: usize) -> Result<Value> {
+    // Only pick a list or compound if there is room to nest.
+    let choices = if depth < MAX_DEPTH { 12 } else { 10 };
+
+    Ok(match u.choose_index(choices)? {
+        0 => Value::Byte(u.arbitrary()?),
+        1 => Value::Short(u.arbitrary()?),
+        2 => Value::Int(u.arbitrary()?),
+        3 => Value::Long(u.arbitrary()?),
+        4 => Value::Float(u.arbitrary()?),
+        5 => Value::Double(u.arbitrary()?),
+        6 => Value::ByteArray(u.arbitrary()?),
+        7 => Value::String(u.arbitrary()?),
+        8 => Value::IntArray(u.arbitrary()?),
+        9 => Value::LongArray(u.arbitrary()?),
+        10 => Value::List(arb_list(u, depth + 1)?),
+        _ => Value::Compound(arb_compound(u, depth + 1)?),
+    })
+}
+
+fn arb_list(u: &mut Unstructured, depth: usize) -> Result<List> {
+    let choices = if depth < MAX_DEPTH { 12 } else { 10 };
+
+    Ok(match u.choose_index(choices)? {
+        0 => List::Byte(u.arbitrary()?),
+        1 => List::Short(u.arbitrary()?),
+        2 => List::Int(u.arbitrary()?),
+        3 => List::Long(u.arbitrary()?),
+        4 => List::Float(u.arbitrary()?),
+        5 => List::Double(u.arbitrary()?),
+        6 => List::ByteArray(u.arbitrary()?),
+        7 => List::String(u.arbitrary()?),
+        8 => List::IntArray(u.arbitrary()?),
+        9 => List::LongArray(u.arbitrary()?),
+        10 => {
+            let len = u.arbitrary_len::<u8>()?;
+            List::List(
+                (0..len)
+                    .map(|_| arb_list(u, depth + 1))
+                    .collect::<Result<_>>()?,
+            )
+        }
+        _ => {
+            let len = u.arbitrary_len::<u8>()?;
+            List::Compound(
+                (0..len)
+                    .map(|_| arb_compound(u, depth + 1))
+                    .collect::<Result<_>>()?,
+            )
+        }
+    })
+}
+
+fn arb_compound(u: &mut Unstructured, depth: usize) -> Result<Compound> {
+    let len = u.arbitrary_len::<(String, u8)>()?;
+    let mut compound = Compound::with_capacity(len);
+
+    for _ in 0..len {
+        compound.insert(u.arbitrary()?, arb_value(u, depth)?);
+    }
+
+    Ok(compound)
+}
@@ -1,23 +1,38 @@
 use std::fmt::Formatter;
 use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
 
 use serde::de::value::SeqAccessDeserializer;
 use serde::de::{EnumAccess, IgnoredAny, SeqAccess, VariantAccess, Visitor};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::{
-    ARRAY_ENUM_NAME, BYTE_ARRAY_VARIANT_NAME, INT_ARRAY_VARIANT_NAME, LONG_ARRAY_VARIANT_NAME,
+    Value, ARRAY_ENUM_NAME, BYTE_ARRAY_VARIANT_NAME, INT_ARRAY_VARIANT_NAME,
+    LONG_ARRAY_VARIANT_NAME,
 };
 
 macro_rules! def_mod {
-    ($index:literal, $mod_name:ident, $display_name:literal, $variant_name:ident) => {
+    (
+        $index:literal,
+        $mod_name:ident,
+        $display_name:literal,
+        $variant_name:ident,
+        $vanilla_use:literal
+    ) => {
         /// Provides (de)serialization support for the NBT type
         #[doc = concat!(" \"", $display_name, "\".")]
         ///
         /// This module is intended to be the target of serde's `#[serde(with =
         /// "module")]` field attribute.
         ///
-        /// The target field must serialize and deserialize as a seq.
+        /// The target field must serialize and deserialize as a seq. Without
+        /// this module, a seq serializes as a list (`TAG_List`) instead, which
+        /// is a different type in NBT. Vanilla Minecraft expects the array
+        /// type for data such as
+        #[doc = concat!(" ", $vanilla_use, ".")]
+        ///
+        /// The elements may be of any signed integer type. Serialization fails
+        /// if an element does not fit in the element type of the array.
         ///
         /// # Examples
         ///
@@ -96,6 +111,120 @@ macro_rules! def_mod {
     };
 }
 
-def_mod!(0, byte_array, "byte array", BYTE_ARRAY_VARIANT_NAME);
-def_mod!(1, int_array, "int array", INT_ARRAY_VARIANT_NAME);
-def_mod!(2, long_array, "long array", LONG_ARRAY_VARIANT_NAME);
+def_mod!(
+    0,
+    byte_array,
+    "byte array",
+    BYTE_ARRAY_VARIANT_NAME,
+    "the `Blocks` and `Data` of chunk sections before 1.13"
+);
+def_mod!(
+    1,
+    int_array,
+    "int array",
+    INT_ARRAY_VARIANT_NAME,
+    "UUIDs since 1.16 and the `Biomes` of chunks from 1.13 to 1.17"
+);
+def_mod!(
+    2,
+    long_array,
+    "long array",
+    LONG_ARRAY_VARIANT_NAME,
+    "the packed `BlockStates` of chunk sections and `Heightmaps`"
+);
+
+macro_rules! def_wrapper {
+    (
+        $name:ident,
+        $mod_name:ident,
+        $elem:ty,
+        $display_name:literal
+    ) => {
+        /// A wrapper around a `Vec` that always (de)serializes as the NBT type
+        #[doc = concat!(" \"", $display_name, "\".")]
+        ///
+        /// This is an alternative to the
+        #[doc = concat!(" [`", stringify!($mod_name), "`](crate::", stringify!($mod_name), ")")]
+        /// module that does not need a `#[serde(with = "...")]` attribute,
+        /// which makes it usable in places where attributes are not, such as
+        /// inside of other containers and in generic code.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use serde::{Deserialize, Serialize};
+        #[doc = concat!(" use serde_nbt::", stringify!($name), ";")]
+        /// use serde_nbt::binary::{from_reader, to_writer};
+        ///
+        /// #[derive(PartialEq, Debug, Serialize, Deserialize)]
+        /// struct MyStruct {
+        #[doc = concat!("     arrays: Vec<", stringify!($name), ">,")]
+        /// }
+        ///
+        /// let s = MyStruct {
+        #[doc = concat!("     arrays: vec![", stringify!($name), "(vec![1, 2, 3])],")]
+        /// };
+        ///
+        /// let mut buf = Vec::new();
+        /// to_writer(&mut buf, &s).unwrap();
+        ///
+        /// assert_eq!(from_reader::<_, MyStruct>(buf.as_slice()).unwrap(), s);
+        /// ```
+        #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Debug)]
+        pub struct $name(pub Vec<$elem>);
+
+        impl Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                $mod_name::serialize(&self.0, serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                $mod_name::deserialize(deserializer).map(Self)
+            }
+        }
+
+        impl Deref for $name {
+            type Target = Vec<$elem>;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl DerefMut for $name {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                &mut self.0
+            }
+        }
+
+        impl From<Vec<$elem>> for $name {
+            fn from(v: Vec<$elem>) -> Self {
+                Self(v)
+            }
+        }
+
+        impl From<$name> for Vec<$elem> {
+            fn from(v: $name) -> Self {
+                v.0
+            }
+        }
+
+        impl From<$name> for Value {
+            fn from(v: $name) -> Self {
+                Value::$name(v.0)
+            }
+        }
+    };
+}
+
+def_wrapper!(ByteArray, byte_array, i8, "byte array");
+def_wrapper!(IntArray, int_array, i32, "int array");
+def_wrapper!(LongArray, long_array, i64, "long array");
@@ -1,20 +1,33 @@
-use std::io::Read;
+use std::io::{BufRead, Read, Seek, SeekFrom};
 
+pub use lenient::from_reader_lenient;
+pub(crate) use raw::copy_payload;
+pub(crate) use root::read_string_into;
 pub use root::RootDeserializer as Deserializer;
 use serde::de::DeserializeOwned;
+use serde::Deserialize;
 
-use crate::Error;
+use crate::binary::de::payload::PayloadDeserializer;
+use crate::{Compound, Error, Tag, Value};
 
 mod array;
 mod compound;
+mod lenient;
 mod list;
 mod payload;
+mod raw;
 mod root;
 
 /// Reads uncompressed NBT binary data from the provided reader.
 ///
 /// The name of the root compound is discarded. If you need access to it, see
 /// [`Deserializer`].
+///
+/// Besides [`Value`], the data can be read into other self-describing types
+/// such as `serde_json::Value`. Compounds become maps, lists and arrays both
+/// become sequences, and numbers and strings keep their values. JSON has no
+/// representation for NaN or infinite floats, so `serde_json::Value` stores
+/// them as `null`.
 pub fn from_reader<R, T>(reader: R) -> Result<T, Error>
 where
     R: Read,
@@ -22,3 +35,235 @@ where
 {
     T::deserialize(&mut Deserializer::new(reader, false))
 }
+
+/// Reads uncompressed NBT binary data which starts `offset` bytes into the
+/// provided reader.
+///
+/// The reader is seeked to `offset` before reading, which is useful for NBT
+/// that is embedded in a container format after some header bytes. A reader
+/// which is already positioned at the start of the NBT data can be passed to
+/// [`from_reader`] directly. On success, the reader is left positioned just
+/// after the root compound.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Cursor;
+///
+/// use serde_nbt::binary::from_reader_at;
+/// use serde_nbt::Value;
+///
+/// // A four byte header followed by the NBT data.
+/// let file = [b'H', b'D', b'R', 0, 10, 0, 0, 3, 0, 1, b'a', 0, 0, 0, 7, 0];
+///
+/// let value: Value = from_reader_at(Cursor::new(file), 4).unwrap();
+///
+/// assert_eq!(value.as_compound().unwrap()["a"], Value::Int(7));
+/// ```
+pub fn from_reader_at<R, T>(mut reader: R, offset: u64) -> Result<T, Error>
+where
+    R: Read + Seek,
+    T: DeserializeOwned,
+{
+    reader.seek(SeekFrom::Start(offset))?;
+    from_reader(reader)
+}
+
+/// Reads uncompressed NBT binary data from the provided reader as a
+/// [`Compound`].
+///
+/// The root of an NBT document is always a compound, so this is equivalent to
+/// reading a [`Value`](crate::Value) and unwrapping the
+/// [`Value::Compound`](crate::Value::Compound) variant. The name of the root
+/// compound is discarded.
+///
+/// # Examples
+///
+/// ```
+/// use serde_nbt::binary::from_reader_compound;
+/// use serde_nbt::Value;
+///
+/// let some_bytes = [10, 0, 0, 3, 0, 3, 105, 110, 116, 0, 0, 222, 173, 0];
+///
+/// let compound = from_reader_compound(some_bytes.as_slice()).unwrap();
+///
+/// assert_eq!(compound["int"], Value::Int(0xdead));
+/// ```
+pub fn from_reader_compound<R>(reader: R) -> Result<Compound, Error>
+where
+    R: Read,
+{
+    from_reader(reader)
+}
+
+/// Returns the ID of the next tag in the reader without consuming it, or
+/// `None` if the reader is at the end of its data.
+///
+/// Peeking requires a [`BufRead`]. Readers which only implement [`Read`] can
+/// be wrapped in a [`BufReader`](std::io::BufReader) first.
+///
+/// # Examples
+///
+/// ```
+/// use serde_nbt::binary::peek_tag;
+///
+/// let mut reader = [10, 0, 0, 0].as_slice();
+///
+/// assert_eq!(peek_tag(&mut reader).unwrap(), Some(10));
+/// assert_eq!(reader.len(), 4);
+///
+/// assert_eq!(peek_tag(&mut [].as_slice()).unwrap(), None);
+/// ```
+pub fn peek_tag<R>(reader: &mut R) -> Result<Option<u8>, Error>
+where
+    R: BufRead + ?Sized,
+{
+    Ok(reader.fill_buf()?.first().copied())
+}
+
+/// Reads uncompressed NBT binary data which may be absent.
+///
+/// In some places, such as item slots in the network protocol, a missing
+/// compound is encoded as a lone `TAG_End` byte instead of a root compound.
+/// If the next tag is `TAG_End`, it is consumed and `None` is returned.
+/// Otherwise, this is the same as [`from_reader`].
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Read;
+///
+/// use serde_nbt::binary::from_reader_optional;
+/// use serde_nbt::Compound;
+///
+/// let mut reader = [0, 10, 0, 0, 0, 42].as_slice();
+///
+/// assert_eq!(from_reader_optional::<_, Compound>(&mut reader).unwrap(), None);
+/// assert_eq!(
+///     from_reader_optional::<_, Compound>(&mut reader).unwrap(),
+///     Some(Compound::new())
+/// );
+/// assert_eq!(reader, [42]);
+/// ```
+pub fn from_reader_optional<R, T>(mut reader: R) -> Result<Option<T>, Error>
+where
+    R: BufRead,
+    T: DeserializeOwned,
+{
+    match peek_tag(&mut reader)? {
+        Some(id) if id == Tag::End as u8 => {
+            reader.consume(1);
+            Ok(None)
+        }
+        _ => from_reader(reader).map(Some),
+    }
+}
+
+/// Reads the payload of a single tag with the given tag ID.
+///
+/// Only the payload is read. There is no tag ID or name in front of it, and
+/// the payload may be of any type, not just a compound. Together with
+/// [`write_tag_body`](crate::binary::write_tag_body), this can be used to read
+/// NBT in custom framings, such as the network format where the root compound
+/// has no name.
+///
+/// An error is returned if `tag_id` is not a valid tag ID or is the ID of
+/// `TAG_End`, which has no payload.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Read;
+///
+/// use serde_nbt::binary::read_tag_body;
+/// use serde_nbt::Value;
+///
+/// // A nameless int tag followed by a nameless string tag.
+/// let bytes = [3, 0, 0, 0, 42, 8, 0, 2, b'h', b'i'];
+/// let mut reader = bytes.as_slice();
+///
+/// let mut values = vec![];
+/// let mut tag_id = [0];
+///
+/// while reader.read(&mut tag_id).unwrap() != 0 {
+///     values.push(read_tag_body(&mut reader, tag_id[0]).unwrap());
+/// }
+///
+/// assert_eq!(values, [Value::Int(42), Value::String("hi".to_owned())]);
+/// ```
+pub fn read_tag_body<R>(reader: R, tag_id: u8) -> Result<Value, Error>
+where
+    R: Read,
+{
+    let tag = Tag::from_id(tag_id)?;
+
+    if tag == Tag::End {
+        return Err(Error::new_static("TAG_End has no payload"));
+    }
+
+    let mut de = Deserializer::new(reader, false);
+    Value::deserialize(PayloadDeserializer { de: &mut de, tag })
+}
+
+/// Reads uncompressed NBT binary data from the front of the provided slice.
+///
+/// On success, the deserialized value is returned along with the bytes of the
+/// slice that come after the NBT data. This is useful when NBT is embedded in
+/// a larger buffer and reading needs to continue after it.
+///
+/// # Examples
+///
+/// ```
+/// use serde_nbt::binary::from_slice_with_remainder;
+/// use serde_nbt::Value;
+///
+/// let some_bytes = [10, 0, 0, 3, 0, 3, 105, 110, 116, 0, 0, 222, 173, 0, 1, 2, 3];
+///
+/// let (_value, remainder) = from_slice_with_remainder::<Value>(&some_bytes).unwrap();
+///
+/// assert_eq!(remainder, [1, 2, 3]);
+/// ```
+pub fn from_slice_with_remainder<T>(slice: &[u8]) -> Result<(T, &[u8]), Error>
+where
+    T: DeserializeOwned,
+{
+    let mut de = Deserializer::new(slice, false);
+    let t = T::deserialize(&mut de)?;
+    Ok((t, de.reader))
+}
+
+/// Like [`from_reader`], but fails if there is any data left in the reader
+/// after the root compound.
+///
+/// This is useful for validating standalone NBT documents, where trailing
+/// bytes are a sign of corruption. Use [`from_reader`] if the NBT data is
+/// embedded in a larger stream.
+pub fn from_reader_exact<R, T>(reader: R) -> Result<T, Error>
+where
+    R: Read,
+    T: DeserializeOwned,
+{
+    let mut de = Deserializer::new(reader, false);
+    let t = T::deserialize(&mut de)?;
+
+    if de.reader.read(&mut [0])? != 0 {
+        return Err(Error::new_static("trailing bytes after root compound"));
+    }
+
+    Ok(t)
+}
+
+/// Like [`from_slice_with_remainder`], but fails if there are any bytes left
+/// in the slice after the root compound.
+pub fn from_slice_exact<T>(slice: &[u8]) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    match from_slice_with_remainder(slice)? {
+        (t, []) => Ok(t),
+        (_, remainder) => Err(Error::new_owned(format!(
+            "{} trailing byte(s) after root compound",
+            remainder.len()
+        ))),
+    }
+}
@@ -6,18 +6,19 @@ use serde::de::{DeserializeSeed, Error as _, SeqAccess, Unexpected, Visitor};
 use serde::{de, forward_to_deserialize_any, Deserializer};
 
 use crate::binary::de::payload::PayloadDeserializer;
+use crate::binary::de::root::RootDeserializer;
 use crate::{
     ArrayType, Error, BYTE_ARRAY_VARIANT_NAME, INT_ARRAY_VARIANT_NAME, LONG_ARRAY_VARIANT_NAME,
 };
 
-pub struct EnumAccess<'r, R: ?Sized> {
-    pub(super) reader: &'r mut R,
+pub struct EnumAccess<'r, 'a, R> {
+    pub(super) de: &'r mut RootDeserializer<'a, R>,
     pub(super) array_type: ArrayType,
 }
 
-impl<'de: 'r, 'r, R: Read + ?Sized> de::EnumAccess<'de> for EnumAccess<'r, R> {
+impl<'de: 'r, 'r, 'a, R: Read> de::EnumAccess<'de> for EnumAccess<'r, 'a, R> {
     type Error = Error;
-    type Variant = VariantAccess<'r, R>;
+    type Variant = VariantAccess<'r, 'a, R>;
 
     fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
     where
@@ -32,19 +33,19 @@ impl<'de: 'r, 'r, R: Read + ?Sized> de::EnumAccess<'de> for EnumAccess<'r, R> {
         Ok((
             seed.deserialize(StrDeserializer::<Error>::new(variant_name))?,
             VariantAccess {
-                reader: self.reader,
+                de: self.de,
                 array_type: self.array_type,
             },
         ))
     }
 }
 
-pub struct VariantAccess<'r, R: ?Sized> {
-    reader: &'r mut R,
+pub struct VariantAccess<'r, 'a, R> {
+    de: &'r mut RootDeserializer<'a, R>,
     array_type: ArrayType,
 }
 
-impl<'de: 'r, 'r, R: Read + ?Sized> de::VariantAccess<'de> for VariantAccess<'r, R> {
+impl<'de: 'r, 'r, 'a, R: Read> de::VariantAccess<'de> for VariantAccess<'r, 'a, R> {
     type Error = Error;
 
     fn unit_variant(self) -> Result<(), Self::Error> {
@@ -59,7 +60,7 @@ impl<'de: 'r, 'r, R: Read + ?Sized> de::VariantAccess<'de> for VariantAccess<'r,
         T: DeserializeSeed<'de>,
     {
         seed.deserialize(ArrayDeserializer {
-            reader: self.reader,
+            de: self.de,
             array_type: self.array_type,
         })
     }
@@ -89,12 +90,12 @@ impl<'de: 'r, 'r, R: Read + ?Sized> de::VariantAccess<'de> for VariantAccess<'r,
     }
 }
 
-struct ArrayDeserializer<'r, R: ?Sized> {
-    reader: &'r mut R,
-    array_type: ArrayType,
+pub(super) struct ArrayDeserializer<'r, 'a, R> {
+    pub(super) de: &'r mut RootDeserializer<'a, R>,
+    pub(super) array_type: ArrayType,
 }
 
-impl<'de: 'r, 'r, R: Read + ?Sized> Deserializer<'de> for ArrayDeserializer<'r, R> {
+impl<'de: 'r, 'r, 'a, R: Read> Deserializer<'de> for ArrayDeserializer<'r, 'a, R> {
     type Error = Error;
 
     forward_to_deserialize_any! {
@@ -107,16 +108,19 @@ impl<'de: 'r, 'r, R: Read + ?Sized> Deserializer<'de> for ArrayDeserializer<'r,
     where
         V: Visitor<'de>,
     {
-        let len = self.reader.read_i32::<BigEndian>()?;
+        let len = self.de.reader.read_i32::<BigEndian>()?;
 
         if len < 0 {
             return Err(Error::new_static("array with negative length"));
         }
 
+        self.de.count_elements(len as u64)?;
+
         visitor.visit_seq(ArraySeqAccess {
-            reader: self.reader,
+            de: self.de,
             array_type: self.array_type,
             remaining: len,
+            index: 0,
         })
     }
 
@@ -125,13 +129,14 @@ impl<'de: 'r, 'r, R: Read + ?Sized> Deserializer<'de> for ArrayDeserializer<'r,
     }
 }
 
-struct ArraySeqAccess<'r, R: ?Sized> {
-    reader: &'r mut R,
+struct ArraySeqAccess<'r, 'a, R> {
+    de: &'r mut RootDeserializer<'a, R>,
     array_type: ArrayType,
     remaining: i32,
+    index: i32,
 }
 
-impl<'de: 'r, 'r, R: Read + ?Sized> SeqAccess<'de> for ArraySeqAccess<'r, R> {
+impl<'de: 'r, 'r, 'a, R: Read> SeqAccess<'de> for ArraySeqAccess<'r, 'a, R> {
     type Error = Error;
 
     fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
@@ -140,12 +145,15 @@ impl<'de: 'r, 'r, R: Read + ?Sized> SeqAccess<'de> for ArraySeqAccess<'r, R> {
     {
         if self.remaining > 0 {
             self.remaining -= 1;
+            let index = self.index;
+            self.index += 1;
 
             seed.deserialize(PayloadDeserializer {
-                reader: self.reader,
+                de: self.de,
                 tag: self.array_type.element_tag(),
             })
             .map(Some)
+            .map_err(|e| e.index(index as usize))
         } else {
             Ok(None)
         }
@@ -1,51 +1,51 @@
 use std::io::Read;
 
-use byteorder::ReadBytesExt;
 use serde::de;
+use serde::de::value::StrDeserializer;
 use serde::de::DeserializeSeed;
 
 use crate::binary::de::payload::PayloadDeserializer;
+use crate::binary::de::root::{read_string_into, RootDeserializer};
 use crate::{Error, Tag};
 
-pub struct MapAccess<'r, R: ?Sized> {
-    reader: &'r mut R,
+pub struct MapAccess<'r, 'a, R> {
+    de: &'r mut RootDeserializer<'a, R>,
     value_tag: Tag,
-    /// Provides error context when deserializing structs.
-    fields: &'static [&'static str],
+    /// The most recently read key. Provides error context.
+    key: String,
 }
 
-impl<'r, R: Read + ?Sized> MapAccess<'r, R> {
-    pub fn new(reader: &'r mut R, fields: &'static [&'static str]) -> Self {
+impl<'r, 'a, R: Read> MapAccess<'r, 'a, R> {
+    pub fn new(de: &'r mut RootDeserializer<'a, R>) -> Self {
         Self {
-            reader,
+            de,
             value_tag: Tag::End,
-            fields,
+            key: String::new(),
         }
     }
 }
 
-impl<'de: 'r, 'r, R: Read + ?Sized> de::MapAccess<'de> for MapAccess<'r, R> {
+impl<'de: 'r, 'r, 'a, R: Read> de::MapAccess<'de> for MapAccess<'r, 'a, R> {
     type Error = Error;
 
     fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
     where
         K: DeserializeSeed<'de>,
     {
-        self.value_tag = Tag::from_u8(self.reader.read_u8()?)?;
+        self.value_tag = self.de.read_entry_tag(&mut self.key)?;
 
         if self.value_tag == Tag::End {
             return Ok(None);
         }
 
-        seed.deserialize(PayloadDeserializer {
-            reader: self.reader,
-            tag: Tag::String,
-        })
-        .map(Some)
-        .map_err(|e| match self.fields {
-            [f, ..] => e.field(*f),
-            [] => e,
-        })
+        read_string_into(&mut self.de.reader, &mut self.key)?;
+        self.de
+            .check_key(&self.key)
+            .map_err(|e| e.field(self.key.clone()))?;
+
+        seed.deserialize(StrDeserializer::<Error>::new(&self.key))
+            .map(Some)
+            .map_err(|e| e.field(self.key.clone()))
     }
 
     fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
@@ -56,21 +56,16 @@ impl<'de: 'r, 'r, R: Read + ?Sized> de::MapAccess<'de> for MapAccess<'r, R> {
             return Err(Error::new_static("end of compound?"));
         }
 
-        let field = match self.fields {
-            [field, rest @ ..] => {
-                self.fields = rest;
-                Some(*field)
-            }
-            [] => None,
-        };
+        let path_len = self.de.enter_field(&self.key, self.value_tag);
+
+        let value = seed
+            .deserialize(PayloadDeserializer {
+                de: self.de,
+                tag: self.value_tag,
+            })
+            .map_err(|e| e.field(self.key.clone()));
 
-        seed.deserialize(PayloadDeserializer {
-            reader: self.reader,
-            tag: self.value_tag,
-        })
-        .map_err(|e| match field {
-            Some(f) => e.field(f),
-            None => e,
-        })
+        self.de.leave(path_len);
+        value
     }
 }
@@ -0,0 +1,251 @@
+use std::io;
+use std::io::Read;
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::binary::de::list::truncated_list;
+use crate::binary::de::root::{read_string_into, RootDeserializer};
+use crate::{Compound, Error, List, Tag, Value};
+
+/// Reads uncompressed NBT binary data from the provided reader, keeping as
+/// much of it as possible if the data is malformed.
+///
+/// Instead of discarding everything when an error is encountered, the value
+/// read so far is returned alongside the error. Compounds and lists which
+/// were cut short by the error contain the entries and elements read before
+/// it, while the tag the error occurred in is left out. This is intended for
+/// tools which salvage what they can from damaged files.
+///
+/// The partial value is best-effort. It is a valid [`Value`], but it may be
+/// missing arbitrary parts of the original data and should not be written
+/// back in place of it. If the root tag itself is malformed, the value is an
+/// empty compound. The name of the root compound is discarded.
+///
+/// To set limits or other options, use
+/// [`Deserializer::deserialize_lenient`](crate::binary::Deserializer::deserialize_lenient)
+/// instead.
+///
+/// # Examples
+///
+/// ```
+/// use serde_nbt::binary::from_reader_lenient;
+/// use serde_nbt::{Compound, Value};
+///
+/// // A compound with an int "a" followed by a truncated int "b".
+/// let bytes = [10, 0, 0, 3, 0, 1, b'a', 0, 0, 0, 7, 3, 0, 1, b'b', 0, 0];
+///
+/// let (value, err) = from_reader_lenient(bytes.as_slice());
+///
+/// assert_eq!(
+///     value,
+///     Value::Compound(Compound::from_iter([("a".to_owned(), Value::Int(7))]))
+/// );
+/// assert_eq!(err.unwrap().path(), "b");
+/// ```
+pub fn from_reader_lenient<R>(reader: R) -> (Value, Option<Error>)
+where
+    R: Read,
+{
+    RootDeserializer::new(reader, false).deserialize_lenient()
+}
+
+impl<'a, R: Read> RootDeserializer<'a, R> {
+    /// Reads a document like [`from_reader_lenient`], but with the limits and
+    /// options of this deserializer.
+    ///
+    /// Exceeding a limit or failing a check ends reading just like malformed
+    /// data does, so the value read up to that point is still returned. The
+    /// root name is saved if [`Self::save_root_name`] is set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::binary::Deserializer;
+    /// use serde_nbt::{Compound, List, Value};
+    ///
+    /// // A compound with a list of three bytes named "a".
+    /// let bytes = [10, 0, 0, 9, 0, 1, b'a', 1, 0, 0, 0, 3, 1, 2, 3, 0];
+    ///
+    /// let mut de = Deserializer::new(bytes.as_slice(), false);
+    /// de.set_max_total_elements(3);
+    ///
+    /// let (value, err) = de.deserialize_lenient();
+    ///
+    /// assert_eq!(
+    ///     value,
+    ///     Value::Compound(Compound::from_iter([(
+    ///         "a".to_owned(),
+    ///         List::Byte(vec![]).into()
+    ///     )]))
+    /// );
+    /// assert_eq!(err.unwrap().path(), "a");
+    /// ```
+    pub fn deserialize_lenient(&mut self) -> (Value, Option<Error>) {
+        let mut compound = Compound::new();
+
+        let res = self
+            .read_name()
+            .and_then(|_| self.nested(|de| de.lenient_compound(&mut compound)));
+
+        (Value::Compound(compound), res.err())
+    }
+
+    /// Reads the payload of a tag. The value is `None` if nothing of it could
+    /// be read.
+    fn lenient_payload(&mut self, tag: Tag) -> (Option<Value>, Result<(), Error>) {
+        let value = match tag {
+            Tag::Compound => {
+                let mut compound = Compound::new();
+                let res = self.nested(|de| de.lenient_compound(&mut compound));
+                return (Some(compound.into()), res);
+            }
+            Tag::List => {
+                let mut list = List::default();
+                let res = self.nested(|de| de.lenient_list(&mut list));
+                return (Some(list.into()), res);
+            }
+            Tag::End => Err(Error::new_static("unexpected TAG_End")),
+            Tag::Byte => self.reader.read_i8().map(Value::Byte).map_err(Error::from),
+            Tag::Short => self
+                .reader
+                .read_i16::<BigEndian>()
+                .map(Value::Short)
+                .map_err(Error::from),
+            Tag::Int => self
+                .reader
+                .read_i32::<BigEndian>()
+                .map(Value::Int)
+                .map_err(Error::from),
+            Tag::Long => self
+                .reader
+                .read_i64::<BigEndian>()
+                .map(Value::Long)
+                .map_err(Error::from),
+            Tag::Float => self
+                .reader
+                .read_f32::<BigEndian>()
+                .map(Value::Float)
+                .map_err(Error::from),
+            Tag::Double => self
+                .reader
+                .read_f64::<BigEndian>()
+                .map(Value::Double)
+                .map_err(Error::from),
+            Tag::ByteArray => self.lenient_array(|r| r.read_i8()).map(Value::ByteArray),
+            Tag::String => {
+                let mut string = String::new();
+                read_string_into(&mut self.reader, &mut string).map(|()| Value::String(string))
+            }
+            Tag::IntArray => self
+                .lenient_array(|r| r.read_i32::<BigEndian>())
+                .map(Value::IntArray),
+            Tag::LongArray => self
+                .lenient_array(|r| r.read_i64::<BigEndian>())
+                .map(Value::LongArray),
+        };
+
+        match value {
+            Ok(value) => (Some(value), Ok(())),
+            Err(e) => (None, Err(e)),
+        }
+    }
+
+    fn lenient_compound(&mut self, out: &mut Compound) -> Result<(), Error> {
+        let mut name = String::new();
+
+        loop {
+            let tag = self.read_entry_tag(&mut name)?;
+
+            if tag == Tag::End {
+                return Ok(());
+            }
+
+            read_string_into(&mut self.reader, &mut name)?;
+            self.check_key(&name).map_err(|e| e.field(name.clone()))?;
+
+            let path_len = self.enter_field(&name, tag);
+            let (value, res) = self.lenient_payload(tag);
+            self.leave(path_len);
+
+            if let Some(value) = value {
+                out.insert(name.clone(), value);
+            }
+
+            res.map_err(|e| e.field(name.clone()))?;
+        }
+    }
+
+    fn lenient_list(&mut self, out: &mut List) -> Result<(), Error> {
+        let (element_tag, len) = self.read_list_header()?;
+
+        // Empty lists keep their element type.
+        *out = empty_list(element_tag);
+
+        for i in 0..len as u32 {
+            let path_len = self.enter_element(i);
+            let (value, res) = self.lenient_payload(element_tag);
+            self.leave(path_len);
+
+            if let Some(value) = value {
+                out.push(value)
+                    .expect("elements of a list have the same tag");
+            }
+
+            res.map_err(|e| {
+                let e = if e.is_eof() {
+                    e.replace_cause(truncated_list(len as u32, i))
+                } else {
+                    e
+                };
+
+                e.index(i as usize)
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads the elements of an array. Nothing is returned if the array is
+    /// incomplete.
+    fn lenient_array<T>(
+        &mut self,
+        mut read: impl FnMut(&mut R) -> io::Result<T>,
+    ) -> Result<Vec<T>, Error> {
+        let len = self.reader.read_i32::<BigEndian>()?;
+
+        if len < 0 {
+            return Err(Error::new_static("array with negative length"));
+        }
+
+        self.count_elements(len as u64)?;
+
+        // Don't trust the length enough to allocate it all up front.
+        let mut out = Vec::new();
+
+        for _ in 0..len {
+            out.push(read(&mut self.reader)?);
+        }
+
+        Ok(out)
+    }
+}
+
+/// Returns an empty list with the element type `tag`. For `TAG_End`, this is
+/// the default list.
+fn empty_list(tag: Tag) -> List {
+    match tag {
+        Tag::End => List::default(),
+        Tag::Byte => List::Byte(vec![]),
+        Tag::Short => List::Short(vec![]),
+        Tag::Int => List::Int(vec![]),
+        Tag::Long => List::Long(vec![]),
+        Tag::Float => List::Float(vec![]),
+        Tag::Double => List::Double(vec![]),
+        Tag::ByteArray => List::ByteArray(vec![]),
+        Tag::String => List::String(vec![]),
+        Tag::List => List::List(vec![]),
+        Tag::Compound => List::Compound(vec![]),
+        Tag::IntArray => List::IntArray(vec![]),
+        Tag::LongArray => List::LongArray(vec![]),
+    }
+}
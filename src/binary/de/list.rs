@@ -4,15 +4,18 @@ use serde::de;
 use serde::de::DeserializeSeed;
 
 use crate::binary::de::payload::PayloadDeserializer;
+use crate::binary::de::root::RootDeserializer;
 use crate::{Error, Tag};
 
-pub(super) struct SeqAccess<'r, R: ?Sized> {
-    pub reader: &'r mut R,
+pub(super) struct SeqAccess<'r, 'a, R> {
+    pub de: &'r mut RootDeserializer<'a, R>,
     pub element_tag: Tag,
     pub remaining: u32,
+    /// The index of the next element. Provides error context.
+    pub index: u32,
 }
 
-impl<'de: 'r, 'r, R: Read + ?Sized> de::SeqAccess<'de> for SeqAccess<'r, R> {
+impl<'de: 'r, 'r, 'a, R: Read> de::SeqAccess<'de> for SeqAccess<'r, 'a, R> {
     type Error = Error;
 
     fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
@@ -20,13 +23,31 @@ impl<'de: 'r, 'r, R: Read + ?Sized> de::SeqAccess<'de> for SeqAccess<'r, R> {
         T: DeserializeSeed<'de>,
     {
         if self.remaining > 0 {
+            let len = self.index + self.remaining;
             self.remaining -= 1;
+            let index = self.index;
+            self.index += 1;
 
-            seed.deserialize(PayloadDeserializer {
-                reader: self.reader,
-                tag: self.element_tag,
-            })
-            .map(Some)
+            let path_len = self.de.enter_element(index);
+
+            let element = seed
+                .deserialize(PayloadDeserializer {
+                    de: self.de,
+                    tag: self.element_tag,
+                })
+                .map(Some)
+                .map_err(|e| {
+                    let e = if e.is_eof() {
+                        e.replace_cause(truncated_list(len, index))
+                    } else {
+                        e
+                    };
+
+                    e.index(index as usize)
+                });
+
+            self.de.leave(path_len);
+            element
         } else {
             Ok(None)
         }
@@ -36,3 +57,14 @@ impl<'de: 'r, 'r, R: Read + ?Sized> de::SeqAccess<'de> for SeqAccess<'r, R> {
         Some(self.remaining as usize)
     }
 }
+
+/// The error for a list whose data ends before all of its elements. `read` is
+/// the number of complete elements.
+///
+/// Use it with [`Error::replace_cause`] so the location of the truncated
+/// element is kept.
+pub(crate) fn truncated_list(len: u32, read: u32) -> Error {
+    Error::new_owned(format!(
+        "list declared {len} element(s) but the data ended after {read}"
+    ))
+}
@@ -3,50 +3,46 @@ use std::io::Read;
 
 use byteorder::{BigEndian, ReadBytesExt};
 use cesu8::from_java_cesu8;
-use serde::de::Visitor;
-use serde::{de, forward_to_deserialize_any};
+use serde::de::{IntoDeserializer, Visitor};
+use serde::{de, forward_to_deserialize_any, Deserializer};
 use smallvec::SmallVec;
 
-use crate::binary::de::array::EnumAccess;
+use crate::binary::de::array::{ArrayDeserializer, EnumAccess};
 use crate::binary::de::compound::MapAccess;
 use crate::binary::de::list::SeqAccess;
-use crate::{ArrayType, Error, Tag, CESU8_DECODE_ERROR};
+use crate::binary::de::raw::RawCompoundAccess;
+use crate::binary::de::root::{read_string_into, RootDeserializer};
+use crate::{ArrayType, Error, Tag, CESU8_DECODE_ERROR, RAW_COMPOUND_NAME, VALUE_NAME};
 
-pub(super) struct PayloadDeserializer<'w, R: ?Sized> {
-    pub reader: &'w mut R,
+pub(super) struct PayloadDeserializer<'w, 'a, R> {
+    pub de: &'w mut RootDeserializer<'a, R>,
     /// The type of payload to be deserialized.
     pub tag: Tag,
 }
 
-impl<'de: 'w, 'w, R: Read + ?Sized> de::Deserializer<'de> for PayloadDeserializer<'w, R> {
-    type Error = Error;
-
-    forward_to_deserialize_any! {
-        i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
-        bytes byte_buf unit unit_struct newtype_struct seq tuple
-        tuple_struct map enum identifier ignored_any
-    }
-
-    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+impl<'de: 'w, 'w, 'a, R: Read> PayloadDeserializer<'w, 'a, R> {
+    /// Deserializes the payload like `deserialize_any`. Arrays are presented
+    /// to the visitor as sequences, unless `arrays_as_enums` is set. Then,
+    /// they are presented as enums whose variant identifies the array type,
+    /// which is how [`Value`](crate::Value) and the array modules tell arrays
+    /// apart from lists.
+    fn deserialize_payload<V>(self, visitor: V, arrays_as_enums: bool) -> Result<V::Value, Error>
     where
         V: Visitor<'de>,
     {
         match self.tag {
             Tag::End => unreachable!("invalid payload tag"),
-            Tag::Byte => visitor.visit_i8(self.reader.read_i8()?),
-            Tag::Short => visitor.visit_i16(self.reader.read_i16::<BigEndian>()?),
-            Tag::Int => visitor.visit_i32(self.reader.read_i32::<BigEndian>()?),
-            Tag::Long => visitor.visit_i64(self.reader.read_i64::<BigEndian>()?),
-            Tag::Float => visitor.visit_f32(self.reader.read_f32::<BigEndian>()?),
-            Tag::Double => visitor.visit_f64(self.reader.read_f64::<BigEndian>()?),
-            Tag::ByteArray => visitor.visit_enum(EnumAccess {
-                reader: self.reader,
-                array_type: ArrayType::Byte,
-            }),
+            Tag::Byte => visitor.visit_i8(self.de.reader.read_i8()?),
+            Tag::Short => visitor.visit_i16(self.de.reader.read_i16::<BigEndian>()?),
+            Tag::Int => visitor.visit_i32(self.de.reader.read_i32::<BigEndian>()?),
+            Tag::Long => visitor.visit_i64(self.de.reader.read_i64::<BigEndian>()?),
+            Tag::Float => visitor.visit_f32(self.de.reader.read_f32::<BigEndian>()?),
+            Tag::Double => visitor.visit_f64(self.de.reader.read_f64::<BigEndian>()?),
+            Tag::ByteArray => self.deserialize_array(visitor, ArrayType::Byte, arrays_as_enums),
             Tag::String => {
                 let mut buf = SmallVec::<[u8; 128]>::new();
-                for _ in 0..self.reader.read_u16::<BigEndian>()? {
-                    buf.push(self.reader.read_u8()?);
+                for _ in 0..self.de.reader.read_u16::<BigEndian>()? {
+                    buf.push(self.de.reader.read_u8()?);
                 }
 
                 match from_java_cesu8(&buf).map_err(|_| Error::new_static(CESU8_DECODE_ERROR))? {
@@ -54,48 +50,132 @@ impl<'de: 'w, 'w, R: Read + ?Sized> de::Deserializer<'de> for PayloadDeserialize
                     Cow::Owned(string) => visitor.visit_string(string),
                 }
             }
-            Tag::List => {
-                let element_tag = Tag::from_u8(self.reader.read_u8()?)?;
-                let len = self.reader.read_i32::<BigEndian>()?;
-
-                if len < 0 {
-                    return Err(Error::new_static("list with negative length"));
-                }
-
-                if element_tag == Tag::End && len != 0 {
-                    return Err(Error::new_static(
-                        "list with TAG_End element type must have length zero",
-                    ));
-                }
+            Tag::List => self.de.nested(|de| {
+                let (element_tag, len) = de.read_list_header()?;
 
                 visitor.visit_seq(SeqAccess {
-                    reader: self.reader,
+                    de,
                     element_tag,
                     remaining: len as u32,
+                    index: 0,
                 })
-            }
-            Tag::Compound => visitor.visit_map(MapAccess::new(self.reader, &[])),
-            Tag::IntArray => visitor.visit_enum(EnumAccess {
-                reader: self.reader,
-                array_type: ArrayType::Int,
-            }),
-            Tag::LongArray => visitor.visit_enum(EnumAccess {
-                reader: self.reader,
-                array_type: ArrayType::Long,
             }),
+            Tag::Compound => self.de.nested(|de| visitor.visit_map(MapAccess::new(de))),
+            Tag::IntArray => self.deserialize_array(visitor, ArrayType::Int, arrays_as_enums),
+            Tag::LongArray => self.deserialize_array(visitor, ArrayType::Long, arrays_as_enums),
+        }
+    }
+
+    fn deserialize_array<V>(
+        self,
+        visitor: V,
+        array_type: ArrayType,
+        as_enum: bool,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        if as_enum {
+            visitor.visit_enum(EnumAccess {
+                de: self.de,
+                array_type,
+            })
+        } else {
+            ArrayDeserializer {
+                de: self.de,
+                array_type,
+            }
+            .deserialize_any(visitor)
         }
     }
+}
+
+impl<'de: 'w, 'w, 'a, R: Read> de::Deserializer<'de> for PayloadDeserializer<'w, 'a, R> {
+    type Error = Error;
+
+    forward_to_deserialize_any! {
+        i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_payload(visitor, false)
+    }
 
     fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        if self.tag == Tag::Byte {
-            match self.reader.read_i8()? {
+        match self.tag {
+            Tag::Byte => match self.de.reader.read_i8()? {
                 0 => visitor.visit_bool(false),
                 1 => visitor.visit_bool(true),
                 n => visitor.visit_i8(n),
+            },
+            // Written by serializers configured with `BoolTag::Int`.
+            Tag::Int => match self.de.reader.read_i32::<BigEndian>()? {
+                0 => visitor.visit_bool(false),
+                1 => visitor.visit_bool(true),
+                n => visitor.visit_i32(n),
+            },
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if name == RAW_COMPOUND_NAME && self.tag == Tag::Compound {
+            let mut payload = Vec::new();
+            self.de.copy_payload(Tag::Compound, &mut payload)?;
+            visitor.visit_map(RawCompoundAccess::new(payload))
+        } else if name == VALUE_NAME {
+            self.deserialize_payload(visitor, true)
+        } else {
+            visitor.visit_newtype_struct(self)
+        }
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if self.tag == Tag::ByteArray {
+            let len = self.de.reader.read_i32::<BigEndian>()?;
+
+            if len < 0 {
+                return Err(Error::new_static("array with negative length"));
+            }
+
+            self.de.count_elements(len as u64)?;
+
+            // Don't trust the length enough to allocate it all up front.
+            let mut buf = Vec::new();
+            (&mut self.de.reader)
+                .take(len as u64)
+                .read_to_end(&mut buf)?;
+
+            if buf.len() != len as usize {
+                return Err(Error::new_static("unexpected end of byte array"));
             }
+
+            visitor.visit_byte_buf(buf)
         } else {
             self.deserialize_any(visitor)
         }
@@ -105,22 +185,28 @@ impl<'de: 'w, 'w, R: Read + ?Sized> de::Deserializer<'de> for PayloadDeserialize
     where
         V: Visitor<'de>,
     {
+        // NBT has no null tag, so a value that is present is always `Some`.
+        // Absent compound entries are handled by serde as missing fields,
+        // which become `None`.
         visitor.visit_some(self)
     }
 
-    fn deserialize_struct<V>(
+    fn deserialize_enum<V>(
         self,
         _name: &'static str,
-        fields: &'static [&'static str],
+        _variants: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        if self.tag == Tag::Compound {
-            visitor.visit_map(MapAccess::new(self.reader, fields))
+        if self.tag == Tag::String {
+            // Unit variants are encoded as strings.
+            let mut variant = String::new();
+            read_string_into(&mut self.de.reader, &mut variant)?;
+            visitor.visit_enum(variant.into_deserializer())
         } else {
-            self.deserialize_any(visitor)
+            self.deserialize_payload(visitor, true)
         }
     }
 
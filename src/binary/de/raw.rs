@@ -0,0 +1,239 @@
+use std::io::Read;
+
+use byteorder::{BigEndian, ReadBytesExt};
+use cesu8::from_java_cesu8;
+use serde::de::value::StrDeserializer;
+use serde::de::{DeserializeSeed, Visitor};
+use serde::{de, forward_to_deserialize_any};
+
+use crate::binary::de::root::RootDeserializer;
+use crate::{check_depth, Error, Tag, CESU8_DECODE_ERROR, RAW_COMPOUND_NAME};
+
+/// Reads the payload of a tag without decoding it, appending its bytes to
+/// `out`.
+///
+/// The structure of the payload is checked, but strings are not decoded.
+pub(crate) fn copy_payload<R: Read + ?Sized>(
+    reader: &mut R,
+    tag: Tag,
+    out: &mut Vec<u8>,
+    depth: usize,
+) -> Result<(), Error> {
+    match tag {
+        Tag::End => return Err(Error::new_static("unexpected TAG_End")),
+        Tag::Byte => copy(reader, 1, out)?,
+        Tag::Short => copy(reader, 2, out)?,
+        Tag::Int | Tag::Float => copy(reader, 4, out)?,
+        Tag::Long | Tag::Double => copy(reader, 8, out)?,
+        Tag::ByteArray => {
+            let len = copy_len(reader, out, "array")?;
+            copy(reader, len, out)?;
+        }
+        Tag::String => {
+            let len = reader.read_u16::<BigEndian>()?;
+            out.extend(len.to_be_bytes());
+            copy(reader, len.into(), out)?;
+        }
+        Tag::List => {
+            check_depth(depth + 1)?;
+
+            let element_tag = Tag::from_id(reader.read_u8()?)?;
+            out.push(element_tag as u8);
+            let len = copy_len(reader, out, "list")?;
+
+            if element_tag == Tag::End && len != 0 {
+                return Err(Error::new_static(
+                    "list with TAG_End element type must have length zero",
+                ));
+            }
+
+            for i in 0..len {
+                copy_payload(reader, element_tag, out, depth + 1)
+                    .map_err(|e| e.index(i as usize))?;
+            }
+        }
+        Tag::Compound => {
+            check_depth(depth + 1)?;
+
+            loop {
+                let tag = Tag::from_id(reader.read_u8()?)?;
+                out.push(tag as u8);
+
+                if tag == Tag::End {
+                    break;
+                }
+
+                let name_len = reader.read_u16::<BigEndian>()?;
+                out.extend(name_len.to_be_bytes());
+                copy(reader, name_len.into(), out)?;
+
+                copy_payload(reader, tag, out, depth + 1)?;
+            }
+        }
+        Tag::IntArray => {
+            let len = copy_len(reader, out, "array")?;
+            copy(reader, len * 4, out)?;
+        }
+        Tag::LongArray => {
+            let len = copy_len(reader, out, "array")?;
+            copy(reader, len * 8, out)?;
+        }
+    }
+
+    Ok(())
+}
+
+impl<'a, R: Read> RootDeserializer<'a, R> {
+    /// Like [`copy_payload`], but the limits and options of the deserializer
+    /// apply just as they do when deserializing the payload.
+    ///
+    /// Entries and list elements with an unknown tag are skipped rather than
+    /// copied, so the result is always a payload that [`copy_payload`]
+    /// accepts.
+    pub(super) fn copy_payload(&mut self, tag: Tag, out: &mut Vec<u8>) -> Result<(), Error> {
+        match tag {
+            Tag::ByteArray | Tag::IntArray | Tag::LongArray => {
+                let len = copy_len(&mut self.reader, out, "array")?;
+                self.count_elements(len)?;
+
+                let size = match tag {
+                    Tag::ByteArray => 1,
+                    Tag::IntArray => 4,
+                    _ => 8,
+                };
+
+                copy(&mut self.reader, len * size, out)
+            }
+            Tag::List => self.nested(|de| {
+                let (element_tag, len) = de.read_list_header()?;
+                out.push(element_tag as u8);
+                out.extend(len.to_be_bytes());
+
+                for i in 0..len as u32 {
+                    let path_len = de.enter_element(i);
+                    let res = de.copy_payload(element_tag, out);
+                    de.leave(path_len);
+                    res.map_err(|e| e.index(i as usize))?;
+                }
+
+                Ok(())
+            }),
+            Tag::Compound => self.nested(|de| {
+                let mut key = String::new();
+
+                loop {
+                    let tag = de.read_entry_tag(&mut key)?;
+                    out.push(tag as u8);
+
+                    if tag == Tag::End {
+                        return Ok(());
+                    }
+
+                    // The key is copied as is, but decoded for the checks.
+                    let start = out.len() + 2;
+                    copy_payload(&mut de.reader, Tag::String, out, 0)?;
+                    key = from_java_cesu8(&out[start..])
+                        .map_err(|_| Error::new_static(CESU8_DECODE_ERROR))?
+                        .into_owned();
+
+                    de.check_key(&key).map_err(|e| e.field(key.clone()))?;
+
+                    let path_len = de.enter_field(&key, tag);
+                    let res = de.copy_payload(tag, out);
+                    de.leave(path_len);
+                    res.map_err(|e| e.field(key.clone()))?;
+                }
+            }),
+            _ => copy_payload(&mut self.reader, tag, out, 0),
+        }
+    }
+}
+
+/// Presents the payload copied by [`RootDeserializer::copy_payload`] to the
+/// visitor of a [`RawCompound`](crate::RawCompound) as a map with a single
+/// entry. The key is [`RAW_COMPOUND_NAME`], which tells the visitor that the
+/// payload has already been checked.
+pub(super) struct RawCompoundAccess {
+    payload: Option<Vec<u8>>,
+}
+
+impl RawCompoundAccess {
+    pub fn new(payload: Vec<u8>) -> Self {
+        Self {
+            payload: Some(payload),
+        }
+    }
+}
+
+impl<'de> de::MapAccess<'de> for RawCompoundAccess {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if self.payload.is_none() {
+            return Ok(None);
+        }
+
+        seed.deserialize(StrDeserializer::<Error>::new(RAW_COMPOUND_NAME))
+            .map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        match self.payload.take() {
+            Some(payload) => seed.deserialize(ByteBufDeserializer(payload)),
+            None => Err(Error::new_static("end of raw compound?")),
+        }
+    }
+}
+
+struct ByteBufDeserializer(Vec<u8>);
+
+impl<'de> de::Deserializer<'de> for ByteBufDeserializer {
+    type Error = Error;
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_byte_buf(self.0)
+    }
+}
+
+/// Copies a list or array length, returning it.
+///
+/// The length is at most `i32::MAX`, so multiplying it by an element size
+/// cannot overflow a `u64`.
+fn copy_len<R: Read + ?Sized>(
+    reader: &mut R,
+    out: &mut Vec<u8>,
+    what: &'static str,
+) -> Result<u64, Error> {
+    let len = reader.read_i32::<BigEndian>()?;
+
+    if len < 0 {
+        return Err(Error::new_owned(format!("{what} with negative length")));
+    }
+
+    out.extend(len.to_be_bytes());
+    Ok(len as u64)
+}
+
+fn copy<R: Read + ?Sized>(reader: &mut R, n: u64, out: &mut Vec<u8>) -> Result<(), Error> {
+    // Don't trust the length enough to allocate it all up front.
+    if Read::take(&mut *reader, n).read_to_end(out)? as u64 != n {
+        return Err(Error::new_static("unexpected end of NBT data"));
+    }
+
+    Ok(())
+}
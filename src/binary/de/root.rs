@@ -1,4 +1,5 @@
 use std::borrow::Cow;
+use std::fmt::Write;
 use std::io::Read;
 
 use byteorder::{BigEndian, ReadBytesExt};
@@ -8,11 +9,11 @@ use serde::{forward_to_deserialize_any, Deserializer};
 use smallvec::SmallVec;
 
 use crate::binary::de::payload::PayloadDeserializer;
-use crate::{Error, Tag, CESU8_DECODE_ERROR};
+use crate::{check_depth_limit, Error, Tag, TagType, CESU8_DECODE_ERROR, MAX_DEPTH};
 
 /// A serde [`Deserializer`] for the binary representation of NBT.
 #[non_exhaustive]
-pub struct RootDeserializer<R> {
+pub struct RootDeserializer<'a, R> {
     /// The reader to deserialize from.
     pub reader: R,
     /// The name of the root compound that was deserialized. If
@@ -22,9 +23,33 @@ pub struct RootDeserializer<R> {
     /// Whether or not the root name should be saved to [`Self::root_name`]
     /// during deserialization.
     pub save_root_name: bool,
+    /// The maximum number of list elements, array elements, and compound
+    /// entries allowed in a single document.
+    max_total_elements: u64,
+    /// The number of elements encountered so far in the current document.
+    total_elements: u64,
+    /// The maximum nesting depth of compounds and lists.
+    max_depth: usize,
+    /// The nesting depth of the value being deserialized.
+    depth: usize,
+    /// Whether compound keys with control characters are rejected.
+    strict_keys: bool,
+    /// Whether empty lists must have the `TAG_End` element type.
+    strict_empty_lists: bool,
+    /// Called with the payloads of tags with an unknown ID.
+    unknown_tag: Option<UnknownTagFn<'a>>,
+    /// Called with the path and type of every compound entry.
+    on_field: Option<OnFieldFn<'a>>,
+    /// The path of the value being deserialized. Only maintained while
+    /// [`Self::on_field`] is set.
+    path: String,
 }
 
-impl<R: Read> RootDeserializer<R> {
+type UnknownTagFn<'a> = Box<dyn FnMut(u8, &mut dyn Read) -> Result<(), Error> + Send + 'a>;
+
+type OnFieldFn<'a> = Box<dyn FnMut(&str, TagType) + Send + 'a>;
+
+impl<'a, R: Read> RootDeserializer<'a, R> {
     /// Constructs a new deserializer
     ///
     /// [`Self::root_name`] is set to the empty string.
@@ -33,28 +58,418 @@ impl<R: Read> RootDeserializer<R> {
             reader,
             root_name: String::new(),
             save_root_name,
+            max_total_elements: u64::MAX,
+            total_elements: 0,
+            max_depth: MAX_DEPTH,
+            depth: 0,
+            strict_keys: false,
+            strict_empty_lists: false,
+            unknown_tag: None,
+            on_field: None,
+            path: String::new(),
         }
     }
 
-    fn read_name(&mut self) -> Result<Tag, Error> {
-        let tag = Tag::from_u8(self.reader.read_u8()?)?;
+    /// Consumes the deserializer, returning the reader.
+    ///
+    /// After a document has been deserialized, the reader is positioned just
+    /// after it. This is useful when NBT is followed by other data in the
+    /// same stream, as in network packets.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use serde_nbt::binary::Deserializer;
+    /// use serde_nbt::Compound;
+    ///
+    /// let packet = [10, 0, 0, 0, 1, 2, 3];
+    ///
+    /// let mut de = Deserializer::new(packet.as_slice(), false);
+    /// let compound = Compound::deserialize(&mut de).unwrap();
+    ///
+    /// assert!(compound.is_empty());
+    /// assert_eq!(de.into_inner(), [1, 2, 3]);
+    /// ```
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Sets the maximum number of elements allowed in a single document.
+    ///
+    /// Every list element, array element, and compound entry counts towards
+    /// the limit, no matter how deeply it is nested. Deserialization fails
+    /// once the limit is exceeded. This protects against malicious input
+    /// which spreads a huge number of elements across many small
+    /// containers.
+    ///
+    /// By default, there is no limit.
+    pub fn set_max_total_elements(&mut self, max: u64) {
+        self.max_total_elements = max;
+    }
 
-        if tag != Tag::Compound {
+    /// Sets the maximum nesting depth of compounds and lists.
+    ///
+    /// The root compound has a depth of one, and every compound or list
+    /// inside of it adds one more. Deserialization fails once the limit is
+    /// exceeded instead of overflowing the stack.
+    ///
+    /// The default limit is 512.
+    pub fn set_max_depth(&mut self, max: usize) {
+        self.max_depth = max;
+    }
+
+    /// Sets whether compound keys containing NUL or other control characters
+    /// are rejected.
+    ///
+    /// Such keys can be encoded in NBT, but they rarely appear in real data
+    /// and usually indicate corruption. Enabling this option is useful for
+    /// sanitizing data before passing it on to programs that are stricter
+    /// about keys.
+    ///
+    /// By default, all keys are accepted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use serde_nbt::binary::Deserializer;
+    /// use serde_nbt::Compound;
+    ///
+    /// // A compound with a byte entry whose key is "a\n".
+    /// let bytes = [10, 0, 0, 1, 0, 2, b'a', b'\n', 5, 0];
+    ///
+    /// assert!(Compound::deserialize(&mut Deserializer::new(bytes.as_slice(), false)).is_ok());
+    ///
+    /// let mut de = Deserializer::new(bytes.as_slice(), false);
+    /// de.set_strict_keys(true);
+    ///
+    /// assert!(Compound::deserialize(&mut de).is_err());
+    /// ```
+    pub fn set_strict_keys(&mut self, strict: bool) {
+        self.strict_keys = strict;
+    }
+
+    /// Sets whether empty lists with an element type other than `TAG_End`
+    /// are rejected.
+    ///
+    /// The canonical element type of an empty list is `TAG_End`, but any
+    /// type is valid. Vanilla Minecraft accepts every element type when
+    /// reading. It writes `TAG_End` for lists that were created empty, while
+    /// a list that had all of its elements removed keeps its element type, so
+    /// both forms appear in real data. Enabling this option is useful for
+    /// checking that data is canonical.
+    ///
+    /// The check also applies inside of [`RawCompound`]s and when reading with
+    /// [`Self::deserialize_lenient`]. By default, empty lists of any element
+    /// type are accepted.
+    ///
+    /// [`RawCompound`]: crate::RawCompound
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use serde_nbt::binary::Deserializer;
+    /// use serde_nbt::Compound;
+    ///
+    /// // A compound with an empty list of ints named "a".
+    /// let bytes = [10, 0, 0, 9, 0, 1, b'a', 3, 0, 0, 0, 0, 0];
+    ///
+    /// assert!(Compound::deserialize(&mut Deserializer::new(bytes.as_slice(), false)).is_ok());
+    ///
+    /// let mut de = Deserializer::new(bytes.as_slice(), false);
+    /// de.set_strict_empty_lists(true);
+    ///
+    /// assert!(Compound::deserialize(&mut de).is_err());
+    /// ```
+    pub fn set_strict_empty_lists(&mut self, strict: bool) {
+        self.strict_empty_lists = strict;
+    }
+
+    /// Sets a callback for tags whose ID is not known to this crate, such as
+    /// tags added by a future version of Minecraft.
+    ///
+    /// The callback receives the tag ID and the reader, positioned at the
+    /// start of the payload. It must read the entire payload, which it is free
+    /// to store elsewhere, and return `Ok(())` to skip the tag. Returning an
+    /// error fails deserialization.
+    ///
+    /// Compound entries with an unknown tag are skipped as if they were
+    /// absent. For a list with an unknown element type, the callback is
+    /// called once for every element, and the list is deserialized as empty.
+    ///
+    /// The callback may borrow local state for as long as the deserializer is
+    /// alive. By default, unknown tags are an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Read;
+    ///
+    /// use serde::Deserialize;
+    /// use serde_nbt::binary::Deserializer;
+    /// use serde_nbt::{Compound, Value};
+    ///
+    /// // A compound with an entry of the unknown tag 42, whose payload
+    /// // happens to be 2 bytes long, followed by a byte entry.
+    /// let bytes = [10, 0, 0, 42, 0, 1, b'a', 7, 7, 1, 0, 1, b'b', 5, 0];
+    ///
+    /// let mut de = Deserializer::new(bytes.as_slice(), false);
+    /// de.on_unknown_tag(|id, reader| {
+    ///     assert_eq!(id, 42);
+    ///     reader.read_exact(&mut [0; 2])?;
+    ///     Ok(())
+    /// });
+    ///
+    /// let value = Value::deserialize(&mut de).unwrap();
+    ///
+    /// assert_eq!(
+    ///     value,
+    ///     Value::Compound(Compound::from_iter([("b".to_owned(), Value::Byte(5))]))
+    /// );
+    /// ```
+    pub fn on_unknown_tag<F>(&mut self, f: F)
+    where
+        F: FnMut(u8, &mut dyn Read) -> Result<(), Error> + Send + 'a,
+    {
+        self.unknown_tag = Some(Box::new(f));
+    }
+
+    /// Sets a callback which is called as each compound entry is entered,
+    /// before its value is read.
+    ///
+    /// The callback receives the path of the entry, in the same form as
+    /// [`Error::path`], and its tag type. Entries are reported in the order
+    /// they appear in the data, including entries which are not part of the
+    /// type being deserialized. This is useful for reporting progress or
+    /// building an index of a large file in a single pass.
+    ///
+    /// Paths are only tracked while a callback is set, so there is no cost
+    /// otherwise. The callback may borrow local state for as long as the
+    /// deserializer is alive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use serde_nbt::binary::Deserializer;
+    /// use serde_nbt::{TagType, Value};
+    ///
+    /// // A compound with a list named "a" of one compound, which has a byte
+    /// // entry named "b".
+    /// let bytes = [10, 0, 0, 9, 0, 1, b'a', 10, 0, 0, 0, 1, 1, 0, 1, b'b', 5, 0, 0];
+    ///
+    /// let mut fields = vec![];
+    ///
+    /// let mut de = Deserializer::new(bytes.as_slice(), false);
+    /// de.on_field(|path, tag_type| fields.push((path.to_owned(), tag_type)));
+    ///
+    /// Value::deserialize(&mut de).unwrap();
+    /// drop(de);
+    ///
+    /// assert_eq!(
+    ///     fields,
+    ///     [
+    ///         ("a".to_owned(), TagType::List),
+    ///         ("a[0].b".to_owned(), TagType::Byte)
+    ///     ]
+    /// );
+    /// ```
+    pub fn on_field<F>(&mut self, f: F)
+    where
+        F: FnMut(&str, TagType) + Send + 'a,
+    {
+        self.on_field = Some(Box::new(f));
+    }
+
+    /// Appends the key of a compound entry to the path and reports it to the
+    /// callback set by [`Self::on_field`]. Returns the length of the path to
+    /// restore with [`Self::leave`].
+    pub(super) fn enter_field(&mut self, key: &str, tag: Tag) -> usize {
+        let len = self.path.len();
+
+        if let Some(f) = &mut self.on_field {
+            if !self.path.is_empty() {
+                self.path.push('.');
+            }
+            self.path.push_str(key);
+            f(&self.path, tag);
+        }
+
+        len
+    }
+
+    /// Appends a list index to the path. Returns the length of the path to
+    /// restore with [`Self::leave`].
+    pub(super) fn enter_element(&mut self, index: u32) -> usize {
+        let len = self.path.len();
+
+        if self.on_field.is_some() {
+            let _ = write!(self.path, "[{index}]");
+        }
+
+        len
+    }
+
+    pub(super) fn leave(&mut self, len: usize) {
+        self.path.truncate(len);
+    }
+
+    /// Runs `f` on the contents of a compound or list, failing if they are
+    /// nested deeper than the limit set by [`Self::set_max_depth`].
+    pub(super) fn nested<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        self.depth += 1;
+        let res = check_depth_limit(self.depth, self.max_depth).and_then(|()| f(self));
+        self.depth -= 1;
+        res
+    }
+
+    pub(super) fn skips_unknown_tags(&self) -> bool {
+        self.unknown_tag.is_some()
+    }
+
+    /// Passes the payload of a tag with an unknown ID to the callback set by
+    /// [`Self::on_unknown_tag`], or fails if there is none.
+    pub(super) fn unknown_tag(&mut self, id: u8) -> Result<(), Error> {
+        match &mut self.unknown_tag {
+            Some(f) => f(id, &mut self.reader),
+            None => Err(Error::new_owned(format!("invalid tag byte `{id}`"))),
+        }
+    }
+
+    /// Counts `n` more elements towards the limit set by
+    /// [`Self::set_max_total_elements`].
+    pub(super) fn count_elements(&mut self, n: u64) -> Result<(), Error> {
+        self.total_elements = self.total_elements.saturating_add(n);
+
+        if self.total_elements > self.max_total_elements {
             return Err(Error::new_owned(format!(
-                "unexpected tag `{tag}` (root value must be a compound)"
+                "document exceeds the maximum of {} total elements",
+                self.max_total_elements
             )));
         }
 
-        if self.save_root_name {
-            let mut buf = SmallVec::<[u8; 128]>::new();
-            for _ in 0..self.reader.read_u16::<BigEndian>()? {
-                buf.push(self.reader.read_u8()?);
+        Ok(())
+    }
+
+    /// Fails if strict keys are enabled and the key contains a control
+    /// character.
+    pub(super) fn check_key(&self, key: &str) -> Result<(), Error> {
+        if self.strict_keys {
+            if let Some(c) = key.chars().find(|c| c.is_control()) {
+                return Err(Error::new_owned(format!(
+                    "compound key contains the control character U+{:04X}",
+                    c as u32
+                )));
             }
+        }
+
+        Ok(())
+    }
 
-            match from_java_cesu8(&buf).map_err(|_| Error::new_static(CESU8_DECODE_ERROR))? {
-                Cow::Borrowed(s) => s.clone_into(&mut self.root_name),
-                Cow::Owned(s) => self.root_name = s,
+    /// Reads the tag of the next compound entry, returning it.
+    ///
+    /// Entries with an unknown tag are passed to the callback set by
+    /// [`Self::on_unknown_tag`] and skipped, using `key` as scratch space.
+    /// Every entry is counted towards the limit set by
+    /// [`Self::set_max_total_elements`]. The key of the returned entry is left
+    /// for the caller to read.
+    pub(super) fn read_entry_tag(&mut self, key: &mut String) -> Result<Tag, Error> {
+        loop {
+            let id = self.reader.read_u8()?;
+
+            let tag = match Tag::from_id(id) {
+                Ok(tag) => tag,
+                Err(e) if !self.skips_unknown_tags() => return Err(e),
+                Err(_) => {
+                    self.count_elements(1)?;
+                    read_string_into(&mut self.reader, key)?;
+                    self.unknown_tag(id).map_err(|e| e.field(key.clone()))?;
+                    continue;
+                }
+            };
+
+            if tag != Tag::End {
+                self.count_elements(1)?;
             }
+
+            return Ok(tag);
+        }
+    }
+
+    /// Reads the element type and length of a list, returning them.
+    ///
+    /// The elements are counted towards the limit set by
+    /// [`Self::set_max_total_elements`]. If the element type is unknown and a
+    /// callback is set by [`Self::on_unknown_tag`], the elements are passed to
+    /// it and the list is returned as an empty list of `TAG_End`.
+    pub(super) fn read_list_header(&mut self) -> Result<(Tag, i32), Error> {
+        let id = self.reader.read_u8()?;
+        let len = self.reader.read_i32::<BigEndian>()?;
+
+        if len < 0 {
+            return Err(Error::new_static("list with negative length"));
+        }
+
+        self.count_elements(len as u64)?;
+
+        let element_tag = match Tag::from_id(id) {
+            Ok(tag) => tag,
+            Err(e) if !self.skips_unknown_tags() => return Err(e),
+            Err(_) => {
+                // Skip the elements and present the list as empty.
+                for i in 0..len {
+                    self.unknown_tag(id).map_err(|e| e.index(i as usize))?;
+                }
+
+                return Ok((Tag::End, 0));
+            }
+        };
+
+        if element_tag == Tag::End && len != 0 {
+            return Err(Error::new_static(
+                "list with TAG_End element type must have length zero",
+            ));
+        }
+
+        self.check_list_header(element_tag, len)?;
+
+        Ok((element_tag, len))
+    }
+
+    /// Fails if strict empty lists are enabled and a list of length zero
+    /// has an element type other than `TAG_End`.
+    pub(super) fn check_list_header(&self, element_tag: Tag, len: i32) -> Result<(), Error> {
+        if self.strict_empty_lists && len == 0 && element_tag != Tag::End {
+            return Err(Error::new_owned(format!(
+                "empty list has the non-canonical element type `{element_tag}`"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Reads the tag and name of the root compound, and starts a new
+    /// document.
+    pub(super) fn read_name(&mut self) -> Result<Tag, Error> {
+        self.total_elements = 0;
+        self.depth = 0;
+        self.path.clear();
+
+        let tag = Tag::from_id(self.reader.read_u8()?)?;
+
+        if tag != Tag::Compound {
+            return Err(Error::new_owned(format!(
+                "unexpected tag `{tag}` (root value must be a compound)"
+            )));
+        }
+
+        if self.save_root_name {
+            read_string_into(&mut self.reader, &mut self.root_name)?;
         } else {
             for _ in 0..self.reader.read_u16::<BigEndian>()? {
                 self.reader.read_u8()?;
@@ -65,13 +480,31 @@ impl<R: Read> RootDeserializer<R> {
     }
 }
 
-impl<'de: 'a, 'a, R: Read> Deserializer<'de> for &'a mut RootDeserializer<R> {
+/// Reads an NBT string into `out`, replacing its contents.
+pub(crate) fn read_string_into<R: Read + ?Sized>(
+    reader: &mut R,
+    out: &mut String,
+) -> Result<(), Error> {
+    let mut buf = SmallVec::<[u8; 128]>::new();
+    for _ in 0..reader.read_u16::<BigEndian>()? {
+        buf.push(reader.read_u8()?);
+    }
+
+    match from_java_cesu8(&buf).map_err(|_| Error::new_static(CESU8_DECODE_ERROR))? {
+        Cow::Borrowed(s) => s.clone_into(out),
+        Cow::Owned(s) => *out = s,
+    }
+
+    Ok(())
+}
+
+impl<'de: 'r, 'r, 'a, R: Read> Deserializer<'de> for &'r mut RootDeserializer<'a, R> {
     type Error = Error;
 
     forward_to_deserialize_any! {
         bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
-        bytes byte_buf option unit unit_struct newtype_struct seq tuple
-        tuple_struct map enum identifier ignored_any
+        bytes byte_buf option unit unit_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
     }
 
     fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -80,17 +513,12 @@ impl<'de: 'a, 'a, R: Read> Deserializer<'de> for &'a mut RootDeserializer<R> {
     {
         let tag = self.read_name()?;
 
-        PayloadDeserializer {
-            reader: &mut self.reader,
-            tag,
-        }
-        .deserialize_any(visitor)
+        PayloadDeserializer { de: self, tag }.deserialize_any(visitor)
     }
 
-    fn deserialize_struct<V>(
+    fn deserialize_newtype_struct<V>(
         self,
         name: &'static str,
-        fields: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value, Self::Error>
     where
@@ -98,11 +526,7 @@ impl<'de: 'a, 'a, R: Read> Deserializer<'de> for &'a mut RootDeserializer<R> {
     {
         let tag = self.read_name()?;
 
-        PayloadDeserializer {
-            reader: &mut self.reader,
-            tag,
-        }
-        .deserialize_struct(name, fields, visitor)
+        PayloadDeserializer { de: self, tag }.deserialize_newtype_struct(name, visitor)
     }
 
     fn is_human_readable(&self) -> bool {
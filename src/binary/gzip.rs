@@ -0,0 +1,40 @@
+use std::io::{BufReader, Read, Write};
+
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::binary::{from_reader, to_writer};
+use crate::Result;
+
+/// Reads gzip-compressed NBT binary data from the provided reader.
+///
+/// Most NBT files, such as `level.dat` and player data, are compressed this
+/// way. Streams made of several concatenated gzip members are read in full,
+/// as if they were a single member.
+///
+/// The name of the root compound is discarded.
+pub fn from_gzip_reader<R, T>(reader: R) -> Result<T>
+where
+    R: Read,
+    T: DeserializeOwned,
+{
+    from_reader(BufReader::new(MultiGzDecoder::new(reader)))
+}
+
+/// Writes gzip-compressed NBT binary data to the provided writer.
+///
+/// The output is a single gzip member compressed at the default level. The
+/// name of the root compound will be `""`.
+pub fn to_gzip_writer<W, T>(writer: W, value: &T) -> Result<()>
+where
+    W: Write,
+    T: Serialize + ?Sized,
+{
+    let mut encoder = GzEncoder::new(writer, Compression::default());
+    to_writer(&mut encoder, value)?;
+    encoder.finish()?;
+    Ok(())
+}
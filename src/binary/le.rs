@@ -0,0 +1,272 @@
+//! Support for the little-endian NBT used by Minecraft: Bedrock Edition.
+//!
+//! Bedrock Edition stores NBT with little-endian numbers and UTF-8 strings,
+//! where Java Edition uses big-endian numbers and CESU-8 strings. The
+//! functions in this module convert between the two representations so that
+//! the same [`Serialize`] and [`Deserialize`](serde::Deserialize)
+//! implementations work for both editions.
+//!
+//! The conversion happens in memory before deserializing, so these functions
+//! are intended for documents of moderate size such as `level.dat` files.
+
+use std::io::{Read, Write};
+
+use byteorder::{BigEndian, LittleEndian, ReadBytesExt, WriteBytesExt};
+use cesu8::{from_java_cesu8, to_java_cesu8};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::binary::{from_slice_exact, string_len, to_vec};
+use crate::{check_depth, Error, Result, Tag, CESU8_DECODE_ERROR};
+
+/// Reads little-endian NBT binary data from the provided reader.
+///
+/// The name of the root compound is discarded.
+pub fn from_reader<R, T>(reader: R) -> Result<T>
+where
+    R: Read,
+    T: DeserializeOwned,
+{
+    let mut buf = Vec::new();
+    Converter::new(reader, &mut buf, false).document()?;
+    from_slice_exact(&buf)
+}
+
+/// Reads a Bedrock Edition `level.dat` file.
+///
+/// These files start with an 8-byte header containing the storage version
+/// and the length of the NBT data that follows, both as little-endian 32-bit
+/// integers. The storage version is returned along with the deserialized
+/// value. An error is returned if the length in the header does not match
+/// the length of the NBT data.
+pub fn from_bedrock_leveldat<R, T>(mut reader: R) -> Result<(u32, T)>
+where
+    R: Read,
+    T: DeserializeOwned,
+{
+    let version = reader.read_u32::<LittleEndian>()?;
+    let len = reader.read_u32::<LittleEndian>()?;
+
+    let mut body = reader.take(len as u64);
+    let mut buf = Vec::new();
+    Converter::new(&mut body, &mut buf, false).document()?;
+
+    if body.limit() != 0 {
+        return Err(Error::new_owned(format!(
+            "level.dat header declares {len} bytes of NBT, but the NBT data is {} bytes long",
+            len as u64 - body.limit()
+        )));
+    }
+
+    Ok((version, from_slice_exact(&buf)?))
+}
+
+/// Writes little-endian NBT binary data to the provided writer.
+///
+/// The name of the root compound will be `""`. Like
+/// [`binary::to_writer`](crate::binary::to_writer), this fails if the value
+/// does not serialize as a compound.
+pub fn to_writer<W, T>(mut writer: W, value: &T) -> Result<()>
+where
+    W: Write,
+    T: Serialize + ?Sized,
+{
+    let buf = to_vec(value)?;
+    Converter::new(buf.as_slice(), &mut writer, true).document()
+}
+
+/// Writes a Bedrock Edition `level.dat` file.
+///
+/// The 8-byte header is written first, containing the given storage version
+/// and the length of the little-endian NBT data that follows. This is the
+/// inverse of [`from_bedrock_leveldat`].
+pub fn to_bedrock_leveldat<W, T>(mut writer: W, version: u32, value: &T) -> Result<()>
+where
+    W: Write,
+    T: Serialize + ?Sized,
+{
+    let mut body = Vec::new();
+    to_writer(&mut body, value)?;
+
+    let len = body
+        .len()
+        .try_into()
+        .map_err(|_| Error::new_static("level.dat NBT data exceeds u32::MAX bytes"))?;
+
+    writer.write_u32::<LittleEndian>(version)?;
+    writer.write_u32::<LittleEndian>(len)?;
+    writer.write_all(&body)?;
+    Ok(())
+}
+
+/// Converts NBT documents between the big-endian and little-endian
+/// representations.
+///
+/// Numbers are converted by reversing their bytes. Strings are re-encoded
+/// between CESU-8 and UTF-8.
+struct Converter<R, W> {
+    reader: R,
+    writer: W,
+    /// If `true`, the input is big-endian and the output is little-endian.
+    /// Otherwise, the opposite.
+    to_le: bool,
+}
+
+impl<R: Read, W: Write> Converter<R, W> {
+    fn new(reader: R, writer: W, to_le: bool) -> Self {
+        Self {
+            reader,
+            writer,
+            to_le,
+        }
+    }
+
+    fn document(&mut self) -> Result<()> {
+        let tag = self.tag()?;
+
+        if tag != Tag::Compound {
+            return Err(Error::new_owned(format!(
+                "unexpected tag `{tag}` (root value must be a compound)"
+            )));
+        }
+
+        self.string()?;
+        self.payload(tag, 0)
+    }
+
+    fn tag(&mut self) -> Result<Tag> {
+        let tag = Tag::from_id(self.reader.read_u8()?)?;
+        self.writer.write_u8(tag as u8)?;
+        Ok(tag)
+    }
+
+    /// Copies a number of `N` bytes, reversing its byte order. Returns the
+    /// bytes as written.
+    fn swap<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut bytes = [0; N];
+        self.reader.read_exact(&mut bytes)?;
+        bytes.reverse();
+        self.writer.write_all(&bytes)?;
+        Ok(bytes)
+    }
+
+    fn len(&mut self, what: &'static str) -> Result<i32> {
+        let bytes = self.swap::<4>()?;
+
+        let len = if self.to_le {
+            i32::from_le_bytes(bytes)
+        } else {
+            i32::from_be_bytes(bytes)
+        };
+
+        if len < 0 {
+            return Err(Error::new_owned(format!("{what} with negative length")));
+        }
+
+        Ok(len)
+    }
+
+    fn string(&mut self) -> Result<()> {
+        let len = if self.to_le {
+            self.reader.read_u16::<BigEndian>()?
+        } else {
+            self.reader.read_u16::<LittleEndian>()?
+        };
+
+        let mut bytes = vec![0; len as usize];
+        self.reader.read_exact(&mut bytes)?;
+
+        let converted = if self.to_le {
+            from_java_cesu8(&bytes)
+                .map_err(|_| Error::new_static(CESU8_DECODE_ERROR))?
+                .into_owned()
+                .into_bytes()
+        } else {
+            let s = std::str::from_utf8(&bytes)
+                .map_err(|_| Error::new_static("invalid UTF-8 in little-endian NBT string"))?;
+            to_java_cesu8(s).into_owned()
+        };
+
+        let len = string_len(converted.len(), "string")?;
+
+        if self.to_le {
+            self.writer.write_u16::<LittleEndian>(len)?;
+        } else {
+            self.writer.write_u16::<BigEndian>(len)?;
+        }
+
+        self.writer.write_all(&converted)?;
+        Ok(())
+    }
+
+    fn payload(&mut self, tag: Tag, depth: usize) -> Result<()> {
+        match tag {
+            Tag::End => return Err(Error::new_static("unexpected TAG_End")),
+            Tag::Byte => {
+                self.swap::<1>()?;
+            }
+            Tag::Short => {
+                self.swap::<2>()?;
+            }
+            Tag::Int | Tag::Float => {
+                self.swap::<4>()?;
+            }
+            Tag::Long | Tag::Double => {
+                self.swap::<8>()?;
+            }
+            Tag::ByteArray => {
+                let len = self.len("array")?;
+                let copied =
+                    std::io::copy(&mut (&mut self.reader).take(len as u64), &mut self.writer)?;
+
+                if copied != len as u64 {
+                    return Err(Error::new_static("unexpected end of byte array"));
+                }
+            }
+            Tag::String => self.string()?,
+            Tag::List => {
+                check_depth(depth + 1)?;
+
+                let element_tag = self.tag()?;
+                let len = self.len("list")?;
+
+                if element_tag == Tag::End && len != 0 {
+                    return Err(Error::new_static(
+                        "list with TAG_End element type must have length zero",
+                    ));
+                }
+
+                for i in 0..len {
+                    self.payload(element_tag, depth + 1)
+                        .map_err(|e| e.index(i as usize))?;
+                }
+            }
+            Tag::Compound => {
+                check_depth(depth + 1)?;
+
+                loop {
+                    let tag = self.tag()?;
+
+                    if tag == Tag::End {
+                        break;
+                    }
+
+                    self.string()?;
+                    self.payload(tag, depth + 1)?;
+                }
+            }
+            Tag::IntArray => {
+                for _ in 0..self.len("array")? {
+                    self.swap::<4>()?;
+                }
+            }
+            Tag::LongArray => {
+                for _ in 0..self.len("array")? {
+                    self.swap::<8>()?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,12 +1,14 @@
-use std::io::Write;
+use std::io::{BufWriter, Write};
 
 use byteorder::{BigEndian, WriteBytesExt};
 use cesu8::to_java_cesu8;
+pub use list_writer::ListWriter;
 pub use root::RootSerializer as Serializer;
 use serde::{ser, Serialize};
 
-use crate::{Error, Result};
+use crate::{check_depth_limit, Error, Result, Tag, Value, MAX_DEPTH};
 
+mod list_writer;
 mod map;
 mod payload;
 mod root;
@@ -21,6 +23,10 @@ mod structs;
 ///
 /// The name of the root compound will be `""`. If you want to use a different
 /// name, see [`Serializer`].
+///
+/// The writer receives many small writes, so it should be buffered. For
+/// unbuffered writers such as [`File`](std::fs::File), use
+/// [`to_writer_buffered`] instead.
 pub fn to_writer<W, T>(writer: W, value: &T) -> Result<()>
 where
     W: Write,
@@ -29,15 +35,251 @@ where
     value.serialize(&mut Serializer::new(writer, ""))
 }
 
+/// Writes uncompressed NBT binary data to the provided writer through an
+/// internal buffer.
+///
+/// This is like [`to_writer`], but the output is collected in a
+/// [`BufWriter`] and passed to `writer` in large pieces. The buffer is
+/// flushed before returning, so any I/O error is reported by this function.
+pub fn to_writer_buffered<W, T>(writer: W, value: &T) -> Result<()>
+where
+    W: Write,
+    T: Serialize + ?Sized,
+{
+    let mut writer = BufWriter::new(writer);
+    to_writer(&mut writer, value)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Like [`to_writer`], but returns the number of bytes written.
+///
+/// This is useful for metrics and for length-prefixed framing, where the
+/// size of the NBT data must be known without serializing it twice.
+///
+/// # Examples
+///
+/// ```
+/// use serde_nbt::binary::to_writer_counted;
+/// use serde_nbt::{Compound, Value};
+///
+/// let compound = Compound::from_iter([("a".to_owned(), Value::Byte(1))]);
+///
+/// let mut buf = vec![];
+/// let len = to_writer_counted(&mut buf, &compound).unwrap();
+///
+/// assert_eq!(len, buf.len());
+/// ```
+pub fn to_writer_counted<W, T>(writer: W, value: &T) -> Result<usize>
+where
+    W: Write,
+    T: Serialize + ?Sized,
+{
+    let mut writer = CountingWriter { writer, count: 0 };
+    to_writer(&mut writer, value)?;
+    Ok(writer.count)
+}
+
+/// Writes uncompressed NBT binary data to a new byte vector.
+///
+/// This is a convenience function around [`to_writer`]. See its documentation
+/// for more information.
+pub fn to_vec<T>(value: &T) -> Result<Vec<u8>>
+where
+    T: Serialize + ?Sized,
+{
+    let mut buf = Vec::new();
+    to_writer(&mut buf, value)?;
+    Ok(buf)
+}
+
+/// Like [`to_writer`], but uses the given name for the root compound.
+///
+/// Most files, such as `level.dat`, have an empty root name, but some formats
+/// give the root compound a name of their own.
+///
+/// # Examples
+///
+/// ```
+/// use serde::Deserialize;
+/// use serde_nbt::binary::{to_writer_named, Deserializer};
+/// use serde_nbt::Compound;
+///
+/// let mut buf = vec![];
+/// to_writer_named(&mut buf, "Schematic", &Compound::new()).unwrap();
+///
+/// let mut de = Deserializer::new(buf.as_slice(), true);
+/// Compound::deserialize(&mut de).unwrap();
+///
+/// assert_eq!(de.root_name, "Schematic");
+/// ```
+pub fn to_writer_named<W, T>(writer: W, root_name: &str, value: &T) -> Result<()>
+where
+    W: Write,
+    T: Serialize + ?Sized,
+{
+    value.serialize(&mut Serializer::new(writer, root_name))
+}
+
+/// Writes uncompressed NBT binary data to a new byte vector, using the given
+/// name for the root compound.
+pub fn to_vec_named<T>(root_name: &str, value: &T) -> Result<Vec<u8>>
+where
+    T: Serialize + ?Sized,
+{
+    let mut buf = Vec::new();
+    to_writer_named(&mut buf, root_name, value)?;
+    Ok(buf)
+}
+
+/// Writes the payload of a single tag to the provided writer.
+///
+/// Only the payload is written, without a tag ID or name in front of it. The
+/// tag ID of the value is `value.tag_type() as u8`. Together with
+/// [`read_tag_body`](crate::binary::read_tag_body), this can be used to write
+/// NBT in custom framings, such as the network format where the root compound
+/// has no name.
+///
+/// # Examples
+///
+/// ```
+/// use serde_nbt::binary::write_tag_body;
+/// use serde_nbt::{Compound, Value};
+///
+/// // A nameless root compound.
+/// let value = Value::Compound(Compound::from_iter([("a".to_owned(), Value::Byte(1))]));
+///
+/// let mut buf = vec![value.tag_type() as u8];
+/// write_tag_body(&mut buf, &value).unwrap();
+///
+/// assert_eq!(buf, [10, 1, 0, 1, b'a', 1, 0]);
+/// ```
+pub fn write_tag_body<W>(writer: W, value: &Value) -> Result<()>
+where
+    W: Write,
+{
+    let tag = value.tag_type();
+
+    write_payload(writer, tag, value)
+}
+
+/// Writes the payload of a tag of type `tag`, like [`write_tag_body`]. This
+/// avoids converting `value` into a [`Value`] first.
+pub(crate) fn write_payload<W, T>(mut writer: W, tag: Tag, value: &T) -> Result<()>
+where
+    W: Write,
+    T: Serialize + ?Sized,
+{
+    value.serialize(&mut payload::PayloadSerializer::seq_element(
+        &mut writer,
+        Context::new(MAX_DEPTH),
+        tag,
+    ))
+}
+
+/// Writes a single named tag to the provided writer.
+pub(crate) fn write_named<W, T>(mut writer: W, name: &str, value: &T) -> Result<()>
+where
+    W: Write,
+    T: Serialize + ?Sized,
+{
+    value.serialize(&mut payload::PayloadSerializer::named(
+        &mut writer,
+        name,
+        Context::new(MAX_DEPTH),
+    ))
+}
+
+/// The state shared by every part of the serializer: the nesting depth of
+/// the value being serialized, and the settings of the serializer.
+///
+/// The depth must stay within a limit. Without one, a pathologically deep
+/// value would overflow the stack.
+#[derive(Clone, Copy)]
+struct Context {
+    depth: usize,
+    max_depth: usize,
+    bool_tag: BoolTag,
+}
+
+impl Context {
+    fn new(max_depth: usize) -> Self {
+        Self {
+            depth: 0,
+            max_depth,
+            bool_tag: BoolTag::Byte,
+        }
+    }
+
+    /// Returns the context of the contents of a compound or list at this
+    /// depth.
+    fn enter(self) -> Result<Self> {
+        let depth = self.depth + 1;
+        check_depth_limit(depth, self.max_depth)?;
+        Ok(Self { depth, ..self })
+    }
+}
+
+/// The tag type that `bool`s are serialized as.
+///
+/// Minecraft stores booleans as bytes, which is the default. Some other
+/// programs that consume NBT expect them as ints instead. Either way, `false`
+/// is `0` and `true` is `1`, and both forms deserialize back into a `bool`.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum BoolTag {
+    /// `TAG_Byte`.
+    #[default]
+    Byte,
+    /// `TAG_Int`.
+    Int,
+}
+
+/// A writer which counts the bytes written to the inner writer.
+struct CountingWriter<W> {
+    writer: W,
+    count: usize,
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.writer.write(buf)?;
+        self.count += n;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
 type Impossible = ser::Impossible<(), Error>;
 
-fn write_string(mut writer: impl Write, string: &str) -> Result<()> {
+pub(crate) fn write_string(writer: impl Write, string: &str) -> Result<()> {
+    write_string_as(writer, string, "string")
+}
+
+/// Writes the name of a tag. This is the same as [`write_string`], but the
+/// error for an oversized name refers to it as a key.
+pub(crate) fn write_key(writer: impl Write, key: &str) -> Result<()> {
+    write_string_as(writer, key, "key")
+}
+
+fn write_string_as(mut writer: impl Write, string: &str, what: &str) -> Result<()> {
     let data = to_java_cesu8(string);
-    match data.len().try_into() {
-        Ok(len) => writer.write_u16::<BigEndian>(len)?,
-        Err(_) => return Err(Error::new_static("string byte length exceeds u16::MAX")),
-    };
+    let len = string_len(data.len(), what)?;
 
+    writer.write_u16::<BigEndian>(len)?;
     writer.write_all(&data)?;
     Ok(())
 }
+
+/// Converts the length of an encoded string to the `u16` written before it,
+/// failing if it is too long instead of truncating the length.
+pub(crate) fn string_len(len: usize, what: &str) -> Result<u16> {
+    len.try_into().map_err(|_| {
+        Error::new_owned(format!(
+            "{what} of {len} bytes exceeds the maximum length of {} bytes",
+            u16::MAX
+        ))
+    })
+}
@@ -0,0 +1,144 @@
+use std::io::Write;
+
+use byteorder::{BigEndian, WriteBytesExt};
+use serde::Serialize;
+
+use crate::binary::ser::payload::PayloadSerializer;
+use crate::binary::ser::Context;
+use crate::{Error, Result, Tag, TagType, MAX_DEPTH};
+
+/// Writes the payload of a list one element at a time.
+///
+/// The element type and length of the list are given up front and written
+/// immediately. Each element is then written as soon as it is pushed, so a
+/// huge list never has to be held in memory as a `Vec` or [`List`].
+///
+/// Like [`write_tag_body`], only the payload of the list is written. The tag
+/// ID and name in front of it, if any, are up to the caller. Call
+/// [`Self::finish`] once all elements have been pushed to check that the
+/// number of elements matches the declared length.
+///
+/// [`List`]: crate::List
+/// [`write_tag_body`]: crate::binary::write_tag_body
+///
+/// # Examples
+///
+/// ```
+/// use serde_nbt::binary::{from_reader, ListWriter};
+/// use serde_nbt::{List, TagType, Value};
+///
+/// // A root compound with a single list named "longs".
+/// let mut buf = vec![10, 0, 0, 9, 0, 5];
+/// buf.extend(b"longs");
+///
+/// let mut list = ListWriter::new(&mut buf, TagType::Long, 1000).unwrap();
+///
+/// for i in 0..1000_i64 {
+///     list.push(&i).unwrap();
+/// }
+///
+/// list.finish().unwrap();
+/// buf.push(0);
+///
+/// let value: Value = from_reader(buf.as_slice()).unwrap();
+///
+/// assert_eq!(
+///     value.as_compound().unwrap()["longs"],
+///     Value::List(List::Long((0..1000).collect()))
+/// );
+/// ```
+pub struct ListWriter<W> {
+    writer: W,
+    /// The context of the elements.
+    ctx: Context,
+    element_tag: Tag,
+    len: i32,
+    written: i32,
+    /// Holds each element until it has been serialized successfully.
+    scratch: Vec<u8>,
+}
+
+impl<W: Write> ListWriter<W> {
+    /// Writes the element type and length of a list to `writer`.
+    ///
+    /// An error is returned if `len` exceeds `i32::MAX`, or if the element
+    /// type is [`TagType::End`] and `len` is not zero.
+    pub fn new(mut writer: W, element_type: TagType, len: usize) -> Result<Self> {
+        let element_tag = element_type;
+
+        let len =
+            i32::try_from(len).map_err(|_| Error::new_static("length of list exceeds i32::MAX"))?;
+
+        if element_tag == Tag::End && len != 0 {
+            return Err(Error::new_static(
+                "list with TAG_End element type must have length zero",
+            ));
+        }
+
+        writer.write_u8(element_tag as u8)?;
+        writer.write_i32::<BigEndian>(len)?;
+
+        Ok(Self {
+            writer,
+            ctx: Context::new(MAX_DEPTH).enter()?,
+            element_tag,
+            len,
+            written: 0,
+            scratch: Vec::new(),
+        })
+    }
+
+    /// Writes the next element of the list.
+    ///
+    /// An error is returned if the element is not of the list's element type
+    /// or if all elements have already been written.
+    ///
+    /// The element is serialized in full before any of it reaches the
+    /// writer, so an element that fails to serialize leaves no partial output
+    /// behind and the list can still be completed with other elements. An
+    /// I/O error from the writer itself may still leave part of the element
+    /// written.
+    pub fn push<T>(&mut self, element: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        if self.written == self.len {
+            return Err(Error::new_owned(format!(
+                "attempt to write more than the {} declared list element(s)",
+                self.len
+            )));
+        }
+
+        self.scratch.clear();
+
+        element
+            .serialize(&mut PayloadSerializer::seq_element(
+                &mut self.scratch,
+                self.ctx,
+                self.element_tag,
+            ))
+            .map_err(|e| e.index(self.written as usize))?;
+
+        self.writer.write_all(&self.scratch)?;
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Returns the number of elements that have yet to be written.
+    pub fn remaining(&self) -> usize {
+        (self.len - self.written) as usize
+    }
+
+    /// Checks that every element of the list has been written, returning the
+    /// writer.
+    pub fn finish(self) -> Result<W> {
+        if self.written != self.len {
+            return Err(Error::new_owned(format!(
+                "{} list element(s) left to write",
+                self.remaining()
+            )));
+        }
+
+        Ok(self.writer)
+    }
+}
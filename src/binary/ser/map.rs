@@ -4,47 +4,46 @@ use byteorder::WriteBytesExt;
 use serde::{ser, Serialize, Serializer};
 
 use crate::binary::ser::payload::PayloadSerializer;
-use crate::binary::ser::Impossible;
+use crate::binary::ser::{Context, Impossible};
 use crate::{Error, Tag};
 
 pub struct SerializeMap<'w, W: ?Sized> {
     pub(super) writer: &'w mut W,
+    /// The context of the values of the map.
+    pub(super) ctx: Context,
 }
 
 impl<'w, W: Write + ?Sized> ser::SerializeMap for SerializeMap<'w, W> {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_key<T: ?Sized>(&mut self, _key: &T) -> Result<(), Error>
+    fn serialize_key<T>(&mut self, _key: &T) -> Result<(), Error>
     where
-        T: Serialize,
+        T: Serialize + ?Sized,
     {
         Err(Error::new_static(
             "map keys cannot be serialized individually",
         ))
     }
 
-    fn serialize_value<T: ?Sized>(&mut self, _value: &T) -> Result<(), Error>
+    fn serialize_value<T>(&mut self, _value: &T) -> Result<(), Error>
     where
-        T: Serialize,
+        T: Serialize + ?Sized,
     {
         Err(Error::new_static(
             "map values cannot be serialized individually",
         ))
     }
 
-    fn serialize_entry<K: ?Sized, V: ?Sized>(
-        &mut self,
-        key: &K,
-        value: &V,
-    ) -> Result<(), Self::Error>
+    fn serialize_entry<K, V>(&mut self, key: &K, value: &V) -> Result<(), Self::Error>
     where
-        K: Serialize,
-        V: Serialize,
+        K: Serialize + ?Sized,
+        V: Serialize + ?Sized,
     {
         key.serialize(MapEntrySerializer {
             writer: self.writer,
             value,
+            ctx: self.ctx,
         })
     }
 
@@ -56,12 +55,13 @@ impl<'w, W: Write + ?Sized> ser::SerializeMap for SerializeMap<'w, W> {
 struct MapEntrySerializer<'w, 'v, W: ?Sized, V: ?Sized> {
     writer: &'w mut W,
     value: &'v V,
+    ctx: Context,
 }
 
 macro_rules! non_string_map_key {
     ($typ:literal) => {
         Err(Error::new_static(concat!(
-            "map keys must be strings (got ",
+            "NBT compound keys must be strings (got ",
             $typ,
             ")"
         )))
@@ -129,7 +129,7 @@ impl<W: Write + ?Sized, V: Serialize + ?Sized> Serializer for MapEntrySerializer
 
     fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
         self.value
-            .serialize(&mut PayloadSerializer::named(self.writer, v))
+            .serialize(&mut PayloadSerializer::named(self.writer, v, self.ctx))
             .map_err(|e| e.field(v))
     }
 
@@ -141,9 +141,9 @@ impl<W: Write + ?Sized, V: Serialize + ?Sized> Serializer for MapEntrySerializer
         non_string_map_key!("None")
     }
 
-    fn serialize_some<T: ?Sized>(self, _value: &T) -> Result<Self::Ok, Self::Error>
+    fn serialize_some<T>(self, _value: &T) -> Result<Self::Ok, Self::Error>
     where
-        T: Serialize,
+        T: Serialize + ?Sized,
     {
         non_string_map_key!("Some")
     }
@@ -165,18 +165,18 @@ impl<W: Write + ?Sized, V: Serialize + ?Sized> Serializer for MapEntrySerializer
         non_string_map_key!("unit variant")
     }
 
-    fn serialize_newtype_struct<T: ?Sized>(
+    fn serialize_newtype_struct<T>(
         self,
         _name: &'static str,
-        _value: &T,
+        value: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
-        T: Serialize,
+        T: Serialize + ?Sized,
     {
-        non_string_map_key!("newtype struct")
+        value.serialize(self)
     }
 
-    fn serialize_newtype_variant<T: ?Sized>(
+    fn serialize_newtype_variant<T>(
         self,
         _name: &'static str,
         _variant_index: u32,
@@ -184,7 +184,7 @@ impl<W: Write + ?Sized, V: Serialize + ?Sized> Serializer for MapEntrySerializer
         _value: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
-        T: Serialize,
+        T: Serialize + ?Sized,
     {
         non_string_map_key!("newtype variant")
     }
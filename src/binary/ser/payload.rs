@@ -6,44 +6,76 @@ use serde::{Serialize, Serializer};
 use crate::binary::ser::map::SerializeMap;
 use crate::binary::ser::seq::SerializeSeq;
 use crate::binary::ser::structs::SerializeStruct;
-use crate::binary::ser::{write_string, Impossible};
-use crate::{ArrayType, Error, Tag};
+use crate::binary::ser::{write_key, write_string, BoolTag, Context, Impossible};
+use crate::{ArrayType, Error, Tag, RAW_COMPOUND_NAME};
 
 pub struct PayloadSerializer<'w, 'n, W: ?Sized> {
     writer: &'w mut W,
     state: State<'n>,
+    /// The context of the compound or list containing this payload.
+    ctx: Context,
 }
 
 #[derive(Clone, Copy)]
 enum State<'n> {
     Named(&'n str),
-    FirstListElement { len: i32, written_tag: Tag },
-    SeqElement { element_type: Tag },
+    FirstListElement {
+        len: i32,
+        written_tag: Tag,
+    },
+    SeqElement {
+        element_type: Tag,
+    },
+    /// Integers of any width are accepted as array elements, as long as they
+    /// fit in the element type of the array.
+    ArrayElement(ArrayType),
     Array(ArrayType),
+    /// The encoded payload of a [`RawCompound`](crate::RawCompound), which
+    /// is written verbatim.
+    RawCompound,
 }
 
 impl<'w, 'n, W: Write + ?Sized> PayloadSerializer<'w, 'n, W> {
-    pub(super) fn named(writer: &'w mut W, name: &'n str) -> Self {
+    pub(super) fn named(writer: &'w mut W, name: &'n str, ctx: Context) -> Self {
         Self {
             writer,
             state: State::Named(name),
+            ctx,
         }
     }
 
-    pub(super) fn first_list_element(writer: &'w mut W, len: i32) -> Self {
+    pub(super) fn first_list_element(writer: &'w mut W, ctx: Context, len: i32) -> Self {
         Self {
             writer,
             state: State::FirstListElement {
                 len,
                 written_tag: Tag::End,
             },
+            ctx,
         }
     }
 
-    pub(super) fn seq_element(writer: &'w mut W, element_type: Tag) -> Self {
+    pub(super) fn seq_element(writer: &'w mut W, ctx: Context, element_type: Tag) -> Self {
         Self {
             writer,
             state: State::SeqElement { element_type },
+            ctx,
+        }
+    }
+
+    pub(super) fn array_element(writer: &'w mut W, ctx: Context, array_type: ArrayType) -> Self {
+        Self {
+            writer,
+            state: State::ArrayElement(array_type),
+            ctx,
+        }
+    }
+
+    pub(super) fn raw_compound(writer: &'w mut W, ctx: Context) -> Self {
+        Self {
+            writer,
+            state: State::RawCompound,
+            ctx,
         }
     }
 
@@ -60,7 +92,7 @@ impl<'w, 'n, W: Write + ?Sized> PayloadSerializer<'w, 'n, W> {
         match &mut self.state {
             State::Named(name) => {
                 self.writer.write_u8(tag as u8)?;
-                write_string(&mut *self.writer, name)?;
+                write_key(&mut *self.writer, name)?;
             }
             State::FirstListElement { len, written_tag } => {
                 self.writer.write_u8(tag as u8)?;
@@ -75,6 +107,16 @@ impl<'w, 'n, W: Write + ?Sized> PayloadSerializer<'w, 'n, W> {
                     )));
                 }
             }
+            State::ArrayElement(array_type) => {
+                let element_type = array_type.element_tag();
+
+                if tag != element_type {
+                    return Err(Error::new_owned(format!(
+                        "list/array elements must be homogeneous (got {tag}, expected \
+                         {element_type})"
+                    )));
+                }
+            }
             State::Array(array_type) => {
                 let msg = match array_type {
                     ArrayType::Byte => "a byte array",
@@ -86,6 +128,36 @@ impl<'w, 'n, W: Write + ?Sized> PayloadSerializer<'w, 'n, W> {
                     "expected a seq for {msg}, got {tag} instead"
                 )));
             }
+            State::RawCompound => {
+                return Err(Error::new_owned(format!(
+                    "expected the bytes of a raw compound, got {tag} instead"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes an integer as an element of an array, failing if it does not fit
+    /// in the element type of the array.
+    fn write_array_element(&mut self, array_type: ArrayType, v: i64) -> Result<(), Error> {
+        let out_of_range = |ty| {
+            Error::new_owned(format!(
+                "value {v} out of {ty} range for {}",
+                array_type.name()
+            ))
+        };
+
+        match array_type {
+            ArrayType::Byte => {
+                let v = i8::try_from(v).map_err(|_| out_of_range("i8"))?;
+                self.writer.write_i8(v)?;
+            }
+            ArrayType::Int => {
+                let v = i32::try_from(v).map_err(|_| out_of_range("i32"))?;
+                self.writer.write_i32::<BigEndian>(v)?;
+            }
+            ArrayType::Long => self.writer.write_i64::<BigEndian>(v)?,
         }
 
         Ok(())
@@ -110,30 +182,60 @@ impl<'a, W: Write + ?Sized> Serializer for &'a mut PayloadSerializer<'_, '_, W>
     type SerializeStructVariant = Impossible;
 
     fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
-        self.check_state(Tag::Byte)?;
-        Ok(self.writer.write_i8(v as i8)?)
+        match self.ctx.bool_tag {
+            BoolTag::Byte => {
+                self.check_state(Tag::Byte)?;
+                Ok(self.writer.write_i8(v as i8)?)
+            }
+            BoolTag::Int => {
+                self.check_state(Tag::Int)?;
+                Ok(self.writer.write_i32::<BigEndian>(v as i32)?)
+            }
+        }
     }
 
     fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+        if let State::ArrayElement(array_type) = self.state {
+            return self.write_array_element(array_type, v.into());
+        }
+
         self.check_state(Tag::Byte)?;
         Ok(self.writer.write_i8(v)?)
     }
 
     fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+        if let State::ArrayElement(array_type) = self.state {
+            return self.write_array_element(array_type, v.into());
+        }
+
         self.check_state(Tag::Short)?;
         Ok(self.writer.write_i16::<BigEndian>(v)?)
     }
 
     fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+        if let State::ArrayElement(array_type) = self.state {
+            return self.write_array_element(array_type, v.into());
+        }
+
         self.check_state(Tag::Int)?;
         Ok(self.writer.write_i32::<BigEndian>(v)?)
     }
 
     fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+        if let State::ArrayElement(array_type) = self.state {
+            return self.write_array_element(array_type, v);
+        }
+
         self.check_state(Tag::Long)?;
         Ok(self.writer.write_i64::<BigEndian>(v)?)
     }
 
+    fn serialize_i128(self, _v: i128) -> Result<Self::Ok, Self::Error> {
+        Err(Error::new_static(
+            "NBT has no 128-bit integer type (see `uuid_int_array` for UUIDs)",
+        ))
+    }
+
     fn serialize_u8(self, _v: u8) -> Result<Self::Ok, Self::Error> {
         unsupported!("u8")
     }
@@ -150,6 +252,12 @@ impl<'a, W: Write + ?Sized> Serializer for &'a mut PayloadSerializer<'_, '_, W>
         unsupported!("u64")
     }
 
+    fn serialize_u128(self, _v: u128) -> Result<Self::Ok, Self::Error> {
+        Err(Error::new_static(
+            "NBT has no 128-bit integer type (see `uuid_int_array` for UUIDs)",
+        ))
+    }
+
     fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
         self.check_state(Tag::Float)?;
         Ok(self.writer.write_f32::<BigEndian>(v)?)
@@ -169,17 +277,34 @@ impl<'a, W: Write + ?Sized> Serializer for &'a mut PayloadSerializer<'_, '_, W>
         write_string(&mut *self.writer, v)
     }
 
-    fn serialize_bytes(self, _v: &[u8]) -> Result<Self::Ok, Self::Error> {
-        unsupported!("&[u8]")
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        if let State::RawCompound = self.state {
+            return Ok(self.writer.write_all(v)?);
+        }
+
+        self.check_state(Tag::ByteArray)?;
+
+        match v.len().try_into() {
+            Ok(len) => self.writer.write_i32::<BigEndian>(len)?,
+            Err(_) => return Err(Error::new_static("length of array exceeds i32::MAX")),
+        }
+
+        Ok(self.writer.write_all(v)?)
     }
 
     fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
-        Ok(())
+        match self.state {
+            // Omit the named tag entirely.
+            State::Named(_) => Ok(()),
+            _ => Err(Error::new_static(
+                "None can only be serialized as a compound entry",
+            )),
+        }
     }
 
-    fn serialize_some<T: ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error>
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
     where
-        T: Serialize,
+        T: Serialize + ?Sized,
     {
         value.serialize(self)
     }
@@ -196,23 +321,32 @@ impl<'a, W: Write + ?Sized> Serializer for &'a mut PayloadSerializer<'_, '_, W>
         self,
         _name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
     ) -> Result<Self::Ok, Self::Error> {
-        unsupported!("unit variant")
+        self.serialize_str(variant)
     }
 
-    fn serialize_newtype_struct<T: ?Sized>(
+    fn serialize_newtype_struct<T>(
         self,
-        _name: &'static str,
-        _value: &T,
+        name: &'static str,
+        value: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
-        T: Serialize,
+        T: Serialize + ?Sized,
     {
-        unsupported!("newtype struct")
+        if name == RAW_COMPOUND_NAME {
+            self.check_state(Tag::Compound)?;
+            value.serialize(&mut PayloadSerializer::raw_compound(
+                &mut *self.writer,
+                self.ctx,
+            ))
+        } else {
+            // Newtype structs are transparent.
+            value.serialize(self)
+        }
     }
 
-    fn serialize_newtype_variant<T: ?Sized>(
+    fn serialize_newtype_variant<T>(
         self,
         name: &'static str,
         _variant_index: u32,
@@ -220,7 +354,7 @@ impl<'a, W: Write + ?Sized> Serializer for &'a mut PayloadSerializer<'_, '_, W>
         value: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
-        T: Serialize,
+        T: Serialize + ?Sized,
     {
         let (array_tag, array_type) = match (name, variant) {
             (crate::ARRAY_ENUM_NAME, crate::BYTE_ARRAY_VARIANT_NAME) => {
@@ -240,14 +374,14 @@ impl<'a, W: Write + ?Sized> Serializer for &'a mut PayloadSerializer<'_, '_, W>
         value.serialize(&mut PayloadSerializer {
             writer: self.writer,
             state: State::Array(array_type),
+            ctx: self.ctx,
         })
     }
 
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
         if let State::Array(array_type) = self.state {
-            let len = match len {
-                Some(len) => len,
-                None => return Err(Error::new_static("array length must be known up front")),
+            let Some(len) = len else {
+                return Ok(SerializeSeq::array(self.writer, self.ctx, array_type, None));
             };
 
             match len.try_into() {
@@ -255,22 +389,23 @@ impl<'a, W: Write + ?Sized> Serializer for &'a mut PayloadSerializer<'_, '_, W>
                     self.writer.write_i32::<BigEndian>(len)?;
                     Ok(SerializeSeq::array(
                         self.writer,
-                        array_type.element_tag(),
-                        len,
+                        self.ctx,
+                        array_type,
+                        Some(len),
                     ))
                 }
                 Err(_) => Err(Error::new_static("length of array exceeds i32::MAX")),
             }
         } else {
+            let ctx = self.ctx.enter()?;
             self.check_state(Tag::List)?;
 
-            let len = match len {
-                Some(len) => len,
-                None => return Err(Error::new_static("list length must be known up front")),
+            let Some(len) = len else {
+                return Ok(SerializeSeq::list(self.writer, ctx, None));
             };
 
             match len.try_into() {
-                Ok(len) => Ok(SerializeSeq::list(self.writer, len)),
+                Ok(len) => Ok(SerializeSeq::list(self.writer, ctx, Some(len))),
                 Err(_) => Err(Error::new_static("length of list exceeds i32::MAX")),
             }
         }
@@ -299,10 +434,12 @@ impl<'a, W: Write + ?Sized> Serializer for &'a mut PayloadSerializer<'_, '_, W>
     }
 
     fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        let ctx = self.ctx.enter()?;
         self.check_state(Tag::Compound)?;
 
         Ok(SerializeMap {
             writer: self.writer,
+            ctx,
         })
     }
 
@@ -311,10 +448,12 @@ impl<'a, W: Write + ?Sized> Serializer for &'a mut PayloadSerializer<'_, '_, W>
         _name: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStruct, Self::Error> {
+        let ctx = self.ctx.enter()?;
         self.check_state(Tag::Compound)?;
 
         Ok(SerializeStruct {
             writer: self.writer,
+            ctx,
         })
     }
 
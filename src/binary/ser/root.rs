@@ -4,9 +4,10 @@ use byteorder::WriteBytesExt;
 use serde::{Serialize, Serializer};
 
 use crate::binary::ser::map::SerializeMap;
+use crate::binary::ser::payload::PayloadSerializer;
 use crate::binary::ser::structs::SerializeStruct;
-use crate::binary::ser::{write_string, Impossible};
-use crate::{Error, Tag};
+use crate::binary::ser::{write_key, BoolTag, Context, Impossible};
+use crate::{Error, Tag, MAX_DEPTH, RAW_COMPOUND_NAME};
 
 /// A serde [`Serializer`] for the binary representation of NBT.
 #[non_exhaustive]
@@ -17,17 +18,41 @@ pub struct RootSerializer<'n, W> {
     ///
     /// The empty string `""` is acceptable.
     pub root_name: &'n str,
+    /// The settings passed down to the rest of the serializer.
+    ctx: Context,
 }
 
 impl<'n, W: Write> RootSerializer<'n, W> {
     /// Constructs a new serializer.
     pub fn new(writer: W, root_name: &'n str) -> Self {
-        Self { writer, root_name }
+        Self {
+            writer,
+            root_name,
+            ctx: Context::new(MAX_DEPTH),
+        }
+    }
+
+    /// Sets the maximum nesting depth of compounds and lists.
+    ///
+    /// The root compound has a depth of one, and every compound or list
+    /// inside of it adds one more. Serialization fails once the limit is
+    /// exceeded instead of overflowing the stack.
+    ///
+    /// The default limit is 512.
+    pub fn set_max_depth(&mut self, max: usize) {
+        self.ctx.max_depth = max;
+    }
+
+    /// Sets the tag type that `bool`s are serialized as.
+    ///
+    /// The default is [`BoolTag::Byte`], which is what Minecraft uses.
+    pub fn set_bool_tag(&mut self, bool_tag: BoolTag) {
+        self.ctx.bool_tag = bool_tag;
     }
 
     fn write_header(&mut self) -> Result<(), Error> {
         self.writer.write_u8(Tag::Compound as u8)?;
-        write_string(&mut self.writer, self.root_name)
+        write_key(&mut self.writer, self.root_name)
     }
 }
 
@@ -112,9 +137,9 @@ impl<'a, W: Write> Serializer for &'a mut RootSerializer<'_, W> {
         not_compound!("None")
     }
 
-    fn serialize_some<T: ?Sized>(self, _value: &T) -> Result<Self::Ok, Self::Error>
+    fn serialize_some<T>(self, _value: &T) -> Result<Self::Ok, Self::Error>
     where
-        T: Serialize,
+        T: Serialize + ?Sized,
     {
         not_compound!("Some")
     }
@@ -136,18 +161,27 @@ impl<'a, W: Write> Serializer for &'a mut RootSerializer<'_, W> {
         not_compound!("unit variant")
     }
 
-    fn serialize_newtype_struct<T: ?Sized>(
+    fn serialize_newtype_struct<T>(
         self,
-        _name: &'static str,
-        _value: &T,
+        name: &'static str,
+        value: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
-        T: Serialize,
+        T: Serialize + ?Sized,
     {
-        not_compound!("newtype struct")
+        if name == RAW_COMPOUND_NAME {
+            self.write_header()?;
+            value.serialize(&mut PayloadSerializer::raw_compound(
+                &mut self.writer,
+                self.ctx,
+            ))
+        } else {
+            // Newtype structs are transparent.
+            value.serialize(self)
+        }
     }
 
-    fn serialize_newtype_variant<T: ?Sized>(
+    fn serialize_newtype_variant<T>(
         self,
         _name: &'static str,
         _variant_index: u32,
@@ -155,7 +189,7 @@ impl<'a, W: Write> Serializer for &'a mut RootSerializer<'_, W> {
         _value: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
-        T: Serialize,
+        T: Serialize + ?Sized,
     {
         not_compound!("newtype variant")
     }
@@ -187,10 +221,12 @@ impl<'a, W: Write> Serializer for &'a mut RootSerializer<'_, W> {
     }
 
     fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        let ctx = self.ctx.enter()?;
         self.write_header()?;
 
         Ok(SerializeMap {
             writer: &mut self.writer,
+            ctx,
         })
     }
 
@@ -199,10 +235,12 @@ impl<'a, W: Write> Serializer for &'a mut RootSerializer<'_, W> {
         _name: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStruct, Self::Error> {
+        let ctx = self.ctx.enter()?;
         self.write_header()?;
 
         Ok(SerializeStruct {
             writer: &mut self.writer,
+            ctx,
         })
     }
 
@@ -4,46 +4,81 @@ use byteorder::{BigEndian, WriteBytesExt};
 use serde::{ser, Serialize};
 
 use crate::binary::ser::payload::PayloadSerializer;
-use crate::{Error, Tag};
+use crate::binary::ser::Context;
+use crate::{ArrayType, Error, Tag};
 
 pub struct SerializeSeq<'w, W: ?Sized> {
     writer: &'w mut W,
+    /// The context of the elements.
+    ctx: Context,
     element_tag: Tag,
     remaining: i32,
     list_or_array: ListOrArray,
+    /// The elements written so far, if the length was not known up front.
+    /// The length is written in front of them once the sequence ends.
+    buffer: Option<Buffer>,
 }
 
 #[derive(Copy, Clone)]
 enum ListOrArray {
     List,
-    Array,
+    Array(ArrayType),
 }
 
 impl ListOrArray {
     pub const fn name(self) -> &'static str {
         match self {
             ListOrArray::List => "list",
-            ListOrArray::Array => "array",
+            ListOrArray::Array(_) => "array",
+        }
+    }
+}
+
+struct Buffer {
+    bytes: Vec<u8>,
+    len: i32,
+}
+
+impl Buffer {
+    fn new(length: Option<i32>) -> Option<Self> {
+        match length {
+            Some(_) => None,
+            None => Some(Self {
+                bytes: Vec::new(),
+                len: 0,
+            }),
         }
     }
 }
 
 impl<'w, W: Write + ?Sized> SerializeSeq<'w, W> {
-    pub(super) fn list(writer: &'w mut W, length: i32) -> Self {
+    /// Starts a list. If the length is not known, the elements are buffered.
+    pub(super) fn list(writer: &'w mut W, ctx: Context, length: Option<i32>) -> Self {
         Self {
             writer,
+            ctx,
             element_tag: Tag::End,
-            remaining: length,
+            remaining: length.unwrap_or(0),
             list_or_array: ListOrArray::List,
+            buffer: Buffer::new(length),
         }
     }
 
-    pub(super) fn array(writer: &'w mut W, element_tag: Tag, length: i32) -> Self {
+    /// Starts an array. If the length is known, it must already have been
+    /// written. Otherwise, the elements are buffered.
+    pub(super) fn array(
+        writer: &'w mut W,
+        ctx: Context,
+        array_type: ArrayType,
+        length: Option<i32>,
+    ) -> Self {
         Self {
             writer,
-            element_tag,
-            remaining: length,
-            list_or_array: ListOrArray::Array,
+            ctx,
+            element_tag: array_type.element_tag(),
+            remaining: length.unwrap_or(0),
+            list_or_array: ListOrArray::Array(array_type),
+            buffer: Buffer::new(length),
         }
     }
 }
@@ -52,42 +87,53 @@ impl<W: Write + ?Sized> ser::SerializeSeq for SerializeSeq<'_, W> {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: Serialize + ?Sized,
     {
-        if self.remaining <= 0 {
-            return Err(Error::new_owned(format!(
-                "attempt to serialize more {} elements than specified",
-                self.list_or_array.name()
-            )));
-        }
-
-        match self.list_or_array {
-            ListOrArray::List => {
-                if self.element_tag == Tag::End {
-                    let mut ser =
-                        PayloadSerializer::first_list_element(self.writer, self.remaining);
-
-                    value.serialize(&mut ser)?;
-
-                    self.element_tag = ser.written_tag().expect("tag must have been written");
-                } else {
-                    value.serialize(&mut PayloadSerializer::seq_element(
-                        self.writer,
-                        self.element_tag,
-                    ))?;
+        match &mut self.buffer {
+            Some(buf) => {
+                if buf.len == i32::MAX {
+                    return Err(Error::new_owned(format!(
+                        "length of {} exceeds i32::MAX",
+                        self.list_or_array.name()
+                    )));
                 }
+
+                // The length written before the first list element is a
+                // placeholder until the sequence ends.
+                write_element(
+                    &mut buf.bytes,
+                    self.ctx,
+                    self.list_or_array,
+                    &mut self.element_tag,
+                    0,
+                    value,
+                )?;
+
+                buf.len += 1;
             }
-            ListOrArray::Array => {
-                value.serialize(&mut PayloadSerializer::seq_element(
+            None => {
+                if self.remaining <= 0 {
+                    return Err(Error::new_owned(format!(
+                        "attempt to serialize more {} elements than specified",
+                        self.list_or_array.name()
+                    )));
+                }
+
+                write_element(
                     self.writer,
-                    self.element_tag,
-                ))?;
+                    self.ctx,
+                    self.list_or_array,
+                    &mut self.element_tag,
+                    self.remaining,
+                    value,
+                )?;
+
+                self.remaining -= 1;
             }
         }
 
-        self.remaining -= 1;
         Ok(())
     }
 
@@ -108,13 +154,63 @@ impl<W: Write + ?Sized> ser::SerializeSeq for SerializeSeq<'_, W> {
                     self.writer.write_u8(Tag::End as u8)?;
                     // List length.
                     self.writer.write_i32::<BigEndian>(0)?;
+                } else if let Some(mut buf) = self.buffer {
+                    // Fill in the length after the element type.
+                    buf.bytes[1..5].copy_from_slice(&buf.len.to_be_bytes());
+                    self.writer.write_all(&buf.bytes)?;
                 }
             }
-            ListOrArray::Array => {
-                // Array length should be written by the serializer already.
+            ListOrArray::Array(_) => {
+                if let Some(buf) = self.buffer {
+                    self.writer.write_i32::<BigEndian>(buf.len)?;
+                    self.writer.write_all(&buf.bytes)?;
+                }
+
+                // Otherwise, the array length was written by the serializer
+                // already.
             }
         }
 
         Ok(())
     }
 }
+
+/// Writes one element of a list or array. If this is the first element of a
+/// list, its tag and the length of the list are written first.
+fn write_element<W, T>(
+    writer: &mut W,
+    ctx: Context,
+    list_or_array: ListOrArray,
+    element_tag: &mut Tag,
+    length: i32,
+    value: &T,
+) -> Result<(), Error>
+where
+    W: Write + ?Sized,
+    T: Serialize + ?Sized,
+{
+    match list_or_array {
+        ListOrArray::List => {
+            if *element_tag == Tag::End {
+                let mut ser = PayloadSerializer::first_list_element(writer, ctx, length);
+
+                value.serialize(&mut ser)?;
+
+                *element_tag = ser.written_tag().expect("tag must have been written");
+            } else {
+                value.serialize(&mut PayloadSerializer::seq_element(
+                    writer,
+                    ctx,
+                    *element_tag,
+                ))?;
+            }
+        }
+        ListOrArray::Array(array_type) => {
+            value.serialize(&mut PayloadSerializer::array_element(
+                writer, ctx, array_type,
+            ))?;
+        }
+    }
+
+    Ok(())
+}
@@ -4,26 +4,25 @@ use byteorder::WriteBytesExt;
 use serde::{ser, Serialize};
 
 use crate::binary::ser::payload::PayloadSerializer;
+use crate::binary::ser::Context;
 use crate::{Error, Tag};
 
 pub struct SerializeStruct<'w, W: ?Sized> {
     pub(super) writer: &'w mut W,
+    /// The context of the fields of the struct.
+    pub(super) ctx: Context,
 }
 
 impl<W: Write + ?Sized> ser::SerializeStruct for SerializeStruct<'_, W> {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(
-        &mut self,
-        key: &'static str,
-        value: &T,
-    ) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: Serialize + ?Sized,
     {
         value
-            .serialize(&mut PayloadSerializer::named(self.writer, key))
+            .serialize(&mut PayloadSerializer::named(self.writer, key, self.ctx))
             .map_err(|e| e.field(key))
     }
 
@@ -0,0 +1,94 @@
+use crate::{Compound, List, Result, Value};
+
+impl Value {
+    /// Encodes this value in a deterministic binary form suitable for hashing
+    /// or signing.
+    ///
+    /// The encoding is the binary NBT representation of this value as a tag
+    /// with an empty name, with the following normalizations applied:
+    ///
+    /// - Compound entries are sorted by key.
+    /// - All NaN floats are replaced with the same NaN bit pattern.
+    /// - Negative zero is replaced with positive zero.
+    ///
+    /// As a result, two values that compare equal produce identical bytes, no
+    /// matter the order in which compound entries were inserted. For a
+    /// [`Value::Compound`], the output is a valid NBT document with an empty
+    /// root name.
+    ///
+    /// An error is returned if the value cannot be encoded, for instance if a
+    /// string is too long.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::{Compound, Value};
+    ///
+    /// let a = Value::Compound(Compound::from_iter([
+    ///     ("a".to_owned(), Value::Int(1)),
+    ///     ("b".to_owned(), Value::Int(2)),
+    /// ]));
+    ///
+    /// let b = Value::Compound(Compound::from_iter([
+    ///     ("b".to_owned(), Value::Int(2)),
+    ///     ("a".to_owned(), Value::Int(1)),
+    /// ]));
+    ///
+    /// assert_eq!(a.canonicalize().unwrap(), b.canonicalize().unwrap());
+    /// ```
+    pub fn canonicalize(&self) -> Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        crate::binary::write_named(&mut buf, "", &canonical_value(self))?;
+        Ok(buf)
+    }
+}
+
+fn canonical_value(value: &Value) -> Value {
+    match value {
+        Value::Float(f) => Value::Float(canonical_f32(*f)),
+        Value::Double(f) => Value::Double(canonical_f64(*f)),
+        Value::List(l) => Value::List(canonical_list(l)),
+        Value::Compound(c) => Value::Compound(canonical_compound(c)),
+        v => v.clone(),
+    }
+}
+
+fn canonical_list(list: &List) -> List {
+    match list {
+        List::Float(l) => List::Float(l.iter().copied().map(canonical_f32).collect()),
+        List::Double(l) => List::Double(l.iter().copied().map(canonical_f64).collect()),
+        List::List(l) => List::List(l.iter().map(canonical_list).collect()),
+        List::Compound(l) => List::Compound(l.iter().map(canonical_compound).collect()),
+        l => l.clone(),
+    }
+}
+
+fn canonical_compound(compound: &Compound) -> Compound {
+    let mut res: Compound = compound
+        .iter()
+        .map(|(k, v)| (k.clone(), canonical_value(v)))
+        .collect();
+
+    res.sort_keys();
+    res
+}
+
+fn canonical_f32(f: f32) -> f32 {
+    if f.is_nan() {
+        f32::NAN
+    } else if f == 0.0 {
+        0.0
+    } else {
+        f
+    }
+}
+
+fn canonical_f64(f: f64) -> f64 {
+    if f.is_nan() {
+        f64::NAN
+    } else if f == 0.0 {
+        0.0
+    } else {
+        f
+    }
+}
@@ -0,0 +1,170 @@
+//! Helpers for the block state data stored in Anvil chunk sections.
+//!
+//! The block states of a chunk section are stored as a palette of block
+//! states and a [`Value::LongArray`](crate::Value) of indices into that
+//! palette. Each index occupies a fixed number of bits which depends on the
+//! length of the palette. How the indices are laid out in the longs changed in
+//! Minecraft 1.16, as described by [`Packing`]. The functions in this module
+//! convert between the packed longs and a plain list of palette indices.
+//!
+//! # Examples
+//!
+//! ```
+//! use serde_nbt::chunk::{decode_block_states, encode_block_states, Packing, SECTION_VOLUME};
+//!
+//! let indices: Vec<u16> = (0..SECTION_VOLUME).map(|i| (i % 20) as u16).collect();
+//!
+//! let packed = encode_block_states(&indices, 20, Packing::Aligned).unwrap();
+//! assert_eq!(
+//!     decode_block_states(&packed, 20, Packing::Aligned).unwrap(),
+//!     indices
+//! );
+//! ```
+
+use crate::{Error, Result};
+
+/// The number of block states in a chunk section.
+pub const SECTION_VOLUME: usize = 16 * 16 * 16;
+
+/// The layout of palette indices in the packed longs.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Packing {
+    /// Indices are packed back to back, so an index may be split across two
+    /// longs. Used before Minecraft 1.16.
+    Spanning,
+    /// Each long holds as many whole indices as fit, and the remaining high
+    /// bits are unused. Used since Minecraft 1.16.
+    Aligned,
+}
+
+/// Returns the number of bits used to store each palette index for a palette
+/// of the given length.
+///
+/// This is the number of bits needed to represent the largest index, but no
+/// less than four.
+pub fn bits_per_block(palette_len: usize) -> u32 {
+    let bits = usize::BITS - palette_len.saturating_sub(1).leading_zeros();
+    bits.max(4)
+}
+
+/// Returns the number of longs needed to store the block states of a section
+/// with a palette of the given length.
+pub fn packed_len(palette_len: usize, packing: Packing) -> usize {
+    let bits = bits_per_block(palette_len) as usize;
+
+    match packing {
+        Packing::Spanning => (SECTION_VOLUME * bits).div_ceil(64),
+        Packing::Aligned => SECTION_VOLUME.div_ceil(64 / bits),
+    }
+}
+
+fn out_of_bounds(idx: u64, palette_len: usize) -> Error {
+    Error::new_owned(format!(
+        "block state index {idx} is out of bounds for a palette of length {palette_len}"
+    ))
+}
+
+/// Unpacks the palette indices of a chunk section.
+///
+/// Returns [`SECTION_VOLUME`] indices in the order they are stored (`y`, then
+/// `z`, then `x`). An error is returned if `data` does not have the expected
+/// length for the palette or if an index is out of bounds for the palette.
+pub fn decode_block_states(data: &[i64], palette_len: usize, packing: Packing) -> Result<Vec<u16>> {
+    if palette_len > u16::MAX as usize + 1 {
+        return Err(Error::new_owned(format!(
+            "palette length {palette_len} is too large"
+        )));
+    }
+
+    let expected_len = packed_len(palette_len, packing);
+
+    if data.len() != expected_len {
+        return Err(Error::new_owned(format!(
+            "expected {expected_len} longs of block state data for a palette of length \
+             {palette_len}, got {}",
+            data.len()
+        )));
+    }
+
+    let bits = bits_per_block(palette_len) as usize;
+    let per_long = 64 / bits;
+    let mask = (1_u64 << bits) - 1;
+
+    let mut indices = Vec::with_capacity(SECTION_VOLUME);
+
+    for i in 0..SECTION_VOLUME {
+        let idx = match packing {
+            Packing::Spanning => {
+                let start = i * bits;
+                let (long, offset) = (start / 64, start % 64);
+
+                let mut idx = data[long] as u64 >> offset;
+                if offset + bits > 64 {
+                    idx |= (data[long + 1] as u64) << (64 - offset);
+                }
+                idx & mask
+            }
+            Packing::Aligned => {
+                let (long, offset) = (i / per_long, i % per_long * bits);
+                (data[long] as u64 >> offset) & mask
+            }
+        };
+
+        if idx as usize >= palette_len {
+            return Err(out_of_bounds(idx, palette_len));
+        }
+
+        indices.push(idx as u16);
+    }
+
+    Ok(indices)
+}
+
+/// Packs the palette indices of a chunk section.
+///
+/// This is the inverse of [`decode_block_states`]. An error is returned if
+/// there are not exactly [`SECTION_VOLUME`] indices or if an index is out of
+/// bounds for the palette.
+pub fn encode_block_states(
+    indices: &[u16],
+    palette_len: usize,
+    packing: Packing,
+) -> Result<Vec<i64>> {
+    if indices.len() != SECTION_VOLUME {
+        return Err(Error::new_owned(format!(
+            "expected {SECTION_VOLUME} block state indices, got {}",
+            indices.len()
+        )));
+    }
+
+    let bits = bits_per_block(palette_len) as usize;
+    let per_long = 64 / bits;
+
+    let mut data = vec![0_u64; packed_len(palette_len, packing)];
+
+    for (i, &idx) in indices.iter().enumerate() {
+        if idx as usize >= palette_len {
+            return Err(out_of_bounds(idx as u64, palette_len));
+        }
+
+        let idx = idx as u64;
+
+        match packing {
+            Packing::Spanning => {
+                let start = i * bits;
+                let (long, offset) = (start / 64, start % 64);
+
+                data[long] |= idx << offset;
+                if offset + bits > 64 {
+                    data[long + 1] |= idx >> (64 - offset);
+                }
+            }
+            Packing::Aligned => {
+                let (long, offset) = (i / per_long, i % per_long * bits);
+                data[long] |= idx << offset;
+            }
+        }
+    }
+
+    Ok(data.into_iter().map(|l| l as i64).collect())
+}
@@ -0,0 +1,428 @@
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::Hash;
+use std::ops::{Deref, DerefMut, Index, IndexMut};
+
+use indexmap::map::{Entry, IntoIter, Iter, IterMut};
+use indexmap::IndexMap;
+use serde::de::{MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::Value;
+
+/// An arbitrary NBT compound.
+///
+/// A compound is a map from string keys to [`Value`]s. Entries are kept in
+/// the order they were inserted.
+///
+/// `Compound` dereferences to the [`IndexMap`] that stores its entries, so
+/// all of the map's methods, such as [`IndexMap::sort_keys`],
+/// [`IndexMap::get_index`], and [`IndexMap::drain`], can be called on a
+/// compound directly. The map can also be accessed with [`Self::as_map`] and
+/// [`Self::into_map`].
+///
+/// Equality does not depend on the order of entries. Two compounds are equal
+/// if they contain the same keys mapped to equal values. Likewise, compounds
+/// are ordered by their entries sorted by key. See [`Value`] for details on
+/// how values are compared.
+#[derive(Clone, PartialEq, Default)]
+pub struct Compound {
+    map: IndexMap<String, Value>,
+}
+
+impl Compound {
+    /// Creates an empty compound.
+    pub fn new() -> Self {
+        Self {
+            map: IndexMap::new(),
+        }
+    }
+
+    /// Creates an empty compound with space for at least `capacity` entries.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            map: IndexMap::with_capacity(capacity),
+        }
+    }
+
+    /// Returns a reference to the underlying map.
+    pub fn as_map(&self) -> &IndexMap<String, Value> {
+        &self.map
+    }
+
+    /// Returns a mutable reference to the underlying map.
+    pub fn as_map_mut(&mut self) -> &mut IndexMap<String, Value> {
+        &mut self.map
+    }
+
+    /// Converts this compound into the underlying map.
+    pub fn into_map(self) -> IndexMap<String, Value> {
+        self.map
+    }
+
+    /// Converts this compound into a [`Value::Compound`].
+    ///
+    /// This is the same as `Value::from(compound)`, but reads better at the
+    /// end of a chain of method calls.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::{Compound, Value};
+    ///
+    /// let mut compound = Compound::new();
+    /// compound.insert("a".to_owned(), Value::Int(1));
+    ///
+    /// let value = compound.into_value();
+    ///
+    /// assert_eq!(value.as_compound().unwrap()["a"], Value::Int(1));
+    /// ```
+    pub fn into_value(self) -> Value {
+        Value::Compound(self)
+    }
+
+    /// Removes an entry from the compound, returning its value if the key was
+    /// present.
+    ///
+    /// Like [`IndexMap::remove`], this is the same as
+    /// [`IndexMap::swap_remove`]: the last entry is moved into the position of
+    /// the removed one, which changes the order of the compound. Use
+    /// [`IndexMap::shift_remove`] to preserve the order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::{Compound, Value};
+    ///
+    /// let mut compound = Compound::from_iter([
+    ///     ("a".to_owned(), Value::Int(1)),
+    ///     ("b".to_owned(), Value::Int(2)),
+    ///     ("c".to_owned(), Value::Int(3)),
+    /// ]);
+    ///
+    /// assert_eq!(compound.remove("a"), Some(Value::Int(1)));
+    /// assert!(compound.keys().eq(["c", "b"]));
+    ///
+    /// assert_eq!(compound.shift_remove("c"), Some(Value::Int(3)));
+    /// assert!(compound.keys().eq(["b"]));
+    /// ```
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<Value>
+    where
+        String: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.swap_remove(key)
+    }
+
+    /// Changes the key of an entry, returning `true` if an entry with the key
+    /// `from` was present.
+    ///
+    /// The entry keeps its position. If another entry already has the key
+    /// `to`, it is replaced.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::{Compound, Value};
+    ///
+    /// let mut entity = Compound::from_iter([
+    ///     ("id".to_owned(), Value::String("minecraft:pig".to_owned())),
+    ///     ("Riding".to_owned(), Value::Compound(Compound::new())),
+    ///     ("Health".to_owned(), Value::Float(10.0)),
+    /// ]);
+    ///
+    /// assert!(entity.rename_key("Riding", "Passengers"));
+    /// assert!(!entity.rename_key("Riding", "Passengers"));
+    ///
+    /// assert_eq!(
+    ///     entity.keys().collect::<Vec<_>>(),
+    ///     ["id", "Passengers", "Health"]
+    /// );
+    /// ```
+    pub fn rename_key<K>(&mut self, from: &str, to: K) -> bool
+    where
+        K: Into<String>,
+    {
+        let Some((mut index, _, value)) = self.map.shift_remove_full(from) else {
+            return false;
+        };
+
+        let to = to.into();
+
+        if let Some((other, _, _)) = self.map.shift_remove_full(&to) {
+            if other < index {
+                index -= 1;
+            }
+        }
+
+        let (end, _) = self.map.insert_full(to, value);
+        self.map.move_index(end, index);
+
+        true
+    }
+
+    /// Recursively removes entries whose values are empty compounds, lists,
+    /// or arrays.
+    ///
+    /// Containers are pruned before they are checked, so an entry is also
+    /// removed if it only contained empty containers. Elements of lists are
+    /// pruned in the same way. See [`Value::prune_empty`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::{Compound, List, Value};
+    ///
+    /// let mut item = Compound::from_iter([
+    ///     ("id".to_owned(), Value::String("minecraft:stick".to_owned())),
+    ///     (
+    ///         "tag".to_owned(),
+    ///         Value::Compound(Compound::from_iter([(
+    ///             "Enchantments".to_owned(),
+    ///             List::Compound(vec![]).into(),
+    ///         )])),
+    ///     ),
+    /// ]);
+    ///
+    /// item.prune_empty();
+    ///
+    /// assert_eq!(item.keys().collect::<Vec<_>>(), ["id"]);
+    /// ```
+    pub fn prune_empty(&mut self) {
+        self.retain(|_, v| {
+            v.prune_empty();
+            !v.is_empty()
+        });
+    }
+
+    /// Gets the entry for the given key for in-place manipulation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::{Compound, Value};
+    ///
+    /// let mut compound = Compound::new();
+    ///
+    /// for _ in 0..3 {
+    ///     if let Value::Int(count) = compound.entry("count").or_insert(0.into()) {
+    ///         *count += 1;
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(compound["count"], Value::Int(3));
+    /// ```
+    pub fn entry<K>(&mut self, key: K) -> Entry<'_, String, Value>
+    where
+        K: Into<String>,
+    {
+        self.map.entry(key.into())
+    }
+
+    /// Returns a mutable reference to the value of the key, inserting the
+    /// result of `f` first if the key is absent.
+    ///
+    /// `f` is only called if the key is absent. The new entry is placed at
+    /// the end of the compound.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::{Compound, Value};
+    ///
+    /// let mut root = Compound::new();
+    ///
+    /// for name in ["a", "b"] {
+    ///     let data = root.get_or_insert_with("Data", || Compound::new().into());
+    ///
+    ///     if let Value::Compound(data) = data {
+    ///         data.insert(name.to_owned(), Value::Byte(1));
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(root["Data"].as_compound().unwrap().len(), 2);
+    /// ```
+    pub fn get_or_insert_with<K, F>(&mut self, key: K, f: F) -> &mut Value
+    where
+        K: Into<String>,
+        F: FnOnce() -> Value,
+    {
+        self.map.entry(key.into()).or_insert_with(f)
+    }
+
+    /// Returns an iterator over the entries of the compound, in order, with
+    /// the keys as `&str` and mutable references to the values.
+    ///
+    /// Every value can be modified during iteration without collecting the
+    /// keys first. The keys themselves cannot be changed.
+    pub fn entries_mut(&mut self) -> impl Iterator<Item = (&str, &mut Value)> {
+        self.map.iter_mut().map(|(k, v)| (k.as_str(), v))
+    }
+}
+
+impl fmt::Debug for Compound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.map.fmt(f)
+    }
+}
+
+impl Deref for Compound {
+    type Target = IndexMap<String, Value>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+impl DerefMut for Compound {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.map
+    }
+}
+
+impl From<IndexMap<String, Value>> for Compound {
+    fn from(map: IndexMap<String, Value>) -> Self {
+        Self { map }
+    }
+}
+
+impl From<Compound> for IndexMap<String, Value> {
+    fn from(c: Compound) -> Self {
+        c.map
+    }
+}
+
+impl FromIterator<(String, Value)> for Compound {
+    fn from_iter<T: IntoIterator<Item = (String, Value)>>(iter: T) -> Self {
+        Self {
+            map: IndexMap::from_iter(iter),
+        }
+    }
+}
+
+/// Inserts entries from an iterator, with the same behavior as
+/// [`IndexMap::insert`].
+///
+/// The keys may be any type convertible to a `String`, so string literals
+/// can be used directly.
+///
+/// # Examples
+///
+/// ```
+/// use serde_nbt::{Compound, Value};
+///
+/// let mut compound = Compound::new();
+/// compound.extend([("a", 1.into()), ("b", "two".into())]);
+///
+/// assert_eq!(compound["a"], Value::Int(1));
+/// assert_eq!(compound["b"], Value::String("two".to_owned()));
+/// ```
+impl<K> Extend<(K, Value)> for Compound
+where
+    K: Into<String>,
+{
+    fn extend<T: IntoIterator<Item = (K, Value)>>(&mut self, iter: T) {
+        self.map
+            .extend(iter.into_iter().map(|(k, v)| (k.into(), v)))
+    }
+}
+
+impl IntoIterator for Compound {
+    type Item = (String, Value);
+    type IntoIter = IntoIter<String, Value>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Compound {
+    type Item = (&'a String, &'a Value);
+    type IntoIter = Iter<'a, String, Value>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut Compound {
+    type Item = (&'a String, &'a mut Value);
+    type IntoIter = IterMut<'a, String, Value>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.iter_mut()
+    }
+}
+
+impl<Q> Index<&'_ Q> for Compound
+where
+    String: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+{
+    type Output = Value;
+
+    /// Returns a reference to the value corresponding to the key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is not present in the compound.
+    fn index(&self, key: &Q) -> &Value {
+        &self.map[key]
+    }
+}
+
+impl<Q> IndexMut<&'_ Q> for Compound
+where
+    String: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+{
+    /// Returns a mutable reference to the value corresponding to the key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is not present in the compound.
+    fn index_mut(&mut self, key: &Q) -> &mut Value {
+        &mut self.map[key]
+    }
+}
+
+impl Serialize for Compound {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_map(&self.map)
+    }
+}
+
+impl<'de> Deserialize<'de> for Compound {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(CompoundVisitor)
+    }
+}
+
+pub(crate) struct CompoundVisitor;
+
+impl<'de> Visitor<'de> for CompoundVisitor {
+    type Value = Compound;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "an NBT compound")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut compound = Compound::with_capacity(map.size_hint().unwrap_or(0).min(256));
+
+        while let Some((k, v)) = map.next_entry::<String, Value>()? {
+            compound.insert(k, v);
+        }
+
+        Ok(compound)
+    }
+}
@@ -0,0 +1,187 @@
+use std::fmt;
+use std::fmt::Debug;
+
+use crate::{Compound, List, Value};
+
+/// A view of a [`Value`] whose [`Debug`] output is limited in size, as
+/// returned by [`Value::debug_truncated`].
+#[derive(Clone, Copy)]
+pub struct DebugTruncated<'a> {
+    value: &'a Value,
+    max_elems: usize,
+}
+
+impl Value {
+    /// Returns a view of this value with a bounded [`Debug`] representation.
+    ///
+    /// The output has the same form as the `Debug` output of `Value`, but
+    /// only the first `max_elems` elements of every list and array and the
+    /// first `max_elems` characters of every string value are shown. The rest
+    /// are summarized as `…(N more)`. This keeps large values such as chunks from
+    /// flooding logs and terminals.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::Value;
+    ///
+    /// let value = Value::LongArray((0..1000).collect());
+    ///
+    /// assert_eq!(
+    ///     format!("{:?}", value.debug_truncated(3)),
+    ///     "LongArray([0, 1, 2, …(997 more)])"
+    /// );
+    /// ```
+    pub fn debug_truncated(&self, max_elems: usize) -> DebugTruncated<'_> {
+        DebugTruncated {
+            value: self,
+            max_elems,
+        }
+    }
+}
+
+impl Debug for DebugTruncated<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let max = self.max_elems;
+
+        match self.value {
+            Value::Byte(v) => f.debug_tuple("Byte").field(v).finish(),
+            Value::Short(v) => f.debug_tuple("Short").field(v).finish(),
+            Value::Int(v) => f.debug_tuple("Int").field(v).finish(),
+            Value::Long(v) => f.debug_tuple("Long").field(v).finish(),
+            Value::Float(v) => f.debug_tuple("Float").field(v).finish(),
+            Value::Double(v) => f.debug_tuple("Double").field(v).finish(),
+            Value::ByteArray(v) => f
+                .debug_tuple("ByteArray")
+                .field(&elements(v, max, |v| v))
+                .finish(),
+            Value::String(v) => f.debug_tuple("String").field(&string(v, max)).finish(),
+            Value::List(v) => f.debug_tuple("List").field(&list(v, max)).finish(),
+            Value::Compound(v) => f.debug_tuple("Compound").field(&compound(v, max)).finish(),
+            Value::IntArray(v) => f
+                .debug_tuple("IntArray")
+                .field(&elements(v, max, |v| v))
+                .finish(),
+            Value::LongArray(v) => f
+                .debug_tuple("LongArray")
+                .field(&elements(v, max, |v| v))
+                .finish(),
+        }
+    }
+}
+
+/// Implements `Debug` with a closure.
+struct Fmt<F>(F);
+
+impl<F> Debug for Fmt<F>
+where
+    F: Fn(&mut fmt::Formatter<'_>) -> fmt::Result,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (self.0)(f)
+    }
+}
+
+/// The summary of the elements which were left out.
+struct More(usize);
+
+impl Debug for More {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "…({} more)", self.0)
+    }
+}
+
+fn string(s: &str, max: usize) -> impl Debug + '_ {
+    Fmt(
+        move |f: &mut fmt::Formatter<'_>| match s.char_indices().nth(max) {
+            Some((end, _)) => {
+                let rest = s[end..].chars().count();
+                write!(f, "{:?}{:?}", &s[..end], More(rest))
+            }
+            None => Debug::fmt(s, f),
+        },
+    )
+}
+
+fn compound(c: &Compound, max: usize) -> impl Debug + '_ {
+    Fmt(move |f: &mut fmt::Formatter<'_>| {
+        f.debug_map()
+            .entries(c.iter().map(|(k, v)| (k, v.debug_truncated(max))))
+            .finish()
+    })
+}
+
+fn list(l: &List, max: usize) -> impl Debug + '_ {
+    Fmt(move |f: &mut fmt::Formatter<'_>| match l {
+        List::Byte(v) => f
+            .debug_tuple("Byte")
+            .field(&elements(v, max, |v| v))
+            .finish(),
+        List::Short(v) => f
+            .debug_tuple("Short")
+            .field(&elements(v, max, |v| v))
+            .finish(),
+        List::Int(v) => f
+            .debug_tuple("Int")
+            .field(&elements(v, max, |v| v))
+            .finish(),
+        List::Long(v) => f
+            .debug_tuple("Long")
+            .field(&elements(v, max, |v| v))
+            .finish(),
+        List::Float(v) => f
+            .debug_tuple("Float")
+            .field(&elements(v, max, |v| v))
+            .finish(),
+        List::Double(v) => f
+            .debug_tuple("Double")
+            .field(&elements(v, max, |v| v))
+            .finish(),
+        List::ByteArray(v) => f
+            .debug_tuple("ByteArray")
+            .field(&elements(v, max, |v| elements(v, max, |v| v)))
+            .finish(),
+        List::String(v) => f
+            .debug_tuple("String")
+            .field(&elements(v, max, |v| string(v, max)))
+            .finish(),
+        List::List(v) => f
+            .debug_tuple("List")
+            .field(&elements(v, max, |v| list(v, max)))
+            .finish(),
+        List::Compound(v) => f
+            .debug_tuple("Compound")
+            .field(&elements(v, max, |v| compound(v, max)))
+            .finish(),
+        List::IntArray(v) => f
+            .debug_tuple("IntArray")
+            .field(&elements(v, max, |v| elements(v, max, |v| v)))
+            .finish(),
+        List::LongArray(v) => f
+            .debug_tuple("LongArray")
+            .field(&elements(v, max, |v| elements(v, max, |v| v)))
+            .finish(),
+    })
+}
+
+/// Formats the first `max` items as a list, followed by a summary of the
+/// rest.
+fn elements<'a, T, D>(
+    items: &'a [T],
+    max: usize,
+    debug: impl Fn(&'a T) -> D + 'a,
+) -> impl Debug + 'a
+where
+    D: Debug,
+{
+    Fmt(move |f: &mut fmt::Formatter<'_>| {
+        let mut list = f.debug_list();
+        list.entries(items.iter().take(max).map(&debug));
+
+        if items.len() > max {
+            list.entry(&More(items.len() - max));
+        }
+
+        list.finish()
+    })
+}
@@ -0,0 +1,131 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{Compound, Error, Result, Value};
+
+/// The difference between two NBT compounds.
+///
+/// A diff is created with [`Value::diff`] and applied with
+/// [`Value::apply_diff`]. Applying the diff of `old` and `new` to `old`
+/// produces `new`. This makes it possible to synchronize NBT data by sending
+/// only what has changed.
+///
+/// Compounds present on both sides are diffed recursively. Every other value
+/// is compared as a whole, so a value whose type changed or a list whose
+/// elements were reordered is replaced entirely.
+///
+/// `CompoundDiff` implements [`Serialize`] and [`Deserialize`], so it can be
+/// encoded as NBT itself.
+#[derive(Clone, PartialEq, Default, Debug, Serialize, Deserialize)]
+pub struct CompoundDiff {
+    /// Entries that were added or replaced.
+    pub set: Compound,
+    /// Keys that were removed.
+    pub removed: Vec<String>,
+    /// Nested compounds that were modified.
+    pub nested: IndexMap<String, CompoundDiff>,
+}
+
+impl CompoundDiff {
+    /// Returns `true` if this diff contains no changes.
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty() && self.removed.is_empty() && self.nested.is_empty()
+    }
+
+    fn new(old: &Compound, new: &Compound) -> Self {
+        let mut diff = Self::default();
+
+        for key in old.keys() {
+            if !new.contains_key(key) {
+                diff.removed.push(key.clone());
+            }
+        }
+
+        for (key, new_value) in new {
+            match (old.get(key), new_value) {
+                (Some(Value::Compound(old_c)), Value::Compound(new_c)) => {
+                    let nested = Self::new(old_c, new_c);
+                    if !nested.is_empty() {
+                        diff.nested.insert(key.clone(), nested);
+                    }
+                }
+                (Some(old_value), new_value) if old_value == new_value => {}
+                _ => {
+                    diff.set.insert(key.clone(), new_value.clone());
+                }
+            }
+        }
+
+        diff
+    }
+
+    fn apply(&self, compound: &mut Compound) -> Result<()> {
+        for key in &self.removed {
+            compound.shift_remove(key);
+        }
+
+        for (key, value) in &self.set {
+            compound.insert(key.clone(), value.clone());
+        }
+
+        for (key, nested) in &self.nested {
+            match compound.get_mut(key) {
+                Some(Value::Compound(c)) => nested.apply(c).map_err(|e| e.field(key.clone()))?,
+                _ => {
+                    return Err(Error::new_static("diff expects a compound").field(key.clone()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Value {
+    /// Computes the difference between two compound values.
+    ///
+    /// Returns `None` if either value is not a [`Value::Compound`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::{Compound, Value};
+    ///
+    /// let old = Value::Compound(Compound::from_iter([
+    ///     ("health".to_owned(), Value::Float(20.0)),
+    ///     ("name".to_owned(), Value::String("Steve".to_owned())),
+    /// ]));
+    ///
+    /// let mut new = old.clone();
+    /// if let Value::Compound(c) = &mut new {
+    ///     c.insert("health".to_owned(), Value::Float(15.0));
+    /// }
+    ///
+    /// let diff = Value::diff(&old, &new).unwrap();
+    ///
+    /// assert_eq!(diff.set.len(), 1);
+    ///
+    /// let mut patched = old.clone();
+    /// patched.apply_diff(&diff).unwrap();
+    ///
+    /// assert_eq!(patched, new);
+    /// ```
+    pub fn diff(old: &Value, new: &Value) -> Option<CompoundDiff> {
+        match (old, new) {
+            (Value::Compound(old), Value::Compound(new)) => Some(CompoundDiff::new(old, new)),
+            _ => None,
+        }
+    }
+
+    /// Applies a diff created with [`Value::diff`] to this value.
+    ///
+    /// An error is returned if this value or any of the nested compounds
+    /// modified by the diff is not a compound. In that case, this value may
+    /// have been partially modified.
+    pub fn apply_diff(&mut self, diff: &CompoundDiff) -> Result<()> {
+        match self {
+            Value::Compound(c) => diff.apply(c),
+            _ => Err(Error::new_static("diff expects a compound")),
+        }
+    }
+}
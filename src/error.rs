@@ -18,10 +18,25 @@ pub struct Error {
 
 #[derive(Debug)]
 struct ErrorInner {
-    trace: Vec<String>,
+    trace: Vec<Segment>,
     cause: Cause,
 }
 
+#[derive(Debug)]
+enum Segment {
+    Field(String),
+    /// Formatted as `[N]`.
+    Index(String),
+}
+
+impl Segment {
+    fn as_str(&self) -> &str {
+        match self {
+            Segment::Field(s) | Segment::Index(s) => s,
+        }
+    }
+}
+
 #[derive(Debug)]
 enum Cause {
     Io(io::Error),
@@ -50,36 +65,82 @@ impl Error {
     }
 
     pub(crate) fn field(mut self, ctx: impl Into<String>) -> Self {
-        self.inner.trace.push(ctx.into());
+        self.inner.trace.push(Segment::Field(ctx.into()));
         self
     }
 
+    pub(crate) fn index(mut self, idx: usize) -> Self {
+        self.inner.trace.push(Segment::Index(format!("[{idx}]")));
+        self
+    }
+
+    /// Replaces the cause of this error with the cause of `other`, keeping
+    /// the trace of this error.
+    pub(crate) fn replace_cause(mut self, other: Error) -> Self {
+        self.inner.cause = other.inner.cause;
+        self
+    }
+
+    /// Returns `true` if the error was caused by the data ending early.
+    pub(crate) fn is_eof(&self) -> bool {
+        matches!(&self.inner.cause, Cause::Io(e) if e.kind() == io::ErrorKind::UnexpectedEof)
+    }
+
     /// Returns an iterator through the nested fields of an NBT compound to the
     /// location where the error occurred.
     ///
-    /// The iterator's `Item` is the name of the current field.
+    /// The iterator's `Item` is the name of the current field. Indices into
+    /// lists and arrays are included as `[N]`, where `N` is the index.
     pub fn trace(
         &self,
     ) -> impl DoubleEndedIterator<Item = &str> + ExactSizeIterator + FusedIterator + Clone + '_
     {
-        self.inner.trace.iter().rev().map(|s| s.as_str())
+        self.inner.trace.iter().rev().map(Segment::as_str)
+    }
+
+    /// Returns the location where the error occurred as a single string, such
+    /// as `Level.Sections[3].Palette[0].Name`.
+    ///
+    /// Field names which are empty or contain `.`, `[`, or `"` are quoted,
+    /// with `"` and `\` escaped by a backslash, so that the path stays
+    /// unambiguous: a field named `a.b` is written as `"a.b"`.
+    ///
+    /// The string is empty if no location is known.
+    pub fn path(&self) -> String {
+        let mut path = String::new();
+
+        for (i, seg) in self.inner.trace.iter().rev().enumerate() {
+            match seg {
+                Segment::Field(name) => {
+                    if i != 0 {
+                        path.push('.');
+                    }
+
+                    if name.is_empty() || name.contains(['.', '[', '"']) {
+                        path.push('"');
+                        for c in name.chars() {
+                            if c == '"' || c == '\\' {
+                                path.push('\\');
+                            }
+                            path.push(c);
+                        }
+                        path.push('"');
+                    } else {
+                        path.push_str(name);
+                    }
+                }
+                Segment::Index(idx) => path.push_str(idx),
+            }
+        }
+
+        path
     }
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let len = self.inner.trace.len();
-
-        if len > 0 {
-            write!(f, "(")?;
-            for (i, ctx) in self.trace().enumerate() {
-                write!(f, "{ctx}")?;
-
-                if i != len - 1 {
-                    write!(f, " → ")?;
-                }
-            }
-            write!(f, ") ")?;
+        if !self.inner.trace.is_empty() {
+            write!(f, "at {}: ", self.path())?;
         }
 
         match &self.inner.cause {
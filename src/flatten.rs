@@ -0,0 +1,228 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::{Compound, Error, List, Result, Value};
+
+impl Value {
+    /// Flattens this value into a single-level map from paths to leaf values.
+    ///
+    /// Compounds and lists are descended into, and every other value,
+    /// including arrays, becomes an entry of the map. Paths have the same form
+    /// as [`Error::path`]: compound keys are separated by `.`, and list
+    /// elements are keyed by their index in brackets, as in
+    /// `Inventory[0].Count`. A value which is not a compound or list is
+    /// returned as a single entry with an empty path.
+    ///
+    /// Empty compounds and lists have no leaves, so they do not appear in the
+    /// map. Keys which are empty or contain `.`, `[`, or `]` lead to paths
+    /// that cannot be told apart from other paths.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::{Compound, List, Value};
+    ///
+    /// let value = Value::Compound(Compound::from_iter([
+    ///     (
+    ///         "Level".to_owned(),
+    ///         Value::Compound(Compound::from_iter([("Y".to_owned(), Value::Byte(-4))])),
+    ///     ),
+    ///     ("Pos".to_owned(), List::Double(vec![0.5, 64.0]).into()),
+    ///     ("Biomes".to_owned(), Value::IntArray(vec![1, 2])),
+    /// ]));
+    ///
+    /// let flat = value.flatten();
+    ///
+    /// assert!(flat.keys().eq(["Biomes", "Level.Y", "Pos[0]", "Pos[1]"]));
+    /// assert_eq!(flat["Pos[1]"], Value::Double(64.0));
+    ///
+    /// assert_eq!(Value::unflatten(flat).unwrap(), value);
+    /// ```
+    pub fn flatten(&self) -> BTreeMap<String, Value> {
+        let mut out = BTreeMap::new();
+        flatten_into(self.clone(), &mut String::new(), &mut out);
+        out
+    }
+
+    /// Reconstructs a value from the output of [`Self::flatten`].
+    ///
+    /// Compounds are created for keys and lists for indices. The entries of
+    /// the compounds are sorted by key. An error is returned if a path is
+    /// malformed, if one path is a prefix of another that goes through a leaf,
+    /// if the indices of a list are not contiguous from zero, or if the
+    /// elements of a list have different types.
+    ///
+    /// An empty map results in an empty compound.
+    pub fn unflatten(map: BTreeMap<String, Value>) -> Result<Value> {
+        let mut root = None;
+
+        for (path, value) in map {
+            let segments = parse_path(&path)?;
+            insert(&mut root, &segments, value)?;
+        }
+
+        match root {
+            Some(node) => build(node),
+            None => Ok(Value::Compound(Compound::new())),
+        }
+    }
+}
+
+fn flatten_into(value: Value, path: &mut String, out: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Compound(c) => {
+            for (k, v) in c {
+                let len = path.len();
+
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(&k);
+
+                flatten_into(v, path, out);
+                path.truncate(len);
+            }
+        }
+        Value::List(l) => {
+            for (i, v) in l.into_values().into_iter().enumerate() {
+                let len = path.len();
+                let _ = write!(path, "[{i}]");
+
+                flatten_into(v, path, out);
+                path.truncate(len);
+            }
+        }
+        v => {
+            out.insert(path.clone(), v);
+        }
+    }
+}
+
+enum Segment<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+fn parse_path(path: &str) -> Result<Vec<Segment<'_>>> {
+    let invalid = || Error::new_owned(format!("invalid path `{path}`"));
+
+    let mut segments = Vec::new();
+    let mut rest = path;
+
+    if rest.is_empty() {
+        return Ok(segments);
+    }
+
+    loop {
+        // A key, unless the path continues with an index right away.
+        if !rest.starts_with('[') {
+            let end = rest.find(['.', '[']).unwrap_or(rest.len());
+            segments.push(Segment::Key(&rest[..end]));
+            rest = &rest[end..];
+        }
+
+        while let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').ok_or_else(invalid)?;
+            let digits = &after[..end];
+
+            if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(invalid());
+            }
+
+            segments.push(Segment::Index(digits.parse().map_err(|_| invalid())?));
+            rest = &after[end + 1..];
+        }
+
+        match rest.strip_prefix('.') {
+            Some(after) => rest = after,
+            None if rest.is_empty() => return Ok(segments),
+            None => return Err(invalid()),
+        }
+    }
+}
+
+/// A value under construction.
+enum Node {
+    Leaf(Value),
+    Compound(BTreeMap<String, Option<Node>>),
+    List(BTreeMap<usize, Option<Node>>),
+}
+
+fn insert(slot: &mut Option<Node>, segments: &[Segment], value: Value) -> Result<()> {
+    let conflict = || Error::new_static("path conflicts with another path");
+
+    match segments.split_first() {
+        None => match slot {
+            Some(_) => Err(conflict()),
+            None => {
+                *slot = Some(Node::Leaf(value));
+                Ok(())
+            }
+        },
+        Some((Segment::Key(k), rest)) => {
+            match slot.get_or_insert_with(|| Node::Compound(BTreeMap::new())) {
+                Node::Compound(m) => {
+                    let child = m.entry((*k).to_owned()).or_insert(None);
+                    insert(child, rest, value).map_err(|e| e.field(*k))
+                }
+                _ => Err(conflict()),
+            }
+        }
+        Some((Segment::Index(i), rest)) => {
+            match slot.get_or_insert_with(|| Node::List(BTreeMap::new())) {
+                Node::List(m) => {
+                    let child = m.entry(*i).or_insert(None);
+                    insert(child, rest, value).map_err(|e| e.index(*i))
+                }
+                _ => Err(conflict()),
+            }
+        }
+    }
+}
+
+fn build(node: Node) -> Result<Value> {
+    match node {
+        Node::Leaf(v) => Ok(v),
+        Node::Compound(m) => {
+            let mut compound = Compound::with_capacity(m.len());
+
+            for (k, child) in m {
+                let v = build(child.expect("inserted nodes are filled in"))
+                    .map_err(|e| e.field(k.clone()))?;
+                compound.insert(k, v);
+            }
+
+            Ok(Value::Compound(compound))
+        }
+        Node::List(m) => {
+            let mut list: Option<List> = None;
+
+            for (expected, (i, child)) in m.into_iter().enumerate() {
+                if i != expected {
+                    return Err(Error::new_owned(format!(
+                        "list is missing the element at index {expected}"
+                    )));
+                }
+
+                let v =
+                    build(child.expect("inserted nodes are filled in")).map_err(|e| e.index(i))?;
+
+                match &mut list {
+                    None => list = Some(List::singleton(v)),
+                    Some(list) => {
+                        if let Err(v) = list.push(v) {
+                            return Err(Error::new_owned(format!(
+                                "list elements must be homogeneous (got {}, expected {})",
+                                v.tag_type(),
+                                list.element_type()
+                            ))
+                            .index(i));
+                        }
+                    }
+                }
+            }
+
+            Ok(Value::List(list.unwrap_or_default()))
+        }
+    }
+}
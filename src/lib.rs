@@ -51,19 +51,89 @@
 //!
 //! assert_eq!(value, expected_value);
 //! ```
+//!
+//! # Optional values
+//!
+//! NBT has no null value. Instead, optional data is represented by the
+//! presence or absence of a compound entry. When serializing, a `None` field
+//! is omitted from the compound entirely, and a `Some` field is written as
+//! its contained value. When deserializing, an absent entry becomes `None`
+//! and a present entry always becomes `Some`. Fields with `#[serde(default)]`
+//! receive their default value when the entry is absent.
+//!
+//! The same is true when a struct is read into a [`Value`]: a `None` field
+//! has no entry in the resulting [`Compound`], and reading that `Value` back
+//! into the struct restores the field as `None`.
+//!
+//! Because there is no way to represent a missing list element, serializing
+//! `None` inside of a list is an error.
+//!
+//! # Byte arrays
+//!
+//! A `TAG_Byte_Array` is written by [`serialize_bytes`] and read by
+//! [`deserialize_bytes`] and [`deserialize_byte_buf`], so types such as
+//! `serde_bytes::ByteBuf` map to byte arrays. A plain `Vec<u8>` does not:
+//! serde treats it as a sequence of `u8`s, and NBT has no unsigned integer
+//! types. Annotate such fields with `#[serde(with = "serde_bytes")]`, or use
+//! a `Vec<i8>` with [`byte_array`].
+//!
+//! [`serialize_bytes`]: serde::Serializer::serialize_bytes
+//! [`deserialize_bytes`]: serde::Deserializer::deserialize_bytes
+//! [`deserialize_byte_buf`]: serde::Deserializer::deserialize_byte_buf
+//!
+//! # Numeric types
+//!
+//! When deserializing into a Rust number, the type of the NBT tag does not
+//! need to match exactly. Any integer tag can be read into a float, such as a
+//! `TAG_Int` into an `f64`, and any integer tag can be read into an integer
+//! type the value fits in. Float tags are never read into integers, since
+//! that would lose data.
 
 use std::fmt;
-use std::fmt::{Display, Formatter};
+use std::fmt::Formatter;
 
 pub use array::*;
+pub use compound::*;
+pub use debug::*;
+pub use diff::*;
 pub use error::*;
+pub use or_unknown::*;
+pub use raw::*;
+pub use scalars::*;
 use serde::de::Visitor;
 use serde::{Deserialize, Deserializer};
+pub use shared::*;
+pub use tag_type::*;
+// The internal name for `TagType`, kept from when it was a separate enum.
+use tag_type::TagType as Tag;
+pub use tagged::*;
+pub use timestamp::*;
+pub use uuid::*;
 pub use value::*;
+pub use value_ref::*;
 
 mod array;
+mod canonical;
+mod compound;
+mod debug;
+mod diff;
 mod error;
+mod flatten;
+mod merge;
+mod or_unknown;
+mod ord;
+mod pointer;
+mod project;
+mod raw;
+mod scalars;
+mod semantic;
+mod shared;
+mod tag_type;
+mod tagged;
+mod timestamp;
+mod uuid;
 mod value;
+mod value_ref;
 
 #[cfg(test)]
 mod tests;
@@ -73,28 +143,23 @@ pub mod binary {
     pub use de::*;
     pub use ser::*;
 
+    #[cfg(feature = "gzip")]
+    pub use gzip::*;
+
     mod de;
+    #[cfg(feature = "gzip")]
+    mod gzip;
+    pub mod le;
     mod ser;
 }
 
-pub type Result<T> = std::result::Result<T, Error>;
+#[cfg(feature = "chunk")]
+pub mod chunk;
+pub mod schema;
+pub mod snbt;
+pub mod transcode;
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
-enum Tag {
-    End,
-    Byte,
-    Short,
-    Int,
-    Long,
-    Float,
-    Double,
-    ByteArray,
-    String,
-    List,
-    Compound,
-    IntArray,
-    LongArray,
-}
+pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 enum ArrayType {
@@ -111,6 +176,14 @@ impl ArrayType {
             ArrayType::Long => Tag::Long,
         }
     }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            ArrayType::Byte => "byte array",
+            ArrayType::Int => "int array",
+            ArrayType::Long => "long array",
+        }
+    }
 }
 
 impl<'de> Deserialize<'de> for ArrayType {
@@ -156,47 +229,23 @@ impl<'de> Deserialize<'de> for ArrayType {
     }
 }
 
-impl Tag {
-    pub fn from_u8(id: u8) -> Result<Self> {
-        match id {
-            0 => Ok(Tag::End),
-            1 => Ok(Tag::Byte),
-            2 => Ok(Tag::Short),
-            3 => Ok(Tag::Int),
-            4 => Ok(Tag::Long),
-            5 => Ok(Tag::Float),
-            6 => Ok(Tag::Double),
-            7 => Ok(Tag::ByteArray),
-            8 => Ok(Tag::String),
-            9 => Ok(Tag::List),
-            10 => Ok(Tag::Compound),
-            11 => Ok(Tag::IntArray),
-            12 => Ok(Tag::LongArray),
-            _ => Err(Error::new_owned(format!("invalid tag byte `{id}`"))),
-        }
-    }
+/// The maximum nesting depth of compounds and lists accepted when reading
+/// binary NBT. This is also the default limit of the binary serializer and
+/// deserializer.
+const MAX_DEPTH: usize = 512;
+
+fn check_depth(depth: usize) -> Result<()> {
+    check_depth_limit(depth, MAX_DEPTH)
 }
 
-impl Display for Tag {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        let name = match self {
-            Tag::End => "end",
-            Tag::Byte => "byte",
-            Tag::Short => "short",
-            Tag::Int => "int",
-            Tag::Long => "long",
-            Tag::Float => "float",
-            Tag::Double => "double",
-            Tag::ByteArray => "byte array",
-            Tag::String => "string",
-            Tag::List => "list",
-            Tag::Compound => "compound",
-            Tag::IntArray => "int array",
-            Tag::LongArray => "long array",
-        };
-
-        write!(f, "{name}")
+fn check_depth_limit(depth: usize, max: usize) -> Result<()> {
+    if depth > max {
+        return Err(Error::new_owned(format!(
+            "NBT exceeds the maximum nesting depth of {max}"
+        )));
     }
+
+    Ok(())
 }
 
 /// Error message for cesu-8 decoding failures.
@@ -208,3 +257,13 @@ const ARRAY_ENUM_NAME: &str = "__array__";
 const BYTE_ARRAY_VARIANT_NAME: &str = "__byte_array__";
 const INT_ARRAY_VARIANT_NAME: &str = "__int_array__";
 const LONG_ARRAY_VARIANT_NAME: &str = "__long_array__";
+
+/// The name of the newtype struct used to pass the bytes of a
+/// [`RawCompound`] through serde.
+const RAW_COMPOUND_NAME: &str = "__raw_compound__";
+
+/// The name of the newtype struct that [`Value`] deserializes through when
+/// the format is not human readable. The binary deserializer recognizes it
+/// and presents arrays as enums, so they stay distinct from lists. Other
+/// visitors see arrays as plain sequences.
+const VALUE_NAME: &str = "__value__";
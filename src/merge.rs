@@ -0,0 +1,127 @@
+use crate::{Compound, Error, List, Result, Value};
+
+impl Value {
+    /// Merges a list of compounds into the list at `path`, matching elements
+    /// by the value of `key_field` instead of by position.
+    ///
+    /// `path` is a sequence of compound keys separated by `.`, relative to
+    /// this value. An empty path refers to this value itself. If the last key
+    /// is absent from its compound, an empty list is created there first.
+    ///
+    /// For every compound in `other`, the element of the list with an equal
+    /// `key_field` entry is updated with the entries of that compound. If no
+    /// element matches, the compound is appended to the list. Elements of the
+    /// list without a `key_field` entry are never matched.
+    ///
+    /// An error is returned if the path does not lead to a list of compounds
+    /// or if a compound in `other` has no `key_field` entry. In that case,
+    /// this value is left unmodified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::{Compound, List, Value};
+    ///
+    /// fn enchantment(id: &str, lvl: i16) -> Compound {
+    ///     Compound::from_iter([
+    ///         ("id".to_owned(), Value::String(id.to_owned())),
+    ///         ("lvl".to_owned(), Value::Short(lvl)),
+    ///     ])
+    /// }
+    ///
+    /// let mut item = Value::Compound(Compound::from_iter([(
+    ///     "tag".to_owned(),
+    ///     Value::Compound(Compound::from_iter([(
+    ///         "Enchantments".to_owned(),
+    ///         List::Compound(vec![enchantment("minecraft:sharpness", 1)]).into(),
+    ///     )])),
+    /// )]));
+    ///
+    /// item.merge_list_by_key(
+    ///     "tag.Enchantments",
+    ///     "id",
+    ///     &[
+    ///         enchantment("minecraft:unbreaking", 3),
+    ///         enchantment("minecraft:sharpness", 5),
+    ///     ],
+    /// )
+    /// .unwrap();
+    ///
+    /// let Value::Compound(item) = item else { unreachable!() };
+    /// let Value::Compound(tag) = &item["tag"] else { unreachable!() };
+    ///
+    /// assert_eq!(
+    ///     tag["Enchantments"],
+    ///     Value::List(List::Compound(vec![
+    ///         enchantment("minecraft:sharpness", 5),
+    ///         enchantment("minecraft:unbreaking", 3),
+    ///     ]))
+    /// );
+    /// ```
+    pub fn merge_list_by_key(
+        &mut self,
+        path: &str,
+        key_field: &str,
+        other: &[Compound],
+    ) -> Result<()> {
+        for (i, compound) in other.iter().enumerate() {
+            if !compound.contains_key(key_field) {
+                return Err(Error::new_owned(format!(
+                    "element {i} of the list to merge has no `{key_field}` entry"
+                )));
+            }
+        }
+
+        let keys: Vec<&str> = if path.is_empty() {
+            Vec::new()
+        } else {
+            path.split('.').collect()
+        };
+
+        merge_at(self, &keys, key_field, other)
+    }
+}
+
+fn merge_at(value: &mut Value, path: &[&str], key_field: &str, other: &[Compound]) -> Result<()> {
+    match (path.split_first(), value) {
+        (None, Value::List(list)) => merge_list(list, key_field, other),
+        (None, _) => Err(Error::new_static("expected a list of compounds")),
+        (Some((&key, rest)), Value::Compound(compound)) => {
+            if rest.is_empty() && !compound.contains_key(key) {
+                compound.insert(key.to_owned(), List::Compound(Vec::new()).into());
+            }
+
+            match compound.get_mut(key) {
+                Some(v) => merge_at(v, rest, key_field, other).map_err(|e| e.field(key)),
+                None => Err(Error::new_static("path does not exist").field(key)),
+            }
+        }
+        (Some(_), _) => Err(Error::new_static("expected a compound")),
+    }
+}
+
+fn merge_list(list: &mut List, key_field: &str, other: &[Compound]) -> Result<()> {
+    // An empty list may have any element type.
+    if list.is_empty() {
+        *list = List::Compound(Vec::new());
+    }
+
+    let List::Compound(elements) = list else {
+        return Err(Error::new_static("expected a list of compounds"));
+    };
+
+    for compound in other {
+        let key = &compound[key_field];
+
+        match elements.iter_mut().find(|e| e.get(key_field) == Some(key)) {
+            Some(existing) => {
+                for (k, v) in compound {
+                    existing.insert(k.clone(), v.clone());
+                }
+            }
+            None => elements.push(compound.clone()),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use serde::de::value::StrDeserializer;
+use serde::de::IntoDeserializer;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A string-encoded value which may not be recognized.
+///
+/// NBT data often stores enum-like values as strings, such as the block state
+/// property `"facing": "north"`. The set of possible strings tends to change
+/// between Minecraft versions, so deserializing directly into a Rust enum
+/// fails as soon as an unfamiliar string is encountered.
+///
+/// `OrUnknown<T>` first deserializes a string and then attempts to convert it
+/// to `T`. If the conversion fails, the string is kept in
+/// [`OrUnknown::Unknown`] instead of failing. Serializing an `OrUnknown<T>`
+/// writes back the original string in both cases.
+///
+/// # Examples
+///
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use serde_nbt::binary::{from_reader, to_writer};
+/// use serde_nbt::{Compound, OrUnknown, Value};
+///
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// #[serde(rename_all = "lowercase")]
+/// enum Facing {
+///     North,
+///     South,
+///     East,
+///     West,
+/// }
+///
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// struct Properties {
+///     facing: OrUnknown<Facing>,
+/// }
+///
+/// let value = Value::Compound(Compound::from_iter([(
+///     "facing".to_owned(),
+///     Value::String("up".to_owned()),
+/// )]));
+///
+/// let mut buf = Vec::new();
+/// to_writer(&mut buf, &value).unwrap();
+///
+/// let props: Properties = from_reader(&mut buf.as_slice()).unwrap();
+///
+/// assert_eq!(props.facing, OrUnknown::Unknown("up".to_owned()));
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum OrUnknown<T> {
+    /// The string was successfully converted to `T`.
+    Known(T),
+    /// The string was not recognized.
+    Unknown(String),
+}
+
+impl<T> OrUnknown<T> {
+    /// Returns the known value, if any.
+    pub fn known(&self) -> Option<&T> {
+        match self {
+            OrUnknown::Known(t) => Some(t),
+            OrUnknown::Unknown(_) => None,
+        }
+    }
+
+    /// Returns the unrecognized string, if any.
+    pub fn unknown(&self) -> Option<&str> {
+        match self {
+            OrUnknown::Known(_) => None,
+            OrUnknown::Unknown(s) => Some(s),
+        }
+    }
+}
+
+impl<T> From<T> for OrUnknown<T> {
+    fn from(t: T) -> Self {
+        OrUnknown::Known(t)
+    }
+}
+
+impl<T: Serialize> Serialize for OrUnknown<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            OrUnknown::Known(t) => t.serialize(serializer),
+            OrUnknown::Unknown(s) => s.serialize(serializer),
+        }
+    }
+}
+
+impl<'de, T> Deserialize<'de> for OrUnknown<T>
+where
+    T: for<'a> Deserialize<'a>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let string = String::deserialize(deserializer)?;
+
+        let de: StrDeserializer<serde::de::value::Error> = string.as_str().into_deserializer();
+
+        match T::deserialize(de) {
+            Ok(t) => Ok(OrUnknown::Known(t)),
+            Err(_) => Ok(OrUnknown::Unknown(string)),
+        }
+    }
+}
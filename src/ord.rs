@@ -0,0 +1,145 @@
+use std::cmp::Ordering;
+
+use smallvec::SmallVec;
+
+use crate::{Compound, List, Value};
+
+// Floats are compared with `total_cmp`, which considers two floats equal
+// exactly when their bits are equal. The `PartialEq` impls below must agree
+// with this, so they compare floats by their bits.
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Byte(a), Value::Byte(b)) => a == b,
+            (Value::Short(a), Value::Short(b)) => a == b,
+            (Value::Int(a), Value::Int(b)) => a == b,
+            (Value::Long(a), Value::Long(b)) => a == b,
+            (Value::Float(a), Value::Float(b)) => a.to_bits() == b.to_bits(),
+            (Value::Double(a), Value::Double(b)) => a.to_bits() == b.to_bits(),
+            (Value::ByteArray(a), Value::ByteArray(b)) => a == b,
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::List(a), Value::List(b)) => a == b,
+            (Value::Compound(a), Value::Compound(b)) => a == b,
+            (Value::IntArray(a), Value::IntArray(b)) => a == b,
+            (Value::LongArray(a), Value::LongArray(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Value {}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Value {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Value::Byte(a), Value::Byte(b)) => a.cmp(b),
+            (Value::Short(a), Value::Short(b)) => a.cmp(b),
+            (Value::Int(a), Value::Int(b)) => a.cmp(b),
+            (Value::Long(a), Value::Long(b)) => a.cmp(b),
+            (Value::Float(a), Value::Float(b)) => a.total_cmp(b),
+            (Value::Double(a), Value::Double(b)) => a.total_cmp(b),
+            (Value::ByteArray(a), Value::ByteArray(b)) => a.cmp(b),
+            (Value::String(a), Value::String(b)) => a.cmp(b),
+            (Value::List(a), Value::List(b)) => a.cmp(b),
+            (Value::Compound(a), Value::Compound(b)) => a.cmp(b),
+            (Value::IntArray(a), Value::IntArray(b)) => a.cmp(b),
+            (Value::LongArray(a), Value::LongArray(b)) => a.cmp(b),
+            _ => self.tag_type().cmp(&other.tag_type()),
+        }
+    }
+}
+
+impl PartialEq for List {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (List::Byte(a), List::Byte(b)) => a == b,
+            (List::Short(a), List::Short(b)) => a == b,
+            (List::Int(a), List::Int(b)) => a == b,
+            (List::Long(a), List::Long(b)) => a == b,
+            (List::Float(a), List::Float(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.to_bits() == b.to_bits())
+            }
+            (List::Double(a), List::Double(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.to_bits() == b.to_bits())
+            }
+            (List::ByteArray(a), List::ByteArray(b)) => a == b,
+            (List::String(a), List::String(b)) => a == b,
+            (List::List(a), List::List(b)) => a == b,
+            (List::Compound(a), List::Compound(b)) => a == b,
+            (List::IntArray(a), List::IntArray(b)) => a == b,
+            (List::LongArray(a), List::LongArray(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for List {}
+
+impl PartialOrd for List {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for List {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (List::Byte(a), List::Byte(b)) => a.cmp(b),
+            (List::Short(a), List::Short(b)) => a.cmp(b),
+            (List::Int(a), List::Int(b)) => a.cmp(b),
+            (List::Long(a), List::Long(b)) => a.cmp(b),
+            (List::Float(a), List::Float(b)) => cmp_by(a, b, f32::total_cmp),
+            (List::Double(a), List::Double(b)) => cmp_by(a, b, f64::total_cmp),
+            (List::ByteArray(a), List::ByteArray(b)) => a.cmp(b),
+            (List::String(a), List::String(b)) => a.cmp(b),
+            (List::List(a), List::List(b)) => a.cmp(b),
+            (List::Compound(a), List::Compound(b)) => a.cmp(b),
+            (List::IntArray(a), List::IntArray(b)) => a.cmp(b),
+            (List::LongArray(a), List::LongArray(b)) => a.cmp(b),
+            _ => self.element_type().cmp(&other.element_type()),
+        }
+    }
+}
+
+impl Eq for Compound {}
+
+impl PartialOrd for Compound {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Compound {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Compare the entries sorted by key so that the order agrees with
+        // equality, which ignores the order of entries.
+        sorted_entries(self).cmp(&sorted_entries(other))
+    }
+}
+
+/// Returns the entries of a compound sorted by key. Most compounds are small
+/// enough to be sorted without allocating.
+fn sorted_entries(compound: &Compound) -> SmallVec<[(&str, &Value); 16]> {
+    let mut entries: SmallVec<[_; 16]> = compound.iter().map(|(k, v)| (k.as_str(), v)).collect();
+    entries.sort_unstable_by(|x, y| x.0.cmp(y.0));
+    entries
+}
+
+/// Compares slices lexicographically with the given element comparison.
+fn cmp_by<T>(a: &[T], b: &[T], mut cmp: impl FnMut(&T, &T) -> Ordering) -> Ordering {
+    for (x, y) in a.iter().zip(b) {
+        match cmp(x, y) {
+            Ordering::Equal => {}
+            ord => return ord,
+        }
+    }
+
+    a.len().cmp(&b.len())
+}
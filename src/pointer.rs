@@ -0,0 +1,163 @@
+use std::borrow::Cow;
+
+use crate::{Compound, List, Value, ValueRef};
+
+impl Value {
+    /// Looks up a value by a [JSON Pointer] (RFC 6901), such as
+    /// `/Level/Sections/0/Y`.
+    ///
+    /// The pointer is a sequence of tokens, each preceded by a `/`. A token is
+    /// a compound key or, for lists, an element index. Within a key, `~1`
+    /// stands for `/` and `~0` stands for `~`, so keys containing any
+    /// characters can be reached. The empty pointer refers to this value.
+    ///
+    /// Lists store their elements directly rather than as `Value`s, so a
+    /// [`ValueRef`] is returned. Every value in the tree can be reached,
+    /// including list and array elements. `None` is returned if the pointer is
+    /// malformed or does not exist.
+    ///
+    /// [JSON Pointer]: https://www.rfc-editor.org/rfc/rfc6901
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::{Compound, List, Value, ValueRef};
+    ///
+    /// let chunk = Value::Compound(Compound::from_iter([(
+    ///     "Level".to_owned(),
+    ///     Value::Compound(Compound::from_iter([
+    ///         (
+    ///             "Sections".to_owned(),
+    ///             List::Compound(vec![Compound::from_iter([(
+    ///                 "Y".to_owned(),
+    ///                 Value::Byte(-4),
+    ///             )])])
+    ///             .into(),
+    ///         ),
+    ///         ("minecraft:a/b".to_owned(), Value::Int(1)),
+    ///     ])),
+    /// )]));
+    ///
+    /// assert_eq!(chunk.pointer("/Level/Sections/0/Y"), Some(ValueRef::Byte(-4)));
+    /// assert_eq!(chunk.pointer("/Level/minecraft:a~1b"), Some(ValueRef::Int(1)));
+    /// assert!(chunk.pointer("/Level/Sections/0").is_some());
+    /// assert_eq!(chunk.pointer("/Level/Sections/1/Y"), None);
+    /// ```
+    pub fn pointer(&self, pointer: &str) -> Option<ValueRef<'_>> {
+        let mut node = ValueRef::from(self);
+
+        for token in tokens(pointer)? {
+            let token = token?;
+
+            node = match node {
+                ValueRef::Compound(c) => c.get(token.as_ref())?.into(),
+                ValueRef::List(l) => l.get(index(&token)?)?,
+                ValueRef::ByteArray(a) => ValueRef::Byte(*a.get(index(&token)?)?),
+                ValueRef::IntArray(a) => ValueRef::Int(*a.get(index(&token)?)?),
+                ValueRef::LongArray(a) => ValueRef::Long(*a.get(index(&token)?)?),
+                _ => return None,
+            };
+        }
+
+        Some(node)
+    }
+
+    /// Like [`Self::pointer`], but returns a mutable reference.
+    ///
+    /// Only values which are stored as `Value`s can be returned, which are
+    /// this value and the entries of compounds. List elements can be passed
+    /// through, but `None` is returned if the pointer ends at one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::{Compound, Value, ValueRef};
+    ///
+    /// let mut value = Value::Compound(Compound::from_iter([(
+    ///     "a.b".to_owned(),
+    ///     Value::Compound(Compound::from_iter([("c".to_owned(), Value::Int(1))])),
+    /// )]));
+    ///
+    /// *value.pointer_mut("/a.b/c").unwrap() = Value::Int(2);
+    ///
+    /// assert_eq!(value.pointer("/a.b/c"), Some(ValueRef::Int(2)));
+    /// ```
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut Value> {
+        let mut node = NodeMut::Value(self);
+
+        for token in tokens(pointer)? {
+            let token = token?;
+
+            node = match node {
+                NodeMut::Value(Value::Compound(c)) | NodeMut::Compound(c) => {
+                    NodeMut::Value(c.get_mut(token.as_ref())?)
+                }
+                NodeMut::Value(Value::List(l)) | NodeMut::List(l) => match l {
+                    List::List(v) => NodeMut::List(v.get_mut(index(&token)?)?),
+                    List::Compound(v) => NodeMut::Compound(v.get_mut(index(&token)?)?),
+                    _ => return None,
+                },
+                NodeMut::Value(_) => return None,
+            };
+        }
+
+        match node {
+            NodeMut::Value(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+/// A position in a value that a mutable pointer can pass through.
+enum NodeMut<'a> {
+    Value(&'a mut Value),
+    List(&'a mut List),
+    Compound(&'a mut Compound),
+}
+
+/// Splits a pointer into its unescaped tokens. Returns `None` if the pointer
+/// is not empty and does not start with `/`. Tokens with invalid escapes are
+/// `None`.
+fn tokens(pointer: &str) -> Option<impl Iterator<Item = Option<Cow<'_, str>>>> {
+    let rest = match pointer {
+        "" => None,
+        _ => Some(pointer.strip_prefix('/')?),
+    };
+
+    Some(rest.into_iter().flat_map(|r| r.split('/')).map(unescape))
+}
+
+fn unescape(token: &str) -> Option<Cow<'_, str>> {
+    if !token.contains('~') {
+        return Some(Cow::Borrowed(token));
+    }
+
+    let mut out = String::with_capacity(token.len());
+    let mut chars = token.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '~' {
+            match chars.next()? {
+                '0' => out.push('~'),
+                '1' => out.push('/'),
+                _ => return None,
+            }
+        } else {
+            out.push(c);
+        }
+    }
+
+    Some(Cow::Owned(out))
+}
+
+/// Parses a list index. Leading zeros are not allowed.
+fn index(token: &str) -> Option<usize> {
+    if token.is_empty()
+        || !token.bytes().all(|b| b.is_ascii_digit())
+        || (token.len() > 1 && token.starts_with('0'))
+    {
+        return None;
+    }
+
+    token.parse().ok()
+}
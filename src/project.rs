@@ -0,0 +1,86 @@
+use crate::{Compound, Value};
+
+impl Value {
+    /// Returns a new compound containing only the values at the given paths.
+    ///
+    /// Each path is a sequence of compound keys separated by `.`, relative to
+    /// this value. The compounds along each path are recreated in the result
+    /// with only the entries needed to reach the selected values. Paths which
+    /// do not exist in this value are skipped, so the result is an empty
+    /// compound if nothing matches.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::{Compound, Value};
+    ///
+    /// let chunk = Value::Compound(Compound::from_iter([
+    ///     ("DataVersion".to_owned(), Value::Int(2586)),
+    ///     (
+    ///         "Level".to_owned(),
+    ///         Value::Compound(Compound::from_iter([
+    ///             ("Biomes".to_owned(), Value::IntArray(vec![1; 4])),
+    ///             ("Sections".to_owned(), Value::Compound(Compound::new())),
+    ///         ])),
+    ///     ),
+    /// ]));
+    ///
+    /// let projected = chunk.project(&["Level.Biomes", "DataVersion"]);
+    ///
+    /// let expected = Value::Compound(Compound::from_iter([
+    ///     (
+    ///         "Level".to_owned(),
+    ///         Value::Compound(Compound::from_iter([(
+    ///             "Biomes".to_owned(),
+    ///             Value::IntArray(vec![1; 4]),
+    ///         )])),
+    ///     ),
+    ///     ("DataVersion".to_owned(), Value::Int(2586)),
+    /// ]));
+    ///
+    /// assert_eq!(projected, expected);
+    /// ```
+    pub fn project(&self, paths: &[&str]) -> Value {
+        let mut result = Compound::new();
+
+        for path in paths {
+            let keys: Vec<&str> = path.split('.').collect();
+
+            if let Some(value) = lookup(self, &keys) {
+                insert(&mut result, &keys, value.clone());
+            }
+        }
+
+        Value::Compound(result)
+    }
+}
+
+fn lookup<'a>(mut value: &'a Value, keys: &[&str]) -> Option<&'a Value> {
+    for key in keys {
+        match value {
+            Value::Compound(c) => value = c.get(*key)?,
+            _ => return None,
+        }
+    }
+
+    Some(value)
+}
+
+fn insert(compound: &mut Compound, keys: &[&str], value: Value) {
+    match keys {
+        [] => {}
+        [last] => {
+            compound.insert((*last).to_owned(), value);
+        }
+        [first, rest @ ..] => {
+            let entry = compound
+                .entry(*first)
+                .or_insert_with(|| Value::Compound(Compound::new()));
+
+            // Existing entries are compounds because `lookup` went through them.
+            if let Value::Compound(c) = entry {
+                insert(c, rest, value);
+            }
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use std::fmt;
+
+use serde::de::{DeserializeSeed, Error as _, MapAccess, Unexpected, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::binary::{copy_payload, read_tag_body, write_payload};
+use crate::{Compound, Error, Result, Tag, RAW_COMPOUND_NAME};
+
+/// A compound which is kept in its encoded binary form.
+///
+/// Deserializing a `RawCompound` from binary NBT copies the bytes of the
+/// compound without decoding them, and serializing it writes the bytes back
+/// verbatim. This makes it cheap to pass through large parts of a document
+/// which are not inspected, such as the sections of a chunk when only its
+/// status is being changed. The limits and options of the
+/// [`Deserializer`](crate::binary::Deserializer) still apply to the copied
+/// compound, and entries with an unknown tag which are skipped by
+/// [`on_unknown_tag`](crate::binary::Deserializer::on_unknown_tag) are left
+/// out of it.
+///
+/// The bytes are the payload of a compound tag, starting with its first
+/// entry and ending with `TAG_End`. They are always a structurally valid
+/// payload.
+///
+/// # Examples
+///
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use serde_nbt::binary::{from_slice_exact, to_vec};
+/// use serde_nbt::{Compound, RawCompound, Value};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Chunk {
+///     #[serde(rename = "Status")]
+///     status: String,
+///     #[serde(rename = "Level")]
+///     level: RawCompound,
+/// }
+///
+/// let level = Compound::from_iter([("xPos".to_owned(), Value::Int(3))]);
+///
+/// let original = to_vec(&Compound::from_iter([
+///     ("Status".to_owned(), Value::String("empty".to_owned())),
+///     ("Level".to_owned(), Value::Compound(level.clone())),
+/// ]))
+/// .unwrap();
+///
+/// let mut chunk: Chunk = from_slice_exact(&original).unwrap();
+/// chunk.status = "full".to_owned();
+///
+/// let modified: Compound = from_slice_exact(&to_vec(&chunk).unwrap()).unwrap();
+///
+/// assert_eq!(modified["Status"], Value::String("full".to_owned()));
+/// assert_eq!(modified["Level"], Value::Compound(level));
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct RawCompound {
+    payload: Vec<u8>,
+}
+
+impl RawCompound {
+    /// Encodes a compound.
+    pub fn from_compound(compound: &Compound) -> Result<Self> {
+        let mut payload = Vec::new();
+        write_payload(&mut payload, Tag::Compound, compound)?;
+        Ok(Self { payload })
+    }
+
+    /// Decodes the compound.
+    pub fn to_compound(&self) -> Result<Compound> {
+        read_tag_body(self.payload.as_slice(), Tag::Compound as u8)?.try_into()
+    }
+
+    /// Returns the encoded payload of the compound.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// Consumes the raw compound, returning the encoded payload.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.payload
+    }
+
+    /// Checks that `bytes` is exactly one compound payload.
+    fn from_bytes(bytes: Vec<u8>) -> Result<Self> {
+        let mut reader = bytes.as_slice();
+        copy_payload(&mut reader, Tag::Compound, &mut Vec::new(), 0)?;
+
+        if !reader.is_empty() {
+            return Err(Error::new_owned(format!(
+                "{} trailing byte(s) after raw compound",
+                reader.len()
+            )));
+        }
+
+        Ok(Self { payload: bytes })
+    }
+}
+
+impl Serialize for RawCompound {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        struct Bytes<'a>(&'a [u8]);
+
+        impl Serialize for Bytes<'_> {
+            fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.serialize_bytes(self.0)
+            }
+        }
+
+        serializer.serialize_newtype_struct(RAW_COMPOUND_NAME, &Bytes(&self.payload))
+    }
+}
+
+impl<'de> Deserialize<'de> for RawCompound {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct RawCompoundVisitor;
+
+        impl<'de> Visitor<'de> for RawCompoundVisitor {
+            type Value = RawCompound;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "the bytes of an NBT compound payload")
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                self.visit_byte_buf(v.to_vec())
+            }
+
+            fn visit_byte_buf<E>(self, v: Vec<u8>) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                RawCompound::from_bytes(v).map_err(E::custom)
+            }
+
+            /// The binary deserializer presents a payload it has already
+            /// checked as a map whose only key is `RAW_COMPOUND_NAME`, so it
+            /// is not checked again.
+            fn visit_map<A>(self, mut map: A) -> std::result::Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                match map.next_key::<String>()? {
+                    Some(key) if key == RAW_COMPOUND_NAME => Ok(RawCompound {
+                        payload: map.next_value_seed(TrustedPayload)?,
+                    }),
+                    _ => Err(A::Error::invalid_type(Unexpected::Map, &self)),
+                }
+            }
+        }
+
+        struct TrustedPayload;
+
+        impl<'de> DeserializeSeed<'de> for TrustedPayload {
+            type Value = Vec<u8>;
+
+            fn deserialize<D>(self, deserializer: D) -> std::result::Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                deserializer.deserialize_byte_buf(self)
+            }
+        }
+
+        impl<'de> Visitor<'de> for TrustedPayload {
+            type Value = Vec<u8>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "the bytes of an NBT compound payload")
+            }
+
+            fn visit_byte_buf<E>(self, v: Vec<u8>) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(v)
+            }
+        }
+
+        deserializer.deserialize_newtype_struct(RAW_COMPOUND_NAME, RawCompoundVisitor)
+    }
+}
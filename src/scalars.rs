@@ -0,0 +1,228 @@
+use std::fmt::Write;
+use std::iter::FusedIterator;
+
+use indexmap::map::Iter;
+
+use crate::{List, Value, ValueRef};
+
+/// A borrowed scalar NBT value, as yielded by [`Value::scalars`].
+///
+/// The elements of lists and arrays are not stored as [`Value`]s, so scalars
+/// are returned by value (or by reference for strings) instead.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ScalarRef<'a> {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(&'a str),
+}
+
+impl<'a> ScalarRef<'a> {
+    /// Returns the scalar as an `f64` if it is numeric. See [`Value::as_f64`].
+    pub fn as_f64(self) -> Option<f64> {
+        self.to_value().as_f64()
+    }
+
+    /// Returns the scalar as an `i64` if it is numeric. See [`Value::as_i64`].
+    pub fn as_i64(self) -> Option<i64> {
+        self.to_value().as_i64()
+    }
+
+    /// Returns the string if this is a string scalar.
+    pub fn as_str(self) -> Option<&'a str> {
+        match self {
+            ScalarRef::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Converts the scalar into an owned [`Value`].
+    pub fn to_value(self) -> Value {
+        match self {
+            ScalarRef::Byte(v) => Value::Byte(v),
+            ScalarRef::Short(v) => Value::Short(v),
+            ScalarRef::Int(v) => Value::Int(v),
+            ScalarRef::Long(v) => Value::Long(v),
+            ScalarRef::Float(v) => Value::Float(v),
+            ScalarRef::Double(v) => Value::Double(v),
+            ScalarRef::String(v) => Value::String(v.to_owned()),
+        }
+    }
+}
+
+impl Value {
+    /// Returns an iterator over every scalar in this value along with its
+    /// path.
+    ///
+    /// Paths have the same form as [`Error::path`](crate::Error::path), such
+    /// as `Inventory[0].Count`. Compounds, lists, and arrays are descended
+    /// into rather than yielded, so the elements of arrays appear with an
+    /// index at the end of their path. Entries are visited in order.
+    ///
+    /// The value is traversed lazily, so stopping early does not visit the
+    /// rest of it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::{Compound, List, ScalarRef, Value};
+    ///
+    /// let item = |count| Compound::from_iter([("Count".to_owned(), Value::Byte(count))]);
+    ///
+    /// let player = Value::Compound(Compound::from_iter([
+    ///     (
+    ///         "Inventory".to_owned(),
+    ///         List::Compound(vec![item(3), item(64)]).into(),
+    ///     ),
+    ///     ("Pos".to_owned(), Value::IntArray(vec![1, 2, 3])),
+    /// ]));
+    ///
+    /// let total: i64 = player
+    ///     .scalars()
+    ///     .filter(|(path, _)| path.ends_with(".Count"))
+    ///     .filter_map(|(_, s)| s.as_i64())
+    ///     .sum();
+    ///
+    /// assert_eq!(total, 67);
+    ///
+    /// let (path, scalar) = player.scalars().last().unwrap();
+    /// assert_eq!(path, "Pos[2]");
+    /// assert_eq!(scalar, ScalarRef::Int(3));
+    /// ```
+    pub fn scalars(&self) -> Scalars<'_> {
+        Scalars {
+            root: Some(self.into()),
+            stack: Vec::new(),
+            path: String::new(),
+        }
+    }
+}
+
+/// An iterator over the scalars in a [`Value`] and their paths, as returned
+/// by [`Value::scalars`].
+#[derive(Clone, Debug)]
+pub struct Scalars<'a> {
+    /// The value itself, until it is visited.
+    root: Option<ValueRef<'a>>,
+    /// The containers being visited, innermost last.
+    stack: Vec<Frame<'a>>,
+    path: String,
+}
+
+/// A container being visited, along with the length of its path.
+#[derive(Clone, Debug)]
+enum Frame<'a> {
+    Compound(Iter<'a, String, Value>, usize),
+    Elements(Elements<'a>, usize, usize),
+}
+
+/// The elements of a list or array. The index of the next element is kept
+/// in [`Frame::Elements`].
+#[derive(Clone, Copy, Debug)]
+enum Elements<'a> {
+    List(&'a List),
+    ByteArray(&'a [i8]),
+    IntArray(&'a [i32]),
+    LongArray(&'a [i64]),
+}
+
+impl<'a> Elements<'a> {
+    fn get(self, index: usize) -> Option<ValueRef<'a>> {
+        match self {
+            Elements::List(l) => l.get(index),
+            Elements::ByteArray(a) => a.get(index).map(|v| ValueRef::Byte(*v)),
+            Elements::IntArray(a) => a.get(index).map(|v| ValueRef::Int(*v)),
+            Elements::LongArray(a) => a.get(index).map(|v| ValueRef::Long(*v)),
+        }
+    }
+}
+
+impl<'a> Scalars<'a> {
+    /// Returns the next value to visit and appends its key or index to the
+    /// path, or returns `None` if the current container is finished.
+    fn next_value(frame: &mut Frame<'a>, path: &mut String) -> Option<ValueRef<'a>> {
+        match frame {
+            Frame::Compound(iter, len) => {
+                path.truncate(*len);
+                let (k, v) = iter.next()?;
+
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(k);
+
+                Some(v.into())
+            }
+            Frame::Elements(elements, index, len) => {
+                path.truncate(*len);
+                let v = elements.get(*index)?;
+
+                let _ = write!(path, "[{index}]");
+                *index += 1;
+
+                Some(v)
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Scalars<'a> {
+    type Item = (String, ScalarRef<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let value = match self.root.take() {
+                Some(root) => root,
+                None => match Self::next_value(self.stack.last_mut()?, &mut self.path) {
+                    Some(value) => value,
+                    None => {
+                        self.stack.pop();
+                        continue;
+                    }
+                },
+            };
+
+            let len = self.path.len();
+
+            let scalar = match value {
+                ValueRef::Byte(v) => ScalarRef::Byte(v),
+                ValueRef::Short(v) => ScalarRef::Short(v),
+                ValueRef::Int(v) => ScalarRef::Int(v),
+                ValueRef::Long(v) => ScalarRef::Long(v),
+                ValueRef::Float(v) => ScalarRef::Float(v),
+                ValueRef::Double(v) => ScalarRef::Double(v),
+                ValueRef::String(v) => ScalarRef::String(v),
+                ValueRef::Compound(c) => {
+                    self.stack.push(Frame::Compound(c.iter(), len));
+                    continue;
+                }
+                ValueRef::List(l) => {
+                    self.stack.push(Frame::Elements(Elements::List(l), 0, len));
+                    continue;
+                }
+                ValueRef::ByteArray(a) => {
+                    self.stack
+                        .push(Frame::Elements(Elements::ByteArray(a), 0, len));
+                    continue;
+                }
+                ValueRef::IntArray(a) => {
+                    self.stack
+                        .push(Frame::Elements(Elements::IntArray(a), 0, len));
+                    continue;
+                }
+                ValueRef::LongArray(a) => {
+                    self.stack
+                        .push(Frame::Elements(Elements::LongArray(a), 0, len));
+                    continue;
+                }
+            };
+
+            return Some((self.path.clone(), scalar));
+        }
+    }
+}
+
+impl FusedIterator for Scalars<'_> {}
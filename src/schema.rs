@@ -0,0 +1,172 @@
+//! Validation of NBT values against a declarative schema.
+//!
+//! A [`Schema`] lists the paths that are expected in a value along with their
+//! [`TagType`]s. Validation reports every mismatch at once, which is useful
+//! for checking save files before processing them.
+//!
+//! Schemas are plain data. They can be built in code or deserialized from a
+//! configuration file in any format supported by serde.
+//!
+//! # Examples
+//!
+//! ```
+//! use serde_nbt::schema::Schema;
+//! use serde_nbt::{Compound, TagType, Value};
+//!
+//! let schema = Schema::new()
+//!     .required("Level.DataVersion", TagType::Int)
+//!     .optional("Level.LevelName", TagType::String);
+//!
+//! let value = Value::Compound(Compound::from_iter([(
+//!     "Level".to_owned(),
+//!     Value::Compound(Compound::from_iter([(
+//!         "DataVersion".to_owned(),
+//!         Value::Long(3465),
+//!     )])),
+//! )]));
+//!
+//! let mismatches = schema.validate(&value);
+//!
+//! assert_eq!(mismatches.len(), 1);
+//! assert_eq!(
+//!     mismatches[0].to_string(),
+//!     "at Level.DataVersion: expected int, found long"
+//! );
+//! ```
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{TagType, Value};
+
+/// A set of expected tag types by path.
+#[derive(Clone, PartialEq, Eq, Default, Debug, Serialize, Deserialize)]
+pub struct Schema {
+    /// The fields checked by this schema, in order.
+    pub fields: Vec<Field>,
+}
+
+/// A single entry in a [`Schema`].
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Field {
+    /// A sequence of compound keys separated by `.`, relative to the value
+    /// being validated.
+    pub path: String,
+    /// The expected type of the value at the path.
+    pub tag_type: TagType,
+    /// Whether it is a mismatch for the path to be absent.
+    pub required: bool,
+}
+
+/// A difference between a value and a [`Schema`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Mismatch {
+    /// The path to the offending value.
+    pub path: String,
+    /// The type that the schema expects.
+    pub expected: TagType,
+    /// The type that was found, or `None` if the path is absent.
+    pub found: Option<TagType>,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.found {
+            Some(found) => write!(
+                f,
+                "at {}: expected {}, found {found}",
+                self.path, self.expected
+            ),
+            None => write!(f, "at {}: missing {}", self.path, self.expected),
+        }
+    }
+}
+
+impl Schema {
+    /// Creates an empty schema, which every value satisfies.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a path which must be present and have the given type.
+    pub fn required(mut self, path: impl Into<String>, tag_type: TagType) -> Self {
+        self.fields.push(Field {
+            path: path.into(),
+            tag_type,
+            required: true,
+        });
+        self
+    }
+
+    /// Adds a path which must have the given type if it is present.
+    pub fn optional(mut self, path: impl Into<String>, tag_type: TagType) -> Self {
+        self.fields.push(Field {
+            path: path.into(),
+            tag_type,
+            required: false,
+        });
+        self
+    }
+
+    /// Validates a value against this schema, returning all mismatches.
+    ///
+    /// An empty vector means that the value satisfies the schema. If a path
+    /// passes through a value which is not a compound, the mismatch is
+    /// reported for that value instead.
+    pub fn validate(&self, value: &Value) -> Vec<Mismatch> {
+        let mut mismatches = Vec::new();
+
+        for field in &self.fields {
+            if let Some(m) = field.check(value) {
+                if !mismatches.contains(&m) {
+                    mismatches.push(m);
+                }
+            }
+        }
+
+        mismatches
+    }
+}
+
+impl Field {
+    fn check(&self, root: &Value) -> Option<Mismatch> {
+        let mut value = root;
+        // The length of the path traversed so far.
+        let mut end = 0_usize;
+
+        if !self.path.is_empty() {
+            for key in self.path.split('.') {
+                let Value::Compound(compound) = value else {
+                    return Some(Mismatch {
+                        path: self.path[..end.saturating_sub(1)].to_owned(),
+                        expected: TagType::Compound,
+                        found: Some(value.tag_type()),
+                    });
+                };
+
+                end += key.len() + 1;
+
+                match compound.get(key) {
+                    Some(v) => value = v,
+                    None if self.required => {
+                        return Some(Mismatch {
+                            path: self.path.clone(),
+                            expected: self.tag_type,
+                            found: None,
+                        })
+                    }
+                    None => return None,
+                }
+            }
+        }
+
+        let found = value.tag_type();
+
+        (found != self.tag_type).then(|| Mismatch {
+            path: self.path.clone(),
+            expected: self.tag_type,
+            found: Some(found),
+        })
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{Compound, List, Value};
+
+impl Value {
+    /// Compares two values while ignoring the difference between lists and
+    /// arrays.
+    ///
+    /// This is like `==`, except that a [`List::Byte`], [`List::Int`], or
+    /// [`List::Long`] is equal to a byte, int, or long array with the same
+    /// elements. Empty lists and arrays are equal to each other no matter
+    /// their element type. This applies at every level of nesting, so
+    /// compounds and lists containing such values are compared the same way.
+    ///
+    /// The [`PartialEq`] implementation of `Value` remains strict and always
+    /// distinguishes lists from arrays. Use this method when only the logical
+    /// contents of the data are of interest. Floats are compared the same way
+    /// as by `==`, which compares their bits. See [`Value`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::{List, Value};
+    ///
+    /// let list = Value::List(List::Int(vec![1, 2, 3]));
+    /// let array = Value::IntArray(vec![1, 2, 3]);
+    ///
+    /// assert_ne!(list, array);
+    /// assert!(list.semantic_eq(&array));
+    ///
+    /// assert!(!list.semantic_eq(&Value::LongArray(vec![1, 2, 3])));
+    /// ```
+    pub fn semantic_eq(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Compound(a), Value::Compound(b)) => compound_eq(a, b),
+            (Value::List(a), Value::List(b)) => list_eq(a, b),
+            (Value::List(l), Value::ByteArray(a)) | (Value::ByteArray(a), Value::List(l)) => {
+                list_matches_array(l, ArrayRef::Byte(a))
+            }
+            (Value::List(l), Value::IntArray(a)) | (Value::IntArray(a), Value::List(l)) => {
+                list_matches_array(l, ArrayRef::Int(a))
+            }
+            (Value::List(l), Value::LongArray(a)) | (Value::LongArray(a), Value::List(l)) => {
+                list_matches_array(l, ArrayRef::Long(a))
+            }
+            _ => self == other,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum ArrayRef<'a> {
+    Byte(&'a [i8]),
+    Int(&'a [i32]),
+    Long(&'a [i64]),
+}
+
+impl ArrayRef<'_> {
+    fn is_empty(self) -> bool {
+        match self {
+            ArrayRef::Byte(a) => a.is_empty(),
+            ArrayRef::Int(a) => a.is_empty(),
+            ArrayRef::Long(a) => a.is_empty(),
+        }
+    }
+}
+
+fn compound_eq(a: &Compound, b: &Compound) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .all(|(k, v)| b.get(k).is_some_and(|w| v.semantic_eq(w)))
+}
+
+fn list_eq(a: &List, b: &List) -> bool {
+    if a.is_empty() && b.is_empty() {
+        return true;
+    }
+
+    match (a, b) {
+        (List::List(a), List::List(b)) => slices_eq(a, b, list_eq),
+        (List::Compound(a), List::Compound(b)) => slices_eq(a, b, compound_eq),
+        (List::List(l), List::ByteArray(a)) | (List::ByteArray(a), List::List(l)) => {
+            slices_eq(l, a, |l, a| list_matches_array(l, ArrayRef::Byte(a)))
+        }
+        (List::List(l), List::IntArray(a)) | (List::IntArray(a), List::List(l)) => {
+            slices_eq(l, a, |l, a| list_matches_array(l, ArrayRef::Int(a)))
+        }
+        (List::List(l), List::LongArray(a)) | (List::LongArray(a), List::List(l)) => {
+            slices_eq(l, a, |l, a| list_matches_array(l, ArrayRef::Long(a)))
+        }
+        _ => a == b,
+    }
+}
+
+fn list_matches_array(list: &List, array: ArrayRef) -> bool {
+    match (list, array) {
+        (List::Byte(l), ArrayRef::Byte(a)) => l == a,
+        (List::Int(l), ArrayRef::Int(a)) => l == a,
+        (List::Long(l), ArrayRef::Long(a)) => l == a,
+        _ => list.is_empty() && array.is_empty(),
+    }
+}
+
+fn slices_eq<A, B>(a: &[A], b: &[B], mut eq: impl FnMut(&A, &B) -> bool) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| eq(a, b))
+}
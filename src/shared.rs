@@ -0,0 +1,111 @@
+use std::ops::Deref;
+use std::sync::Arc;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::Value;
+
+/// An immutable, reference counted NBT [`Value`].
+///
+/// Cloning a `SharedValue` is cheap because it only increments a reference
+/// count instead of deep-copying the whole tree. This is useful when the same
+/// NBT data needs to be sent to many recipients, such as when broadcasting
+/// item NBT to every client on a server.
+///
+/// `SharedValue` serializes and deserializes exactly like the `Value` it
+/// contains.
+///
+/// # Examples
+///
+/// ```
+/// use serde_nbt::binary::to_writer;
+/// use serde_nbt::{Compound, SharedValue, Value};
+///
+/// let value = SharedValue::new(Value::Compound(Compound::from_iter([(
+///     "int".to_owned(),
+///     Value::Int(0xdead),
+/// )])));
+///
+/// for _ in 0..10 {
+///     let value = value.clone();
+///
+///     let mut buf = Vec::new();
+///     to_writer(&mut buf, &value).unwrap();
+/// }
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct SharedValue(Arc<Value>);
+
+impl SharedValue {
+    /// Wraps the given value.
+    pub fn new(value: Value) -> Self {
+        Self(Arc::new(value))
+    }
+
+    /// Returns a mutable reference to the contained value, cloning it first
+    /// if it is shared with other `SharedValue`s.
+    pub fn make_mut(&mut self) -> &mut Value {
+        Arc::make_mut(&mut self.0)
+    }
+
+    /// Returns the contained value, cloning it if it is shared with other
+    /// `SharedValue`s.
+    pub fn into_inner(self) -> Value {
+        Arc::try_unwrap(self.0).unwrap_or_else(|arc| (*arc).clone())
+    }
+
+    /// Returns `true` if both `SharedValue`s point to the same allocation.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        Arc::ptr_eq(&this.0, &other.0)
+    }
+}
+
+impl Deref for SharedValue {
+    type Target = Value;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<Value> for SharedValue {
+    fn as_ref(&self) -> &Value {
+        &self.0
+    }
+}
+
+impl From<Value> for SharedValue {
+    fn from(v: Value) -> Self {
+        Self::new(v)
+    }
+}
+
+impl From<Arc<Value>> for SharedValue {
+    fn from(v: Arc<Value>) -> Self {
+        Self(v)
+    }
+}
+
+impl From<SharedValue> for Arc<Value> {
+    fn from(v: SharedValue) -> Self {
+        v.0
+    }
+}
+
+impl Serialize for SharedValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SharedValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Value::deserialize(deserializer).map(Self::new)
+    }
+}
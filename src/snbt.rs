@@ -0,0 +1,118 @@
+//! Support for stringified NBT (SNBT), the textual representation of NBT used
+//! by Minecraft commands.
+//!
+//! # Examples
+//!
+//! ```
+//! use serde_nbt::snbt::to_string;
+//! use serde_nbt::{Compound, List, Value};
+//!
+//! let value = Value::Compound(Compound::from_iter([
+//!     ("name".to_owned(), Value::String("Steve".to_owned())),
+//!     ("pos".to_owned(), List::Double(vec![1.0, 64.0, -3.5]).into()),
+//! ]));
+//!
+//! assert_eq!(to_string(&value).unwrap(), r#"{name:"Steve",pos:[1.0d,64.0d,-3.5d]}"#);
+//! ```
+//!
+//! Parsing SNBT works in the other direction.
+//!
+//! ```
+//! use serde::Deserialize;
+//! use serde_nbt::snbt::from_str;
+//!
+//! #[derive(Deserialize)]
+//! struct Player {
+//!     name: String,
+//!     pos: Vec<f64>,
+//! }
+//!
+//! let player: Player = from_str(r#"{name:"Steve",pos:[1.0d,64.0d,-3.5d]}"#).unwrap();
+//!
+//! assert_eq!(player.name, "Steve");
+//! assert_eq!(player.pos, [1.0, 64.0, -3.5]);
+//! ```
+
+use std::io::Read;
+
+pub(crate) use parse::*;
+pub use pretty::SnbtFormatter;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+pub(crate) use write::*;
+
+use crate::binary::{from_slice_exact, to_vec};
+use crate::transcode::snbt_to_binary;
+use crate::{Error, Result, Value};
+
+mod display;
+mod parse;
+mod pretty;
+mod write;
+
+/// Serializes a value to a compact SNBT string with no whitespace.
+///
+/// Like the binary serializer, this fails if the value does not serialize as
+/// a compound.
+pub fn to_string<T>(value: &T) -> Result<String>
+where
+    T: Serialize + ?Sized,
+{
+    let value = to_value(value)?;
+
+    let mut s = String::new();
+    Emitter::new(&mut s)
+        .value(&value)
+        .map_err(|_| Error::new_static("failed to write SNBT"))?;
+
+    Ok(s)
+}
+
+/// Serializes a value to an indented SNBT string.
+///
+/// This uses the default options of [`SnbtFormatter`]. Use a formatter
+/// directly to control indentation and line wrapping.
+pub fn to_string_pretty<T>(value: &T) -> Result<String>
+where
+    T: Serialize + ?Sized,
+{
+    SnbtFormatter::new().to_string(value)
+}
+
+/// Parses an SNBT document from a string.
+///
+/// The document must contain a single compound. Whitespace is allowed
+/// around it.
+pub fn from_str<T>(s: &str) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    from_reader(s.as_bytes())
+}
+
+/// Parses an SNBT document from the provided reader.
+///
+/// The text is parsed incrementally as it is read, so the text itself never
+/// has to be held in memory as a whole. However, the document is converted to
+/// the binary format in a buffer, and only then deserialized. The whole
+/// document is held in memory in its binary form, which is usually much
+/// smaller than the text.
+///
+/// The reader is accessed in small pieces, so buffering it is recommended.
+pub fn from_reader<R, T>(reader: R) -> Result<T>
+where
+    R: Read,
+    T: DeserializeOwned,
+{
+    let mut buf = Vec::new();
+    snbt_to_binary(reader, &mut buf)?;
+    from_slice_exact(&buf)
+}
+
+/// Converts a serializable value to a [`Value`] by way of the binary format.
+pub(crate) fn to_value<T>(value: &T) -> Result<Value>
+where
+    T: Serialize + ?Sized,
+{
+    from_slice_exact(&to_vec(value)?)
+}
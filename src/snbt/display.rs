@@ -0,0 +1,58 @@
+use std::fmt;
+
+use crate::snbt::{Emitter, SnbtFormatter};
+use crate::{Compound, List, Value};
+
+/// Formats the value as compact SNBT. The alternate flag (`{:#}`) selects the
+/// indented form produced by [`SnbtFormatter`] with its default options.
+///
+/// # Examples
+///
+/// ```
+/// use serde_nbt::{Compound, List, Value};
+///
+/// let value = Value::Compound(Compound::from_iter([
+///     ("id".to_owned(), Value::String("minecraft:stone".to_owned())),
+///     ("Count".to_owned(), Value::Byte(64)),
+/// ]));
+///
+/// assert_eq!(value.to_string(), r#"{id:"minecraft:stone",Count:64b}"#);
+/// assert_eq!(
+///     format!("{value:#}"),
+///     r#"{id: "minecraft:stone", Count: 64b}"#
+/// );
+///
+/// assert_eq!(List::Int(vec![1, 2, 3]).to_string(), "[1,2,3]");
+/// ```
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.write_str(&SnbtFormatter::new().print(|p| p.value(self))?)
+        } else {
+            Emitter::new(f).value(self)
+        }
+    }
+}
+
+/// Formats the compound as SNBT, like the `Display` implementation of
+/// [`Value`].
+impl fmt::Display for Compound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.write_str(&SnbtFormatter::new().print(|p| p.compound(self))?)
+        } else {
+            Emitter::new(f).compound(self)
+        }
+    }
+}
+
+/// Formats the list as SNBT, like the `Display` implementation of [`Value`].
+impl fmt::Display for List {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.write_str(&SnbtFormatter::new().print(|p| p.list(self))?)
+        } else {
+            Emitter::new(f).list(self)
+        }
+    }
+}
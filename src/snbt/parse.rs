@@ -0,0 +1,418 @@
+use std::io;
+use std::io::Read;
+
+use crate::snbt::is_unquoted_char;
+use crate::{ArrayType, Error, Result};
+
+/// The maximum nesting depth of compounds and lists accepted by the parser.
+const MAX_DEPTH: usize = 512;
+
+/// A non-container value produced by the parser.
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) enum Scalar {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(String),
+}
+
+/// Receives the values encountered by the [`Parser`] in document order.
+pub(crate) trait Sink {
+    fn begin_compound(&mut self) -> Result<()>;
+    fn key(&mut self, key: String) -> Result<()>;
+    fn end_compound(&mut self) -> Result<()>;
+    fn begin_list(&mut self) -> Result<()>;
+    fn begin_array(&mut self, array_type: ArrayType) -> Result<()>;
+    /// Ends a list or array.
+    fn end_list(&mut self) -> Result<()>;
+    fn scalar(&mut self, scalar: Scalar) -> Result<()>;
+}
+
+/// A streaming SNBT parser.
+///
+/// Bytes are pulled from the reader one at a time, so a buffered reader
+/// should be used for good performance.
+pub(crate) struct Parser<R> {
+    reader: R,
+    /// Bytes which have been read but not yet consumed.
+    lookahead: [u8; 2],
+    lookahead_len: usize,
+    /// The offset of the next byte to be consumed. Used in error messages.
+    pos: u64,
+    depth: usize,
+}
+
+impl<R: Read> Parser<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            lookahead: [0; 2],
+            lookahead_len: 0,
+            pos: 0,
+            depth: 0,
+        }
+    }
+
+    /// Parses a single value followed by optional whitespace and the end of
+    /// the input.
+    pub fn parse_document(&mut self, sink: &mut impl Sink) -> Result<()> {
+        self.parse_value(sink)?;
+        self.skip_whitespace()?;
+
+        match self.peek()? {
+            None => Ok(()),
+            Some(b) => Err(self.unexpected(b, "end of input")),
+        }
+    }
+
+    fn fill(&mut self, n: usize) -> Result<()> {
+        while self.lookahead_len < n {
+            let mut byte = [0];
+            match self.reader.read(&mut byte) {
+                Ok(0) => break,
+                Ok(_) => {
+                    self.lookahead[self.lookahead_len] = byte[0];
+                    self.lookahead_len += 1;
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>> {
+        self.fill(1)?;
+        Ok((self.lookahead_len > 0).then_some(self.lookahead[0]))
+    }
+
+    fn peek_second(&mut self) -> Result<Option<u8>> {
+        self.fill(2)?;
+        Ok((self.lookahead_len > 1).then_some(self.lookahead[1]))
+    }
+
+    fn next(&mut self) -> Result<Option<u8>> {
+        let b = self.peek()?;
+
+        if b.is_some() {
+            self.lookahead[0] = self.lookahead[1];
+            self.lookahead_len -= 1;
+            self.pos += 1;
+        }
+
+        Ok(b)
+    }
+
+    fn next_or_eof_error(&mut self) -> Result<u8> {
+        match self.next()? {
+            Some(b) => Ok(b),
+            None => Err(self.eof_error()),
+        }
+    }
+
+    fn eof_error(&self) -> Error {
+        Error::new_owned(format!(
+            "unexpected end of SNBT input at offset {}",
+            self.pos
+        ))
+    }
+
+    fn skip_whitespace(&mut self) -> Result<()> {
+        while let Some(b) = self.peek()? {
+            if !b.is_ascii_whitespace() {
+                break;
+            }
+            self.next()?;
+        }
+
+        Ok(())
+    }
+
+    fn unexpected(&self, found: u8, expected: &str) -> Error {
+        Error::new_owned(format!(
+            "expected {expected}, found `{}` at offset {} in SNBT input",
+            found.escape_ascii(),
+            self.pos
+        ))
+    }
+
+    /// Consumes the next non-whitespace byte, which must be `expected`.
+    fn expect(&mut self, expected: u8) -> Result<()> {
+        self.skip_whitespace()?;
+
+        match self.next_or_eof_error()? {
+            b if b == expected => Ok(()),
+            b => Err(self.unexpected(b, &format!("`{}`", expected as char))),
+        }
+    }
+
+    fn enter(&mut self) -> Result<()> {
+        self.depth += 1;
+
+        if self.depth > MAX_DEPTH {
+            return Err(Error::new_owned(format!(
+                "SNBT exceeds the maximum nesting depth of {MAX_DEPTH}"
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn parse_value(&mut self, sink: &mut impl Sink) -> Result<()> {
+        self.skip_whitespace()?;
+
+        match self.peek()? {
+            Some(b'{') => self.parse_compound(sink),
+            Some(b'[') => self.parse_list_or_array(sink),
+            Some(_) => {
+                let scalar = self.parse_scalar()?;
+                sink.scalar(scalar)
+            }
+            None => Err(self.eof_error()),
+        }
+    }
+
+    fn parse_compound(&mut self, sink: &mut impl Sink) -> Result<()> {
+        self.expect(b'{')?;
+        self.enter()?;
+        sink.begin_compound()?;
+
+        self.skip_whitespace()?;
+        if self.peek()? == Some(b'}') {
+            self.next()?;
+        } else {
+            loop {
+                let key = self.parse_key()?;
+                self.expect(b':')?;
+                sink.key(key.clone())?;
+                self.parse_value(sink).map_err(|e| e.field(key))?;
+
+                self.skip_whitespace()?;
+                match self.next_or_eof_error()? {
+                    b',' => {}
+                    b'}' => break,
+                    b => return Err(self.unexpected(b, "`,` or `}`")),
+                }
+            }
+        }
+
+        self.depth -= 1;
+        sink.end_compound()
+    }
+
+    fn parse_key(&mut self) -> Result<String> {
+        self.skip_whitespace()?;
+
+        match self.peek()? {
+            Some(b'"' | b'\'') => self.parse_quoted(),
+            _ => {
+                let key = self.parse_unquoted()?;
+
+                if key.is_empty() {
+                    let b = self.next_or_eof_error()?;
+                    return Err(self.unexpected(b, "a compound key"));
+                }
+
+                Ok(key)
+            }
+        }
+    }
+
+    fn parse_list_or_array(&mut self, sink: &mut impl Sink) -> Result<()> {
+        self.expect(b'[')?;
+        self.enter()?;
+
+        let array_type = if self.peek_second()? == Some(b';') {
+            match self.peek()? {
+                Some(b'B') => Some(ArrayType::Byte),
+                Some(b'I') => Some(ArrayType::Int),
+                Some(b'L') => Some(ArrayType::Long),
+                _ => None,
+            }
+        } else {
+            None
+        };
+
+        match array_type {
+            Some(array_type) => {
+                self.next()?;
+                self.next()?;
+                sink.begin_array(array_type)?;
+            }
+            None => sink.begin_list()?,
+        }
+
+        self.skip_whitespace()?;
+        if self.peek()? == Some(b']') {
+            self.next()?;
+        } else {
+            let mut index = 0;
+
+            loop {
+                match array_type {
+                    Some(array_type) => {
+                        let elem = self.parse_array_element(array_type);
+                        elem.and_then(|s| sink.scalar(s))
+                    }
+                    None => self.parse_value(sink),
+                }
+                .map_err(|e| e.index(index))?;
+
+                index += 1;
+
+                self.skip_whitespace()?;
+                match self.next_or_eof_error()? {
+                    b',' => {}
+                    b']' => break,
+                    b => return Err(self.unexpected(b, "`,` or `]`")),
+                }
+            }
+        }
+
+        self.depth -= 1;
+        sink.end_list()
+    }
+
+    /// Parses an array element, converting any integer to the element type of
+    /// the array.
+    fn parse_array_element(&mut self, array_type: ArrayType) -> Result<Scalar> {
+        self.skip_whitespace()?;
+
+        let n = match self.parse_scalar()? {
+            Scalar::Byte(n) => n as i64,
+            Scalar::Short(n) => n as i64,
+            Scalar::Int(n) => n as i64,
+            Scalar::Long(n) => n,
+            _ => return Err(Error::new_static("array elements must be integers")),
+        };
+
+        let scalar = match array_type {
+            ArrayType::Byte => i8::try_from(n).ok().map(Scalar::Byte),
+            ArrayType::Int => i32::try_from(n).ok().map(Scalar::Int),
+            ArrayType::Long => Some(Scalar::Long(n)),
+        };
+
+        scalar.ok_or_else(|| Error::new_owned(format!("array element `{n}` is out of range")))
+    }
+
+    fn parse_scalar(&mut self) -> Result<Scalar> {
+        match self.peek()? {
+            Some(b'"' | b'\'') => Ok(Scalar::String(self.parse_quoted()?)),
+            _ => {
+                let token = self.parse_unquoted()?;
+
+                if token.is_empty() {
+                    let b = self.next_or_eof_error()?;
+                    return Err(self.unexpected(b, "a value"));
+                }
+
+                Ok(classify(token))
+            }
+        }
+    }
+
+    fn parse_unquoted(&mut self) -> Result<String> {
+        let mut token = String::new();
+
+        while let Some(b) = self.peek()? {
+            if !is_unquoted_char(b as char) {
+                break;
+            }
+            token.push(b as char);
+            self.next()?;
+        }
+
+        Ok(token)
+    }
+
+    fn parse_quoted(&mut self) -> Result<String> {
+        let quote = self.next_or_eof_error()?;
+        let mut bytes = Vec::new();
+
+        loop {
+            match self.next_or_eof_error()? {
+                b if b == quote => break,
+                b'\\' => {
+                    let c = match self.next_or_eof_error()? {
+                        b'\\' => '\\',
+                        b'"' => '"',
+                        b'\'' => '\'',
+                        b'n' => '\n',
+                        b't' => '\t',
+                        b'r' => '\r',
+                        b'u' => self.parse_unicode_escape()?,
+                        b => return Err(self.unexpected(b, "an escape sequence")),
+                    };
+
+                    let mut buf = [0; 4];
+                    bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                }
+                b => bytes.push(b),
+            }
+        }
+
+        String::from_utf8(bytes).map_err(|_| Error::new_static("invalid UTF-8 in SNBT string"))
+    }
+
+    fn parse_unicode_escape(&mut self) -> Result<char> {
+        let mut code = 0;
+
+        for _ in 0..4 {
+            let b = self.next_or_eof_error()?;
+            match (b as char).to_digit(16) {
+                Some(d) => code = code * 16 + d,
+                None => return Err(self.unexpected(b, "a hexadecimal digit")),
+            }
+        }
+
+        char::from_u32(code)
+            .ok_or_else(|| Error::new_owned(format!("invalid unicode escape `\\u{code:04x}`")))
+    }
+}
+
+/// Determines the type of an unquoted token. Tokens which are not valid
+/// numbers or booleans are strings.
+fn classify(token: String) -> Scalar {
+    match token.as_str() {
+        "true" => return Scalar::Byte(1),
+        "false" => return Scalar::Byte(0),
+        _ => {}
+    }
+
+    let (body, suffix) = token.split_at(token.len() - 1);
+
+    let scalar = match suffix {
+        "b" | "B" => body.parse().ok().map(Scalar::Byte),
+        "s" | "S" => body.parse().ok().map(Scalar::Short),
+        "l" | "L" => body.parse().ok().map(Scalar::Long),
+        "f" | "F" if is_float_literal(body) => body.parse().ok().map(Scalar::Float),
+        "d" | "D" if is_float_literal(body) => body.parse().ok().map(Scalar::Double),
+        _ => None,
+    };
+
+    scalar
+        .or_else(|| token.parse().ok().map(Scalar::Int))
+        .or_else(|| {
+            is_float_literal(&token)
+                .then(|| token.parse().ok().map(Scalar::Double))
+                .flatten()
+        })
+        .unwrap_or(Scalar::String(token))
+}
+
+/// Returns `true` if the string looks like a floating point number. Rust
+/// accepts words like `inf` which should be strings in SNBT.
+fn is_float_literal(s: &str) -> bool {
+    let unsigned = s.strip_prefix(['+', '-']).unwrap_or(s);
+
+    unsigned == "NaN"
+        || unsigned == "Infinity"
+        || (unsigned.bytes().any(|b| b.is_ascii_digit())
+            && unsigned
+                .bytes()
+                .all(|b| matches!(b, b'0'..=b'9' | b'.' | b'e' | b'E' | b'+' | b'-')))
+}
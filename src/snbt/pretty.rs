@@ -0,0 +1,330 @@
+use std::fmt;
+use std::fmt::Write;
+
+use serde::Serialize;
+
+use crate::snbt::{array_prefix, to_value, Emitter};
+use crate::{ArrayType, Compound, Error, List, Result, Value};
+
+/// Formatting options for pretty SNBT output.
+///
+/// Created with [`SnbtFormatter::new`], configured with the builder methods,
+/// and used with [`SnbtFormatter::to_string`]. The default options are the
+/// ones used by [`to_string_pretty`](crate::snbt::to_string_pretty).
+///
+/// # Examples
+///
+/// ```
+/// use serde_nbt::snbt::SnbtFormatter;
+/// use serde_nbt::{Compound, Value};
+///
+/// let value = Value::Compound(Compound::from_iter([(
+///     "heights".to_owned(),
+///     Value::IntArray((0..6).collect()),
+/// )]));
+///
+/// let snbt = SnbtFormatter::new()
+///     .indent("  ")
+///     .wrap_arrays(Some(3))
+///     .max_inline_width(None)
+///     .to_string(&value)
+///     .unwrap();
+///
+/// assert_eq!(snbt, "{\n  heights: [I;\n    0, 1, 2,\n    3, 4, 5\n  ]\n}");
+/// ```
+#[derive(Clone, Debug)]
+pub struct SnbtFormatter {
+    indent: String,
+    wrap_arrays: Option<usize>,
+    max_inline_width: Option<usize>,
+}
+
+impl SnbtFormatter {
+    /// Creates a formatter with the default options.
+    ///
+    /// Nested values are indented with four spaces, arrays and lists of
+    /// numbers or strings wrap after 16 elements per line, and compounds and
+    /// lists which fit in 40 characters stay on one line.
+    pub fn new() -> Self {
+        Self {
+            indent: "    ".to_owned(),
+            wrap_arrays: Some(16),
+            max_inline_width: Some(40),
+        }
+    }
+
+    /// Sets the string used for each level of indentation.
+    pub fn indent(mut self, indent: impl Into<String>) -> Self {
+        self.indent = indent.into();
+        self
+    }
+
+    /// Sets how many elements of an array, or of a list of numbers or
+    /// strings, are written per line.
+    ///
+    /// Sequences with more elements than this are wrapped across several
+    /// lines. If `None`, they are always written on one line.
+    pub fn wrap_arrays(mut self, elements_per_line: Option<usize>) -> Self {
+        self.wrap_arrays = elements_per_line;
+        self
+    }
+
+    /// Sets the maximum width in characters of a compound or list that is
+    /// kept on one line.
+    ///
+    /// Compounds and lists whose single-line form is longer than this are
+    /// written with one entry per line. If `None`, non-empty compounds and
+    /// lists of containers are never kept on one line.
+    pub fn max_inline_width(mut self, width: Option<usize>) -> Self {
+        self.max_inline_width = width;
+        self
+    }
+
+    /// Serializes a value to a pretty SNBT string using these options.
+    ///
+    /// Like the binary serializer, this fails if the value does not serialize
+    /// as a compound.
+    pub fn to_string<T>(&self, value: &T) -> Result<String>
+    where
+        T: Serialize + ?Sized,
+    {
+        let value = to_value(value)?;
+
+        self.print(|p| p.value(&value))
+            .map_err(|_| Error::new_static("failed to write SNBT"))
+    }
+
+    /// Runs `print` with a new printer using these options and returns the
+    /// output.
+    pub(super) fn print(
+        &self,
+        print: impl FnOnce(&mut Printer) -> fmt::Result,
+    ) -> std::result::Result<String, fmt::Error> {
+        let mut printer = Printer {
+            options: self,
+            em: Emitter::spaced(String::new()),
+            level: 0,
+        };
+
+        print(&mut printer)?;
+
+        let Printer { mut em, .. } = printer;
+        Ok(std::mem::take(em.get_mut()))
+    }
+}
+
+impl Default for SnbtFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub(super) struct Printer<'a> {
+    options: &'a SnbtFormatter,
+    /// Only used to write scalars and keys. Separators and brackets are
+    /// written by the printer directly.
+    em: Emitter<String>,
+    level: usize,
+}
+
+impl Printer<'_> {
+    fn out(&mut self) -> &mut String {
+        self.em.get_mut()
+    }
+
+    fn newline(&mut self) {
+        let out = self.em.get_mut();
+        out.push('\n');
+
+        for _ in 0..self.level {
+            out.push_str(&self.options.indent);
+        }
+    }
+
+    /// Writes the value on one line if it fits within the inline width.
+    fn try_inline(&mut self, emit: impl FnOnce(&mut Emitter<Limited>) -> fmt::Result) -> bool {
+        let Some(max) = self.options.max_inline_width else {
+            return false;
+        };
+
+        let mut em = Emitter::spaced(Limited {
+            out: String::new(),
+            remaining: max,
+        });
+
+        if emit(&mut em).is_ok() {
+            self.out().push_str(&em.get_mut().out);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub(super) fn value(&mut self, value: &Value) -> fmt::Result {
+        match value {
+            Value::ByteArray(v) => self.seq(Some(ArrayType::Byte), v, |em, v| em.byte(*v)),
+            Value::List(v) => self.list(v),
+            Value::Compound(v) => self.compound(v),
+            Value::IntArray(v) => self.seq(Some(ArrayType::Int), v, |em, v| em.int(*v)),
+            Value::LongArray(v) => self.seq(Some(ArrayType::Long), v, |em, v| em.long(*v)),
+            v => self.em.value(v),
+        }
+    }
+
+    pub(super) fn compound(&mut self, compound: &Compound) -> fmt::Result {
+        if compound.is_empty() {
+            self.out().push_str("{}");
+            return Ok(());
+        }
+
+        if self.try_inline(|em| em.compound(compound)) {
+            return Ok(());
+        }
+
+        self.out().push('{');
+        self.level += 1;
+
+        for (i, (k, v)) in compound.iter().enumerate() {
+            if i > 0 {
+                self.out().push(',');
+            }
+            self.newline();
+            self.em.key(k)?;
+            self.value(v)?;
+        }
+
+        self.level -= 1;
+        self.newline();
+        self.out().push('}');
+
+        Ok(())
+    }
+
+    pub(super) fn list(&mut self, list: &List) -> fmt::Result {
+        match list {
+            List::Byte(l) => self.seq(None, l, |em, v| em.byte(*v)),
+            List::Short(l) => self.seq(None, l, |em, v| em.short(*v)),
+            List::Int(l) => self.seq(None, l, |em, v| em.int(*v)),
+            List::Long(l) => self.seq(None, l, |em, v| em.long(*v)),
+            List::Float(l) => self.seq(None, l, |em, v| em.float(*v)),
+            List::Double(l) => self.seq(None, l, |em, v| em.double(*v)),
+            List::String(l) => self.seq(None, l, |em, s: &String| em.string(s)),
+            List::ByteArray(l) => self.nested(list, l, |p, v| {
+                p.seq(Some(ArrayType::Byte), v, |em, v| em.byte(*v))
+            }),
+            List::List(l) => self.nested(list, l, Self::list),
+            List::Compound(l) => self.nested(list, l, Self::compound),
+            List::IntArray(l) => self.nested(list, l, |p, v| {
+                p.seq(Some(ArrayType::Int), v, |em, v| em.int(*v))
+            }),
+            List::LongArray(l) => self.nested(list, l, |p, v| {
+                p.seq(Some(ArrayType::Long), v, |em, v| em.long(*v))
+            }),
+        }
+    }
+
+    /// Writes a list of containers, one element per line unless it fits
+    /// inline.
+    fn nested<T>(
+        &mut self,
+        list: &List,
+        elements: &[T],
+        mut emit: impl FnMut(&mut Self, &T) -> fmt::Result,
+    ) -> fmt::Result {
+        if elements.is_empty() {
+            self.out().push_str("[]");
+            return Ok(());
+        }
+
+        if self.try_inline(|em| em.list(list)) {
+            return Ok(());
+        }
+
+        self.out().push('[');
+        self.level += 1;
+
+        for (i, elem) in elements.iter().enumerate() {
+            if i > 0 {
+                self.out().push(',');
+            }
+            self.newline();
+            emit(self, elem)?;
+        }
+
+        self.level -= 1;
+        self.newline();
+        self.out().push(']');
+
+        Ok(())
+    }
+
+    /// Writes an array, or a list of scalars, wrapping it if it is long.
+    fn seq<'a, T: 'a>(
+        &mut self,
+        array_type: Option<ArrayType>,
+        elements: &'a [T],
+        mut emit: impl FnMut(&mut Emitter<String>, &'a T) -> fmt::Result,
+    ) -> fmt::Result {
+        self.out().push('[');
+        if let Some(array_type) = array_type {
+            self.out().push_str(array_prefix(array_type));
+        }
+
+        match self.options.wrap_arrays {
+            Some(n) if elements.len() > n && n > 0 => {
+                self.level += 1;
+
+                for (i, chunk) in elements.chunks(n).enumerate() {
+                    if i > 0 {
+                        self.out().push(',');
+                    }
+                    self.newline();
+
+                    for (j, elem) in chunk.iter().enumerate() {
+                        if j > 0 {
+                            self.out().push_str(", ");
+                        }
+                        emit(&mut self.em, elem)?;
+                    }
+                }
+
+                self.level -= 1;
+                self.newline();
+            }
+            _ => {
+                for (i, elem) in elements.iter().enumerate() {
+                    if i > 0 {
+                        self.out().push_str(", ");
+                    } else if array_type.is_some() {
+                        self.out().push(' ');
+                    }
+                    emit(&mut self.em, elem)?;
+                }
+            }
+        }
+
+        self.out().push(']');
+        Ok(())
+    }
+}
+
+/// A writer which fails once more than a fixed number of characters have
+/// been written.
+struct Limited {
+    out: String,
+    remaining: usize,
+}
+
+impl Write for Limited {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let len = s.chars().count();
+
+        if len > self.remaining {
+            return Err(fmt::Error);
+        }
+
+        self.remaining -= len;
+        self.out.push_str(s);
+        Ok(())
+    }
+}
@@ -0,0 +1,310 @@
+use std::fmt::Write;
+use std::{fmt, io};
+
+use crate::{ArrayType, Compound, List, Value};
+
+/// Writes SNBT text one token at a time.
+///
+/// The emitter keeps track of the containers it is in so that separators are
+/// written in the right places. It does not validate that the sequence of
+/// calls produces well-formed NBT. That is the caller's responsibility.
+pub(crate) struct Emitter<W> {
+    out: W,
+    stack: Vec<Frame>,
+    /// Whether to put spaces after separators.
+    spaced: bool,
+}
+
+#[derive(Clone, Copy)]
+struct Frame {
+    kind: FrameKind,
+    /// Whether nothing has been written to this container yet.
+    first: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FrameKind {
+    Compound,
+    List,
+    Array,
+}
+
+impl<W: Write> Emitter<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            out,
+            stack: Vec::new(),
+            spaced: false,
+        }
+    }
+
+    /// Creates an emitter which writes `, ` and `: ` instead of `,` and `:`.
+    pub fn spaced(out: W) -> Self {
+        Self {
+            out,
+            stack: Vec::new(),
+            spaced: true,
+        }
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.out
+    }
+
+    /// Writes a separator if a value in a list or array is about to be
+    /// written.
+    fn begin_value(&mut self) -> fmt::Result {
+        if let Some(frame) = self.stack.last_mut() {
+            if frame.kind != FrameKind::Compound {
+                if !frame.first {
+                    self.out.write_str(if self.spaced { ", " } else { "," })?;
+                } else if frame.kind == FrameKind::Array && self.spaced {
+                    self.out.write_char(' ')?;
+                }
+                frame.first = false;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn begin_compound(&mut self) -> fmt::Result {
+        self.begin_value()?;
+        self.stack.push(Frame {
+            kind: FrameKind::Compound,
+            first: true,
+        });
+        self.out.write_char('{')
+    }
+
+    pub fn key(&mut self, key: &str) -> fmt::Result {
+        if let Some(frame) = self.stack.last_mut() {
+            if !frame.first {
+                self.out.write_str(if self.spaced { ", " } else { "," })?;
+            }
+            frame.first = false;
+        }
+
+        write_key(&mut self.out, key)?;
+        self.out.write_str(if self.spaced { ": " } else { ":" })
+    }
+
+    pub fn end_compound(&mut self) -> fmt::Result {
+        self.stack.pop();
+        self.out.write_char('}')
+    }
+
+    pub fn begin_list(&mut self) -> fmt::Result {
+        self.begin_value()?;
+        self.stack.push(Frame {
+            kind: FrameKind::List,
+            first: true,
+        });
+        self.out.write_char('[')
+    }
+
+    pub fn begin_array(&mut self, array_type: ArrayType) -> fmt::Result {
+        self.begin_value()?;
+        self.stack.push(Frame {
+            kind: FrameKind::Array,
+            first: true,
+        });
+        self.out.write_char('[')?;
+        self.out.write_str(array_prefix(array_type))
+    }
+
+    /// Ends a list or array.
+    pub fn end_list(&mut self) -> fmt::Result {
+        self.stack.pop();
+        self.out.write_char(']')
+    }
+
+    pub fn byte(&mut self, v: i8) -> fmt::Result {
+        self.begin_value()?;
+        write!(self.out, "{v}b")
+    }
+
+    pub fn short(&mut self, v: i16) -> fmt::Result {
+        self.begin_value()?;
+        write!(self.out, "{v}s")
+    }
+
+    pub fn int(&mut self, v: i32) -> fmt::Result {
+        self.begin_value()?;
+        write!(self.out, "{v}")
+    }
+
+    pub fn long(&mut self, v: i64) -> fmt::Result {
+        self.begin_value()?;
+        write!(self.out, "{v}L")
+    }
+
+    pub fn float(&mut self, v: f32) -> fmt::Result {
+        self.begin_value()?;
+
+        if v.is_finite() {
+            // The `Debug` impl gives the shortest representation that
+            // round-trips.
+            write!(self.out, "{v:?}f")
+        } else {
+            write_non_finite(&mut self.out, v as f64)?;
+            self.out.write_char('f')
+        }
+    }
+
+    pub fn double(&mut self, v: f64) -> fmt::Result {
+        self.begin_value()?;
+
+        if v.is_finite() {
+            write!(self.out, "{v:?}d")
+        } else {
+            write_non_finite(&mut self.out, v)?;
+            self.out.write_char('d')
+        }
+    }
+
+    pub fn string(&mut self, v: &str) -> fmt::Result {
+        self.begin_value()?;
+        write_quoted(&mut self.out, v)
+    }
+
+    pub fn value(&mut self, value: &Value) -> fmt::Result {
+        match value {
+            Value::Byte(v) => self.byte(*v),
+            Value::Short(v) => self.short(*v),
+            Value::Int(v) => self.int(*v),
+            Value::Long(v) => self.long(*v),
+            Value::Float(v) => self.float(*v),
+            Value::Double(v) => self.double(*v),
+            Value::ByteArray(v) => self.array(ArrayType::Byte, v, Self::byte),
+            Value::String(v) => self.string(v),
+            Value::List(v) => self.list(v),
+            Value::Compound(v) => self.compound(v),
+            Value::IntArray(v) => self.array(ArrayType::Int, v, Self::int),
+            Value::LongArray(v) => self.array(ArrayType::Long, v, Self::long),
+        }
+    }
+
+    pub fn compound(&mut self, compound: &Compound) -> fmt::Result {
+        self.begin_compound()?;
+
+        for (k, v) in compound {
+            self.key(k)?;
+            self.value(v)?;
+        }
+
+        self.end_compound()
+    }
+
+    pub fn list(&mut self, list: &List) -> fmt::Result {
+        self.begin_list()?;
+
+        match list {
+            List::Byte(l) => l.iter().try_for_each(|v| self.byte(*v))?,
+            List::Short(l) => l.iter().try_for_each(|v| self.short(*v))?,
+            List::Int(l) => l.iter().try_for_each(|v| self.int(*v))?,
+            List::Long(l) => l.iter().try_for_each(|v| self.long(*v))?,
+            List::Float(l) => l.iter().try_for_each(|v| self.float(*v))?,
+            List::Double(l) => l.iter().try_for_each(|v| self.double(*v))?,
+            List::ByteArray(l) => l
+                .iter()
+                .try_for_each(|v| self.array(ArrayType::Byte, v, Self::byte))?,
+            List::String(l) => l.iter().try_for_each(|v| self.string(v))?,
+            List::List(l) => l.iter().try_for_each(|v| self.list(v))?,
+            List::Compound(l) => l.iter().try_for_each(|v| self.compound(v))?,
+            List::IntArray(l) => l
+                .iter()
+                .try_for_each(|v| self.array(ArrayType::Int, v, Self::int))?,
+            List::LongArray(l) => l
+                .iter()
+                .try_for_each(|v| self.array(ArrayType::Long, v, Self::long))?,
+        }
+
+        self.end_list()
+    }
+
+    fn array<T: Copy>(
+        &mut self,
+        array_type: ArrayType,
+        elements: &[T],
+        mut emit: impl FnMut(&mut Self, T) -> fmt::Result,
+    ) -> fmt::Result {
+        self.begin_array(array_type)?;
+
+        for &elem in elements {
+            emit(self, elem)?;
+        }
+
+        self.end_list()
+    }
+}
+
+pub(crate) fn array_prefix(array_type: ArrayType) -> &'static str {
+    match array_type {
+        ArrayType::Byte => "B;",
+        ArrayType::Int => "I;",
+        ArrayType::Long => "L;",
+    }
+}
+
+fn write_non_finite(out: &mut impl Write, v: f64) -> fmt::Result {
+    if v.is_nan() {
+        out.write_str("NaN")
+    } else if v > 0.0 {
+        out.write_str("Infinity")
+    } else {
+        out.write_str("-Infinity")
+    }
+}
+
+/// Returns `true` if the character may appear in an unquoted string.
+pub(crate) fn is_unquoted_char(c: char) -> bool {
+    matches!(c, '0'..='9' | 'a'..='z' | 'A'..='Z' | '_' | '-' | '.' | '+')
+}
+
+fn write_key(out: &mut impl Write, key: &str) -> fmt::Result {
+    if !key.is_empty() && key.chars().all(is_unquoted_char) {
+        out.write_str(key)
+    } else {
+        write_quoted(out, key)
+    }
+}
+
+fn write_quoted(out: &mut impl Write, s: &str) -> fmt::Result {
+    out.write_char('"')?;
+
+    for c in s.chars() {
+        match c {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            c => out.write_char(c)?,
+        }
+    }
+
+    out.write_char('"')
+}
+
+/// Adapts an [`io::Write`] to [`fmt::Write`].
+///
+/// `fmt::Error` carries no information, so the underlying I/O error is saved
+/// in [`Self::error`] for the caller to retrieve.
+pub(crate) struct IoWriter<W> {
+    pub inner: W,
+    pub error: Option<io::Error>,
+}
+
+impl<W> IoWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self { inner, error: None }
+    }
+}
+
+impl<W: io::Write> Write for IoWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
@@ -0,0 +1,239 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, List, Result, Value};
+
+/// The type of an NBT tag.
+///
+/// [`TagType::End`] only appears in the binary format, where it marks the end
+/// of a compound or the element type of an empty list. It is never the type
+/// of a [`Value`].
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[repr(u8)]
+pub enum TagType {
+    End = 0,
+    Byte = 1,
+    Short = 2,
+    Int = 3,
+    Long = 4,
+    Float = 5,
+    Double = 6,
+    ByteArray = 7,
+    String = 8,
+    List = 9,
+    Compound = 10,
+    IntArray = 11,
+    LongArray = 12,
+}
+
+impl fmt::Display for TagType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            TagType::End => "end",
+            TagType::Byte => "byte",
+            TagType::Short => "short",
+            TagType::Int => "int",
+            TagType::Long => "long",
+            TagType::Float => "float",
+            TagType::Double => "double",
+            TagType::ByteArray => "byte array",
+            TagType::String => "string",
+            TagType::List => "list",
+            TagType::Compound => "compound",
+            TagType::IntArray => "int array",
+            TagType::LongArray => "long array",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+impl TagType {
+    /// Returns the tag type with the given ID, or `None` if the ID is not
+    /// valid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::TagType;
+    ///
+    /// assert_eq!(TagType::from_u8(3), Some(TagType::Int));
+    /// assert_eq!(TagType::from_u8(13), None);
+    /// ```
+    pub const fn from_u8(id: u8) -> Option<Self> {
+        Some(match id {
+            0 => TagType::End,
+            1 => TagType::Byte,
+            2 => TagType::Short,
+            3 => TagType::Int,
+            4 => TagType::Long,
+            5 => TagType::Float,
+            6 => TagType::Double,
+            7 => TagType::ByteArray,
+            8 => TagType::String,
+            9 => TagType::List,
+            10 => TagType::Compound,
+            11 => TagType::IntArray,
+            12 => TagType::LongArray,
+            _ => return None,
+        })
+    }
+
+    /// Like [`Self::from_u8`], but returns an error for an invalid ID.
+    pub(crate) fn from_id(id: u8) -> Result<Self> {
+        Self::from_u8(id).ok_or_else(|| Error::new_owned(format!("invalid tag byte `{id}`")))
+    }
+
+    /// Returns the ID of this tag type, as used in the binary format. This is
+    /// the same as `self as u8`.
+    pub const fn as_u8(self) -> u8 {
+        self as u8
+    }
+
+    /// Returns the conventional name of this tag type, such as `TAG_Byte` or
+    /// `TAG_Compound`.
+    pub const fn tag_name(self) -> &'static str {
+        match self {
+            TagType::End => "TAG_End",
+            TagType::Byte => "TAG_Byte",
+            TagType::Short => "TAG_Short",
+            TagType::Int => "TAG_Int",
+            TagType::Long => "TAG_Long",
+            TagType::Float => "TAG_Float",
+            TagType::Double => "TAG_Double",
+            TagType::ByteArray => "TAG_Byte_Array",
+            TagType::String => "TAG_String",
+            TagType::List => "TAG_List",
+            TagType::Compound => "TAG_Compound",
+            TagType::IntArray => "TAG_Int_Array",
+            TagType::LongArray => "TAG_Long_Array",
+        }
+    }
+}
+
+impl Value {
+    /// Returns the type of this value.
+    pub fn tag_type(&self) -> TagType {
+        match self {
+            Value::Byte(_) => TagType::Byte,
+            Value::Short(_) => TagType::Short,
+            Value::Int(_) => TagType::Int,
+            Value::Long(_) => TagType::Long,
+            Value::Float(_) => TagType::Float,
+            Value::Double(_) => TagType::Double,
+            Value::ByteArray(_) => TagType::ByteArray,
+            Value::String(_) => TagType::String,
+            Value::List(_) => TagType::List,
+            Value::Compound(_) => TagType::Compound,
+            Value::IntArray(_) => TagType::IntArray,
+            Value::LongArray(_) => TagType::LongArray,
+        }
+    }
+
+    /// Counts the tags of each type in this value, including the value itself.
+    ///
+    /// The count for a type is at the index of its tag ID, which is
+    /// `tag_type as usize`. Elements of lists count as tags of the list's
+    /// element type. Elements of arrays are not tags and are not counted, and
+    /// neither are the `TAG_End`s that only appear in the binary format.
+    ///
+    /// This is useful for finding out what makes a large file large.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::{Compound, List, TagType, Value};
+    ///
+    /// let section = || Compound::from_iter([("BlockStates".to_owned(), Value::LongArray(vec![0; 256]))]);
+    ///
+    /// let chunk = Value::Compound(Compound::from_iter([(
+    ///     "Sections".to_owned(),
+    ///     List::Compound(vec![section(), section()]).into(),
+    /// )]));
+    ///
+    /// let counts = chunk.tag_histogram();
+    ///
+    /// assert_eq!(counts[TagType::Compound as usize], 3);
+    /// assert_eq!(counts[TagType::List as usize], 1);
+    /// assert_eq!(counts[TagType::LongArray as usize], 2);
+    /// assert_eq!(counts[TagType::Long as usize], 0);
+    /// ```
+    pub fn tag_histogram(&self) -> [usize; 13] {
+        let mut counts = [0; 13];
+        count_value(self, &mut counts);
+        counts
+    }
+
+    /// Returns the conventional name of the type of this value, such as
+    /// `TAG_Int` or `TAG_Compound`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::Value;
+    ///
+    /// let value = Value::String("abc".to_owned());
+    ///
+    /// assert_eq!(
+    ///     format!("expected TAG_Int, found {}", value.type_name()),
+    ///     "expected TAG_Int, found TAG_String"
+    /// );
+    /// ```
+    pub fn type_name(&self) -> &'static str {
+        self.tag_type().tag_name()
+    }
+}
+
+impl List {
+    /// Returns the type of the elements of this list.
+    ///
+    /// An empty list still has an element type, which is the type of its
+    /// variant.
+    pub fn element_type(&self) -> TagType {
+        match self {
+            List::Byte(_) => TagType::Byte,
+            List::Short(_) => TagType::Short,
+            List::Int(_) => TagType::Int,
+            List::Long(_) => TagType::Long,
+            List::Float(_) => TagType::Float,
+            List::Double(_) => TagType::Double,
+            List::ByteArray(_) => TagType::ByteArray,
+            List::String(_) => TagType::String,
+            List::List(_) => TagType::List,
+            List::Compound(_) => TagType::Compound,
+            List::IntArray(_) => TagType::IntArray,
+            List::LongArray(_) => TagType::LongArray,
+        }
+    }
+
+    /// Returns the conventional name of the type of the elements of this
+    /// list, such as `TAG_Compound`.
+    pub fn element_type_name(&self) -> &'static str {
+        self.element_type().tag_name()
+    }
+}
+
+fn count_value(value: &Value, counts: &mut [usize; 13]) {
+    counts[value.tag_type() as usize] += 1;
+
+    match value {
+        Value::List(l) => count_list(l, counts),
+        Value::Compound(c) => c.values().for_each(|v| count_value(v, counts)),
+        _ => {}
+    }
+}
+
+fn count_list(list: &List, counts: &mut [usize; 13]) {
+    counts[list.element_type() as usize] += list.len();
+
+    match list {
+        List::List(v) => v.iter().for_each(|l| count_list(l, counts)),
+        List::Compound(v) => v
+            .iter()
+            .flat_map(|c| c.values())
+            .for_each(|v| count_value(v, counts)),
+        _ => {}
+    }
+}
@@ -0,0 +1,287 @@
+/// Provides (de)serialization support for [`Value`](crate::Value)s in a
+/// self-describing form that keeps the type of every tag.
+///
+/// Serializing a `Value` directly to a format like JSON loses information.
+/// Bytes, shorts, ints, and longs all become plain numbers, and arrays become
+/// indistinguishable from lists. This module instead writes every value
+/// together with its tag type, so it can be read back exactly as it was. This
+/// is useful for displaying and editing NBT in tools such as web UIs.
+///
+/// # Schema
+///
+/// A value is written as an object with a `type` and a `value`:
+///
+/// ```json
+/// { "type": "int", "value": 5 }
+/// ```
+///
+/// The `type` is one of `byte`, `short`, `int`, `long`, `float`, `double`,
+/// `string`, `byte_array`, `int_array`, `long_array`, `list`, or `compound`.
+/// The `value` depends on the type:
+///
+/// - Numbers are written as numbers and strings as strings.
+/// - Arrays are written as arrays of numbers.
+/// - Compounds are written as objects mapping each key to a value in this
+///   same `type`/`value` form.
+/// - Lists are written as an object with an `element_type` and the
+///   `elements`. The element type uses the same names as `type`, and the
+///   elements are written like the `value` of a value of that type. Keeping
+///   the element type separate means empty lists keep theirs.
+///
+/// For example, a compound with a list of two shorts:
+///
+/// ```json
+/// {
+///   "type": "compound",
+///   "value": {
+///     "Pos": {
+///       "type": "list",
+///       "value": { "element_type": "short", "elements": [1, 2] }
+///     }
+///   }
+/// }
+/// ```
+///
+/// Note that JSON cannot represent NaN or infinite floats, and some JSON
+/// parsers (including JavaScript's) round integers beyond 2<sup>53</sup>.
+///
+/// This module is intended to be the target of serde's `#[serde(with =
+/// "module")]` field attribute. Its functions can also be called with a
+/// serializer or deserializer directly.
+///
+/// # Examples
+///
+/// ```
+/// use serde_nbt::{type_tagged, Compound, List, Value};
+///
+/// let value = Value::Compound(Compound::from_iter([
+///     ("Pos".to_owned(), List::Short(vec![1, 2]).into()),
+///     ("Seed".to_owned(), Value::Long(-3)),
+/// ]));
+///
+/// let mut json = vec![];
+/// type_tagged::serialize(&value, &mut serde_json::Serializer::new(&mut json)).unwrap();
+///
+/// assert_eq!(
+///     String::from_utf8(json.clone()).unwrap(),
+///     r#"{"type":"compound","value":{"Pos":{"type":"list","value":{"element_type":"short","elements":[1,2]}},"Seed":{"type":"long","value":-3}}}"#
+/// );
+///
+/// let read_back =
+///     type_tagged::deserialize(&mut serde_json::Deserializer::from_slice(&json)).unwrap();
+///
+/// assert_eq!(read_back, value);
+/// ```
+pub mod type_tagged {
+    use indexmap::IndexMap;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::{Compound, List, Value};
+
+    pub fn serialize<S>(value: &Value, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        TaggedRef::from(value).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Tagged::deserialize(deserializer).map(Value::from)
+    }
+
+    #[derive(Serialize)]
+    #[serde(tag = "type", content = "value", rename_all = "snake_case")]
+    enum TaggedRef<'a> {
+        Byte(i8),
+        Short(i16),
+        Int(i32),
+        Long(i64),
+        Float(f32),
+        Double(f64),
+        ByteArray(&'a [i8]),
+        String(&'a str),
+        List(ListRef<'a>),
+        Compound(CompoundRef<'a>),
+        IntArray(&'a [i32]),
+        LongArray(&'a [i64]),
+    }
+
+    #[derive(Serialize)]
+    #[serde(tag = "element_type", content = "elements", rename_all = "snake_case")]
+    enum ListRef<'a> {
+        Byte(&'a [i8]),
+        Short(&'a [i16]),
+        Int(&'a [i32]),
+        Long(&'a [i64]),
+        Float(&'a [f32]),
+        Double(&'a [f64]),
+        ByteArray(&'a [Vec<i8>]),
+        String(&'a [String]),
+        List(ListsRef<'a>),
+        Compound(CompoundsRef<'a>),
+        IntArray(&'a [Vec<i32>]),
+        LongArray(&'a [Vec<i64>]),
+    }
+
+    struct CompoundRef<'a>(&'a Compound);
+
+    struct ListsRef<'a>(&'a [List]);
+
+    struct CompoundsRef<'a>(&'a [Compound]);
+
+    impl<'a> From<&'a Value> for TaggedRef<'a> {
+        fn from(value: &'a Value) -> Self {
+            match value {
+                Value::Byte(v) => TaggedRef::Byte(*v),
+                Value::Short(v) => TaggedRef::Short(*v),
+                Value::Int(v) => TaggedRef::Int(*v),
+                Value::Long(v) => TaggedRef::Long(*v),
+                Value::Float(v) => TaggedRef::Float(*v),
+                Value::Double(v) => TaggedRef::Double(*v),
+                Value::ByteArray(v) => TaggedRef::ByteArray(v),
+                Value::String(v) => TaggedRef::String(v),
+                Value::List(v) => TaggedRef::List(v.into()),
+                Value::Compound(v) => TaggedRef::Compound(CompoundRef(v)),
+                Value::IntArray(v) => TaggedRef::IntArray(v),
+                Value::LongArray(v) => TaggedRef::LongArray(v),
+            }
+        }
+    }
+
+    impl<'a> From<&'a List> for ListRef<'a> {
+        fn from(list: &'a List) -> Self {
+            match list {
+                List::Byte(v) => ListRef::Byte(v),
+                List::Short(v) => ListRef::Short(v),
+                List::Int(v) => ListRef::Int(v),
+                List::Long(v) => ListRef::Long(v),
+                List::Float(v) => ListRef::Float(v),
+                List::Double(v) => ListRef::Double(v),
+                List::ByteArray(v) => ListRef::ByteArray(v),
+                List::String(v) => ListRef::String(v),
+                List::List(v) => ListRef::List(ListsRef(v)),
+                List::Compound(v) => ListRef::Compound(CompoundsRef(v)),
+                List::IntArray(v) => ListRef::IntArray(v),
+                List::LongArray(v) => ListRef::LongArray(v),
+            }
+        }
+    }
+
+    impl Serialize for CompoundRef<'_> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.collect_map(self.0.iter().map(|(k, v)| (k, TaggedRef::from(v))))
+        }
+    }
+
+    impl Serialize for ListsRef<'_> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.collect_seq(self.0.iter().map(ListRef::from))
+        }
+    }
+
+    impl Serialize for CompoundsRef<'_> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.collect_seq(self.0.iter().map(CompoundRef))
+        }
+    }
+
+    #[derive(Deserialize)]
+    #[serde(tag = "type", content = "value", rename_all = "snake_case")]
+    enum Tagged {
+        Byte(i8),
+        Short(i16),
+        Int(i32),
+        Long(i64),
+        Float(f32),
+        Double(f64),
+        ByteArray(Vec<i8>),
+        String(String),
+        List(TaggedList),
+        Compound(TaggedCompound),
+        IntArray(Vec<i32>),
+        LongArray(Vec<i64>),
+    }
+
+    #[derive(Deserialize)]
+    #[serde(tag = "element_type", content = "elements", rename_all = "snake_case")]
+    enum TaggedList {
+        Byte(Vec<i8>),
+        Short(Vec<i16>),
+        Int(Vec<i32>),
+        Long(Vec<i64>),
+        Float(Vec<f32>),
+        Double(Vec<f64>),
+        ByteArray(Vec<Vec<i8>>),
+        String(Vec<String>),
+        List(Vec<TaggedList>),
+        Compound(Vec<TaggedCompound>),
+        IntArray(Vec<Vec<i32>>),
+        LongArray(Vec<Vec<i64>>),
+    }
+
+    #[derive(Deserialize)]
+    #[serde(transparent)]
+    struct TaggedCompound(IndexMap<String, Tagged>);
+
+    impl From<Tagged> for Value {
+        fn from(tagged: Tagged) -> Self {
+            match tagged {
+                Tagged::Byte(v) => Value::Byte(v),
+                Tagged::Short(v) => Value::Short(v),
+                Tagged::Int(v) => Value::Int(v),
+                Tagged::Long(v) => Value::Long(v),
+                Tagged::Float(v) => Value::Float(v),
+                Tagged::Double(v) => Value::Double(v),
+                Tagged::ByteArray(v) => Value::ByteArray(v),
+                Tagged::String(v) => Value::String(v),
+                Tagged::List(v) => Value::List(v.into()),
+                Tagged::Compound(v) => Value::Compound(v.into()),
+                Tagged::IntArray(v) => Value::IntArray(v),
+                Tagged::LongArray(v) => Value::LongArray(v),
+            }
+        }
+    }
+
+    impl From<TaggedList> for List {
+        fn from(tagged: TaggedList) -> Self {
+            match tagged {
+                TaggedList::Byte(v) => List::Byte(v),
+                TaggedList::Short(v) => List::Short(v),
+                TaggedList::Int(v) => List::Int(v),
+                TaggedList::Long(v) => List::Long(v),
+                TaggedList::Float(v) => List::Float(v),
+                TaggedList::Double(v) => List::Double(v),
+                TaggedList::ByteArray(v) => List::ByteArray(v),
+                TaggedList::String(v) => List::String(v),
+                TaggedList::List(v) => List::List(v.into_iter().map(List::from).collect()),
+                TaggedList::Compound(v) => {
+                    List::Compound(v.into_iter().map(Compound::from).collect())
+                }
+                TaggedList::IntArray(v) => List::IntArray(v),
+                TaggedList::LongArray(v) => List::LongArray(v),
+            }
+        }
+    }
+
+    impl From<TaggedCompound> for Compound {
+        fn from(tagged: TaggedCompound) -> Self {
+            tagged
+                .0
+                .into_iter()
+                .map(|(k, v)| (k, Value::from(v)))
+                .collect()
+        }
+    }
+}
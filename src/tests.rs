@@ -1,8 +1,24 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::BufReader;
+use std::path::Path;
+
+use indexmap::IndexMap;
 use pretty_assertions::assert_eq;
 use serde::{Deserialize, Serialize};
+use serde_bytes::ByteBuf;
 
-use crate::binary::{from_reader, to_writer, Deserializer, Serializer};
-use crate::{byte_array, int_array, long_array, Compound, List, Value};
+use crate::binary::{
+    from_reader, from_reader_at, from_reader_exact, from_reader_lenient, from_reader_optional,
+    from_slice_exact, from_slice_with_remainder, le, peek_tag, read_tag_body, to_vec, to_vec_named,
+    to_writer, to_writer_buffered, to_writer_counted, to_writer_named, write_tag_body, BoolTag,
+    Deserializer, ListWriter, Serializer,
+};
+use crate::schema::{Mismatch, Schema};
+use crate::{
+    byte_array, int_array, long_array, snbt, transcode, type_tagged, Compound, CompoundDiff, Error,
+    List, OrUnknown, RawCompound, ScalarRef, SharedValue, Tag, TagType, Value, ValueRef,
+};
 
 const ROOT_NAME: &str = "The root name‽";
 
@@ -52,36 +68,35 @@ impl Struct {
         }
     }
 
+    /// The value of [`Struct::new`]. `none_int` is `None`, so it has no
+    /// entry.
     pub fn value() -> Value {
-        Value::Compound(
-            Compound::from_iter([
-                ("byte".into(), 123_i8.into()),
-                ("list_of_int".into(), List::Int(vec![3, -7, 5]).into()),
-                (
-                    "list_of_string".into(),
-                    List::String(vec!["foo".into(), "bar".into(), "baz".into()]).into(),
-                ),
-                ("string".into(), "aé日".into()),
-                (
-                    "inner".into(),
-                    Compound::from_iter([
-                        ("int".into(), i32::MIN.into()),
-                        ("long".into(), i64::MAX.into()),
-                        ("float".into(), 1e10_f32.into()),
-                        ("double".into(), f64::NEG_INFINITY.into()),
-                    ])
-                    .into(),
-                ),
-                (
-                    "int_array".into(),
-                    vec![5, -9, i32::MIN, 0, i32::MAX].into(),
-                ),
-                ("byte_array".into(), vec![0_i8, 1, 2].into()),
-                ("long_array".into(), vec![123_i64, 456, 789].into()),
-                ("some_int".into(), 321.into()),
-            ])
-            .into(),
-        )
+        Value::Compound(Compound::from_iter([
+            ("byte".into(), 123_i8.into()),
+            ("list_of_int".into(), List::Int(vec![3, -7, 5]).into()),
+            (
+                "list_of_string".into(),
+                List::String(vec!["foo".into(), "bar".into(), "baz".into()]).into(),
+            ),
+            ("string".into(), "aé日".into()),
+            (
+                "inner".into(),
+                Compound::from_iter([
+                    ("int".into(), i32::MIN.into()),
+                    ("long".into(), i64::MAX.into()),
+                    ("float".into(), 1e10_f32.into()),
+                    ("double".into(), f64::NEG_INFINITY.into()),
+                ])
+                .into(),
+            ),
+            (
+                "int_array".into(),
+                vec![5, -9, i32::MIN, 0, i32::MAX].into(),
+            ),
+            ("byte_array".into(), vec![0_i8, 1, 2].into()),
+            ("long_array".into(), vec![123_i64, 456, 789].into()),
+            ("some_int".into(), 321.into()),
+        ]))
     }
 }
 
@@ -125,6 +140,22 @@ fn round_trip_binary_value() {
     assert_eq!(de.root_name, ROOT_NAME);
 }
 
+#[test]
+fn none_fields_through_value() {
+    let value: Value = from_slice_exact(&to_vec(&Struct::new()).unwrap()).unwrap();
+
+    assert_eq!(value, Struct::value());
+
+    let compound = value.as_compound().unwrap();
+    assert!(!compound.contains_key("none_int"));
+    assert_eq!(compound.get("some_int"), Some(&Value::Int(321)));
+
+    let struct_: Struct = from_slice_exact(&to_vec(&value).unwrap()).unwrap();
+
+    assert_eq!(struct_.none_int, None);
+    assert_eq!(struct_, Struct::new());
+}
+
 #[test]
 fn to_hematite() {
     let mut buf = Vec::new();
@@ -153,11 +184,11 @@ fn mismatched_array_element() {
     #[derive(Serialize)]
     struct Struct {
         #[serde(with = "byte_array")]
-        data: Vec<i32>,
+        data: Vec<f32>,
     }
 
     let struct_ = Struct {
-        data: vec![1, 2, 3],
+        data: vec![1.0, 2.0, 3.0],
     };
 
     let mut buf = Vec::new();
@@ -166,6 +197,163 @@ fn mismatched_array_element() {
         .is_err());
 }
 
+#[test]
+fn array_element_range() {
+    #[derive(Serialize)]
+    struct Bytes {
+        #[serde(with = "byte_array")]
+        data: Vec<i32>,
+    }
+
+    #[derive(Serialize)]
+    struct Ints {
+        #[serde(with = "int_array")]
+        data: Vec<i64>,
+    }
+
+    // Wider integers are accepted if they fit.
+    let buf = to_vec(&Bytes {
+        data: vec![-128, 0, 127],
+    })
+    .unwrap();
+    let Value::Compound(value) = from_slice_exact::<Value>(&buf).unwrap() else {
+        unreachable!()
+    };
+    assert_eq!(value["data"], Value::ByteArray(vec![-128, 0, 127]));
+
+    for v in [200, -200] {
+        let err = to_vec(&Bytes { data: vec![1, v] }).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("at data: value {v} out of i8 range for byte array")
+        );
+    }
+
+    let err = to_vec(&Ints {
+        data: vec![i64::MAX],
+    })
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!("at data: value {} out of i32 range for int array", i64::MAX)
+    );
+}
+
+#[test]
+fn uuid_int_array() {
+    #[derive(PartialEq, Debug, Serialize, Deserialize)]
+    struct Entity {
+        #[serde(with = "crate::uuid_int_array")]
+        uuid: u128,
+    }
+
+    #[derive(Serialize)]
+    struct Plain {
+        uuid: u128,
+        signed: i128,
+    }
+
+    for uuid in [
+        0,
+        u128::MAX,
+        1 << 127,
+        0x069a79f4_44e94726_a5befca9_0e38aaf5,
+    ] {
+        let entity = Entity { uuid };
+        let buf = to_vec(&entity).unwrap();
+        assert_eq!(from_slice_exact::<Entity>(&buf).unwrap(), entity);
+    }
+
+    let buf = to_vec(&Compound::from_iter([(
+        "uuid".into(),
+        Value::IntArray(vec![1, 2, 3]),
+    )]))
+    .unwrap();
+    assert!(from_slice_exact::<Entity>(&buf).is_err());
+
+    let err = to_vec(&Plain { uuid: 1, signed: 1 }).unwrap_err();
+    assert_eq!(err.path(), "uuid");
+    assert!(err.to_string().contains("NBT has no 128-bit integer type"));
+}
+
+#[test]
+fn nbt_timestamp() {
+    use std::time::{Duration, SystemTime};
+
+    #[derive(PartialEq, Debug, Serialize, Deserialize)]
+    struct Level {
+        #[serde(rename = "LastPlayed", with = "crate::nbt_timestamp")]
+        last_played: SystemTime,
+    }
+
+    let round_trip = |millis: i64, time: SystemTime| {
+        let buf = to_vec(&Level { last_played: time }).unwrap();
+
+        assert_eq!(
+            from_slice_exact::<Value>(&buf).unwrap(),
+            Value::Compound(Compound::from_iter([(
+                "LastPlayed".to_owned(),
+                Value::Long(millis)
+            )]))
+        );
+
+        from_slice_exact::<Level>(&buf).unwrap().last_played
+    };
+
+    // 2022-08-08T23:06:40.123Z
+    let time = SystemTime::UNIX_EPOCH + Duration::from_millis(1_660_000_000_123);
+    assert_eq!(round_trip(1_660_000_000_123, time), time);
+
+    let time = SystemTime::UNIX_EPOCH - Duration::from_millis(86_400_000);
+    assert_eq!(round_trip(-86_400_000, time), time);
+
+    // Sub-millisecond precision is rounded down.
+    let time = SystemTime::UNIX_EPOCH + Duration::from_nanos(1_500_000);
+    assert_eq!(
+        round_trip(1, time),
+        SystemTime::UNIX_EPOCH + Duration::from_millis(1)
+    );
+
+    let time = SystemTime::UNIX_EPOCH - Duration::from_nanos(1_500_000);
+    assert_eq!(
+        round_trip(-2, time),
+        SystemTime::UNIX_EPOCH - Duration::from_millis(2)
+    );
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn nbt_timestamp_chrono() {
+    use chrono::{DateTime, TimeZone, Utc};
+
+    #[derive(PartialEq, Debug, Serialize, Deserialize)]
+    struct Level {
+        #[serde(rename = "LastPlayed", with = "crate::nbt_timestamp")]
+        last_played: DateTime<Utc>,
+    }
+
+    // 2022-08-08T23:06:40.123Z
+    let time = Utc.timestamp_millis_opt(1_660_000_000_123).unwrap();
+    let buf = to_vec(&Level { last_played: time }).unwrap();
+
+    assert_eq!(
+        from_slice_exact::<Value>(&buf).unwrap(),
+        Value::Compound(Compound::from_iter([(
+            "LastPlayed".to_owned(),
+            Value::Long(1_660_000_000_123)
+        )]))
+    );
+    assert_eq!(from_slice_exact::<Level>(&buf).unwrap().last_played, time);
+
+    // Sub-millisecond precision is rounded down, also before the epoch.
+    let time = Utc.timestamp_nanos(-1_500_000);
+    let buf = to_vec(&Level { last_played: time }).unwrap();
+    assert_eq!(
+        from_slice_exact::<Level>(&buf).unwrap().last_played,
+        Utc.timestamp_millis_opt(-2).unwrap()
+    );
+}
+
 #[test]
 fn struct_to_value() {
     let mut buf = Vec::new();
@@ -203,3 +391,3097 @@ fn value_from_json() {
 
     assert_eq!(struct_, struct_de);
 }
+
+#[test]
+fn json_value_from_binary() {
+    let value = Value::Compound(Compound::from_iter([
+        ("byte".to_owned(), Value::Byte(-1)),
+        ("string".to_owned(), Value::String("abc".to_owned())),
+        ("list".to_owned(), List::Short(vec![1, 2]).into()),
+        ("byte_array".to_owned(), Value::ByteArray(vec![3, 4])),
+        ("int_array".to_owned(), Value::IntArray(vec![5])),
+        (
+            "long_arrays".to_owned(),
+            List::LongArray(vec![vec![6, 7], vec![]]).into(),
+        ),
+        (
+            "inner".to_owned(),
+            Value::Compound(Compound::from_iter([(
+                "float".to_owned(),
+                Value::Float(0.5),
+            )])),
+        ),
+        ("nan".to_owned(), Value::Double(f64::NAN)),
+        ("inf".to_owned(), Value::Float(f32::INFINITY)),
+    ]));
+
+    let mut buf = Vec::new();
+    to_writer(&mut buf, &value).unwrap();
+
+    let json: serde_json::Value = from_reader(&mut buf.as_slice()).unwrap();
+
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "byte": -1,
+            "string": "abc",
+            "list": [1, 2],
+            "byte_array": [3, 4],
+            "int_array": [5],
+            "long_arrays": [[6, 7], []],
+            "inner": { "float": 0.5 },
+            "nan": null,
+            "inf": null,
+        })
+    );
+
+    // Arrays are still told apart from lists when reading a `Value`.
+    let read_back: Value = from_reader(&mut buf.as_slice()).unwrap();
+    assert_eq!(read_back, value);
+}
+
+#[test]
+fn round_trip_byte_buf() {
+    #[derive(PartialEq, Debug, Serialize, Deserialize)]
+    struct Bytes {
+        #[serde(with = "serde_bytes")]
+        data: Vec<u8>,
+        empty: ByteBuf,
+    }
+
+    #[derive(Serialize)]
+    struct Signed {
+        #[serde(with = "byte_array")]
+        data: Vec<i8>,
+        #[serde(with = "byte_array")]
+        empty: Vec<i8>,
+    }
+
+    let bytes = Bytes {
+        data: vec![0, 1, 127, 128, 255],
+        empty: ByteBuf::new(),
+    };
+
+    let mut buf = Vec::new();
+    to_writer(&mut buf, &bytes).unwrap();
+
+    let mut signed_buf = Vec::new();
+    let signed = Signed {
+        data: vec![0, 1, 127, -128, -1],
+        empty: vec![],
+    };
+    to_writer(&mut signed_buf, &signed).unwrap();
+
+    assert_eq!(buf, signed_buf);
+
+    let bytes_de: Bytes = from_reader(&mut buf.as_slice()).unwrap();
+
+    assert_eq!(bytes, bytes_de);
+
+    let value: Value = from_reader(&mut buf.as_slice()).unwrap();
+    assert_eq!(
+        value,
+        Value::Compound(Compound::from_iter([
+            (
+                "data".to_owned(),
+                Value::ByteArray(vec![0, 1, 127, -128, -1])
+            ),
+            ("empty".to_owned(), Value::ByteArray(vec![])),
+        ]))
+    );
+
+    // Without `serde_bytes`, a `Vec<u8>` is a sequence of `u8`s, which NBT
+    // cannot represent.
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Plain {
+        data: Vec<u8>,
+    }
+
+    assert!(to_vec(&Plain { data: vec![1] }).is_err());
+    assert!(from_reader::<_, Plain>(&mut buf.as_slice()).is_err());
+}
+
+#[test]
+fn unknown_tags() {
+    use std::sync::{Arc, Mutex};
+
+    #[rustfmt::skip]
+    let buf = [
+        10, 0, 0,
+        // An entry with the unknown tag 13 and a 3 byte payload.
+        13, 0, 1, b'x', 1, 2, 3,
+        // A list of two elements of the same unknown tag.
+        9, 0, 1, b'l', 13, 0, 0, 0, 2, 4, 5, 6, 7, 8, 9,
+        3, 0, 1, b'i', 0, 0, 0, 1,
+        0,
+    ];
+
+    assert!(from_slice_exact::<Value>(&buf).is_err());
+
+    // Unknown element types are an error even in empty lists.
+    let empty_list = [10, 0, 0, 9, 0, 1, b'l', 13, 0, 0, 0, 0, 0];
+    assert!(from_slice_exact::<Value>(&empty_list).is_err());
+
+    let payloads = Arc::new(Mutex::new(Vec::new()));
+    let mut de = Deserializer::new(buf.as_slice(), false);
+
+    let p = payloads.clone();
+    de.on_unknown_tag(move |id, reader| {
+        let mut payload = [0; 3];
+        reader.read_exact(&mut payload)?;
+        p.lock().unwrap().push((id, payload));
+        Ok(())
+    });
+
+    assert_eq!(
+        Value::deserialize(&mut de).unwrap(),
+        Value::Compound(Compound::from_iter([
+            ("l".into(), List::Byte(vec![]).into()),
+            ("i".into(), 1.into()),
+        ]))
+    );
+    assert_eq!(
+        *payloads.lock().unwrap(),
+        [(13, [1, 2, 3]), (13, [4, 5, 6]), (13, [7, 8, 9])]
+    );
+
+    let mut de = Deserializer::new(buf.as_slice(), false);
+    de.on_unknown_tag(|id, _| Err(Error::new_owned(format!("unsupported tag {id}"))));
+    assert_eq!(
+        Value::deserialize(&mut de).unwrap_err().to_string(),
+        "at x: unsupported tag 13"
+    );
+}
+
+#[test]
+fn raw_compound() {
+    #[derive(Serialize, Deserialize)]
+    struct Chunk {
+        status: String,
+        sections: RawCompound,
+        #[serde(default)]
+        extra: Vec<RawCompound>,
+    }
+
+    let sections = Compound::from_iter([
+        ("y".to_owned(), Value::Byte(-4)),
+        ("states".to_owned(), Value::LongArray(vec![1, 2, 3])),
+        (
+            "palette".to_owned(),
+            List::String(vec!["minecraft:air".to_owned()]).into(),
+        ),
+    ]);
+
+    let original = Compound::from_iter([
+        ("status".to_owned(), Value::String("empty".to_owned())),
+        ("sections".to_owned(), Value::Compound(sections.clone())),
+        (
+            "extra".to_owned(),
+            List::Compound(vec![sections.clone(), Compound::new()]).into(),
+        ),
+    ]);
+    let bytes = to_vec(&original).unwrap();
+
+    let chunk: Chunk = from_slice_exact(&bytes).unwrap();
+    assert_eq!(chunk.status, "empty");
+    assert_eq!(chunk.sections.to_compound().unwrap(), sections);
+    assert_eq!(
+        chunk.sections,
+        RawCompound::from_compound(&sections).unwrap()
+    );
+    assert_eq!(chunk.extra[1].as_bytes(), [Tag::End as u8]);
+
+    // Untouched subtrees are written back byte for byte.
+    assert_eq!(to_vec(&chunk).unwrap(), bytes);
+
+    // The root itself may be raw.
+    let root: RawCompound = from_slice_exact(&bytes).unwrap();
+    assert_eq!(root.to_compound().unwrap(), original);
+    assert_eq!(to_vec(&root).unwrap(), bytes);
+
+    // Raw compounds must be compounds.
+    let bytes = to_vec(&Compound::from_iter([(
+        "sections".to_owned(),
+        Value::Int(0),
+    )]))
+    .unwrap();
+    assert!(from_slice_exact::<Chunk>(&bytes).is_err());
+}
+
+#[test]
+fn raw_compound_options() {
+    #[derive(Deserialize)]
+    struct Chunk {
+        raw: RawCompound,
+    }
+
+    let inner = Compound::from_iter([
+        ("a".to_owned(), List::Int(vec![1, 2, 3]).into()),
+        (
+            "b".to_owned(),
+            Value::Compound(Compound::from_iter([("c".to_owned(), Value::Byte(4))])),
+        ),
+    ]);
+    let buf = to_vec(&Compound::from_iter([(
+        "raw".to_owned(),
+        Value::Compound(inner.clone()),
+    )]))
+    .unwrap();
+
+    let mut fields = vec![];
+    let mut de = Deserializer::new(buf.as_slice(), false);
+    de.on_field(|path, tag_type| fields.push((path.to_owned(), tag_type)));
+    assert_eq!(
+        Chunk::deserialize(&mut de)
+            .unwrap()
+            .raw
+            .to_compound()
+            .unwrap(),
+        inner
+    );
+    drop(de);
+
+    assert_eq!(
+        fields,
+        [
+            ("raw".to_owned(), TagType::Compound),
+            ("raw.a".to_owned(), TagType::List),
+            ("raw.b".to_owned(), TagType::Compound),
+            ("raw.b.c".to_owned(), TagType::Byte),
+        ]
+    );
+
+    // The document has 7 elements, and is nested 3 deep.
+    let mut de = Deserializer::new(buf.as_slice(), false);
+    de.set_max_total_elements(6);
+    assert!(Chunk::deserialize(&mut de).is_err());
+    de.reader = buf.as_slice();
+    de.set_max_total_elements(7);
+    assert!(Chunk::deserialize(&mut de).is_ok());
+
+    let mut de = Deserializer::new(buf.as_slice(), false);
+    de.set_max_depth(2);
+    assert!(Chunk::deserialize(&mut de).is_err());
+    de.reader = buf.as_slice();
+    assert!(Value::deserialize(&mut de).is_err());
+    de.reader = buf.as_slice();
+    de.set_max_depth(3);
+    assert!(Chunk::deserialize(&mut de).is_ok());
+
+    let raw = |entry: &[u8]| [&[10, 0, 0, 10, 0, 3, b'r', b'a', b'w'], entry, &[0, 0]].concat();
+
+    // An empty list of ints named "e".
+    let bytes = raw(&[9, 0, 1, b'e', 3, 0, 0, 0, 0]);
+    let mut de = Deserializer::new(bytes.as_slice(), false);
+    de.set_strict_empty_lists(true);
+    assert_eq!(Chunk::deserialize(&mut de).err().unwrap().path(), "raw.e");
+
+    // A byte entry whose key is "a\n".
+    let bytes = raw(&[1, 0, 2, b'a', b'\n', 5]);
+    let mut de = Deserializer::new(bytes.as_slice(), false);
+    de.set_strict_keys(true);
+    assert!(Chunk::deserialize(&mut de).is_err());
+
+    // An entry of the unknown tag 42 with a 1 byte payload, followed by a
+    // byte entry.
+    let bytes = raw(&[42, 0, 1, b'x', 7, 1, 0, 1, b'y', 5]);
+    let mut de = Deserializer::new(bytes.as_slice(), false);
+    assert!(Chunk::deserialize(&mut de).is_err());
+    de.reader = bytes.as_slice();
+    de.on_unknown_tag(|_, reader| {
+        reader.read_exact(&mut [0])?;
+        Ok(())
+    });
+    assert_eq!(
+        Chunk::deserialize(&mut de)
+            .unwrap()
+            .raw
+            .to_compound()
+            .unwrap(),
+        Compound::from_iter([("y".to_owned(), Value::Byte(5))])
+    );
+}
+
+#[test]
+fn array_length_exceeds_data() {
+    // Arrays claiming `i32::MAX` elements, followed by only a few bytes of
+    // data. Reading them must fail at the end of the data rather than trying
+    // to allocate space for every element up front.
+    for tag in [Tag::ByteArray, Tag::IntArray, Tag::LongArray] {
+        let buf = [
+            &[Tag::Compound as u8, 0, 0, tag as u8, 0, 1, b'a'][..],
+            &i32::MAX.to_be_bytes(),
+            &[1, 2, 3, 4],
+        ]
+        .concat();
+
+        let err = from_reader::<_, Value>(buf.as_slice()).unwrap_err();
+        assert!(err.path().starts_with('a'), "{tag}");
+
+        assert!(
+            from_reader::<_, RawCompound>(buf.as_slice()).is_err(),
+            "{tag}"
+        );
+        assert!(from_reader_lenient(buf.as_slice()).1.is_some(), "{tag}");
+        assert!(
+            le::from_reader::<_, Value>(buf.as_slice()).is_err(),
+            "{tag}"
+        );
+    }
+}
+
+#[test]
+fn max_total_elements() {
+    // 1 compound entry + 10 list elements + 10 int elements = 21 elements.
+    let value = Value::Compound(Compound::from_iter([(
+        "nested".into(),
+        List::List(vec![List::Int(vec![123]); 10]).into(),
+    )]));
+
+    let mut buf = Vec::new();
+    to_writer(&mut buf, &value).unwrap();
+
+    let mut de = Deserializer::new(buf.as_slice(), false);
+    de.set_max_total_elements(20);
+    assert!(Value::deserialize(&mut de).is_err());
+
+    let mut de = Deserializer::new(buf.as_slice(), false);
+    de.set_max_total_elements(21);
+    assert_eq!(Value::deserialize(&mut de).unwrap(), value);
+}
+
+#[test]
+fn strict_keys() {
+    let compound = Compound::from_iter([
+        ("ok".to_owned(), Value::Int(1)),
+        (
+            "inner".to_owned(),
+            Compound::from_iter([("bad\0key".to_owned(), Value::Int(2))]).into_value(),
+        ),
+    ]);
+
+    let buf = to_vec(&compound).unwrap();
+
+    let mut de = Deserializer::new(buf.as_slice(), false);
+    assert_eq!(Compound::deserialize(&mut de).unwrap(), compound);
+
+    let mut de = Deserializer::new(buf.as_slice(), false);
+    de.set_strict_keys(true);
+    let err = Compound::deserialize(&mut de).unwrap_err();
+
+    assert_eq!(err.path(), "inner.bad\0key");
+    assert!(err
+        .to_string()
+        .ends_with("compound key contains the control character U+0000"));
+
+    let buf = to_vec(&Struct::new()).unwrap();
+    let mut de = Deserializer::new(buf.as_slice(), false);
+    de.set_strict_keys(true);
+    assert_eq!(Struct::deserialize(&mut de).unwrap(), Struct::new());
+}
+
+#[test]
+fn strict_empty_lists() {
+    #[derive(PartialEq, Debug, Deserialize)]
+    struct Lists {
+        ints: Vec<i32>,
+        canonical: Vec<i32>,
+    }
+
+    #[rustfmt::skip]
+    let bytes = [
+        Tag::Compound as u8, 0, 0,
+        Tag::List as u8, 0, 4, b'i', b'n', b't', b's', Tag::Int as u8, 0, 0, 0, 0,
+        Tag::List as u8, 0, 9, b'c', b'a', b'n', b'o', b'n', b'i', b'c', b'a', b'l',
+        Tag::End as u8, 0, 0, 0, 0,
+        Tag::End as u8,
+    ];
+
+    let expected = Lists {
+        ints: vec![],
+        canonical: vec![],
+    };
+
+    let mut de = Deserializer::new(bytes.as_slice(), false);
+    assert_eq!(Lists::deserialize(&mut de).unwrap(), expected);
+
+    let mut de = Deserializer::new(bytes.as_slice(), false);
+    de.set_strict_empty_lists(true);
+    let err = Lists::deserialize(&mut de).unwrap_err();
+
+    assert_eq!(err.path(), "ints");
+    assert!(err
+        .to_string()
+        .ends_with("empty list has the non-canonical element type `int`"));
+
+    // The serializer always writes `TAG_End` for empty lists.
+    let buf = to_vec(&Compound::from_iter([(
+        "empty".to_owned(),
+        List::Int(vec![]).into_value(),
+    )]))
+    .unwrap();
+    let mut de = Deserializer::new(buf.as_slice(), false);
+    de.set_strict_empty_lists(true);
+    assert!(Compound::deserialize(&mut de).is_ok());
+}
+
+#[test]
+fn on_field() {
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Deserialize)]
+    struct OnlyByte {
+        byte: i8,
+    }
+
+    let value = Value::Compound(Compound::from_iter([
+        ("byte".to_owned(), Value::Byte(1)),
+        (
+            "sections".to_owned(),
+            List::Compound(vec![
+                Compound::new(),
+                Compound::from_iter([("y".to_owned(), Value::Int(2))]),
+            ])
+            .into(),
+        ),
+        (
+            "nested".to_owned(),
+            List::List(vec![List::Compound(vec![Compound::from_iter([(
+                "z".to_owned(),
+                Value::IntArray(vec![3]),
+            )])])])
+            .into(),
+        ),
+    ]));
+
+    let buf = to_vec(&value).unwrap();
+
+    let fields = Arc::new(Mutex::new(vec![]));
+    let fields_ = fields.clone();
+
+    let mut de = Deserializer::new(buf.as_slice(), false);
+    de.on_field(move |path, tag_type| fields_.lock().unwrap().push((path.to_owned(), tag_type)));
+
+    // Entries that are not fields of the struct are still reported.
+    assert_eq!(OnlyByte::deserialize(&mut de).unwrap().byte, 1);
+
+    let expected = [
+        ("byte".to_owned(), TagType::Byte),
+        ("sections".to_owned(), TagType::List),
+        ("sections[1].y".to_owned(), TagType::Int),
+        ("nested".to_owned(), TagType::List),
+        ("nested[0][0].z".to_owned(), TagType::IntArray),
+    ];
+
+    assert_eq!(*fields.lock().unwrap(), expected);
+
+    // The path starts over for every document, even after an error left a
+    // document unfinished.
+    de.reader = &buf[..buf.len() - 4];
+    assert!(Value::deserialize(&mut de).is_err());
+
+    fields.lock().unwrap().clear();
+    de.reader = buf.as_slice();
+    assert_eq!(Value::deserialize(&mut de).unwrap(), value);
+    assert_eq!(*fields.lock().unwrap(), expected);
+}
+
+#[test]
+fn shared_value() {
+    let shared = SharedValue::new(Struct::value());
+    let cloned = shared.clone();
+
+    assert!(SharedValue::ptr_eq(&shared, &cloned));
+
+    let mut shared_buf = Vec::new();
+    to_writer(&mut shared_buf, &cloned).unwrap();
+
+    let mut buf = Vec::new();
+    to_writer(&mut buf, &Struct::value()).unwrap();
+
+    assert_eq!(shared_buf, buf);
+
+    let shared_de: SharedValue = from_reader(&mut buf.as_slice()).unwrap();
+
+    assert_eq!(shared_de.into_inner(), Struct::value());
+}
+
+#[test]
+fn diff_and_apply() {
+    let old = Struct::value();
+
+    let mut new = old.clone();
+    let Value::Compound(c) = &mut new else {
+        unreachable!()
+    };
+    // Removed key.
+    c.shift_remove("byte");
+    // Added key.
+    c.insert("added".into(), 5_i16.into());
+    // Type change.
+    c.insert("string".into(), 123_i32.into());
+    // Reordered list.
+    c.insert("list_of_int".into(), List::Int(vec![5, -7, 3]).into());
+    // Nested change.
+    let Some(Value::Compound(inner)) = c.get_mut("inner") else {
+        unreachable!()
+    };
+    inner.insert("int".into(), 0.into());
+
+    let diff = Value::diff(&old, &new).unwrap();
+
+    assert_eq!(diff.removed, ["byte"]);
+    assert_eq!(diff.set.len(), 3);
+    assert_eq!(diff.nested["inner"].set.len(), 1);
+
+    let mut buf = Vec::new();
+    to_writer(&mut buf, &diff).unwrap();
+    let diff: CompoundDiff = from_reader(&mut buf.as_slice()).unwrap();
+
+    let mut patched = old.clone();
+    patched.apply_diff(&diff).unwrap();
+    assert_eq!(patched, new);
+
+    assert!(Value::diff(&old, &old).unwrap().is_empty());
+}
+
+#[test]
+fn canonicalize() {
+    let Value::Compound(mut a) = Struct::value() else {
+        unreachable!()
+    };
+
+    let mut b = a.clone();
+    b.reverse();
+
+    a.insert("nan".into(), f32::NAN.into());
+    a.insert("zero".into(), 0.0_f64.into());
+
+    b.insert("zero".into(), (-0.0_f64).into());
+    b.insert("nan".into(), f32::from_bits(f32::NAN.to_bits() + 1).into());
+
+    let a = Value::Compound(a).canonicalize().unwrap();
+    let b = Value::Compound(b).canonicalize().unwrap();
+
+    assert_eq!(a, b);
+
+    // The canonical form of a compound is a valid document.
+    let _: Value = from_reader(&mut a.as_slice()).unwrap();
+}
+
+#[test]
+fn max_depth_on_write() {
+    // The root compound is at depth 1, and each level adds a list and a
+    // compound, for a total depth of `1 + 2 * levels`.
+    fn nested(levels: usize) -> Compound {
+        let mut compound = Compound::new();
+
+        for _ in 0..levels {
+            compound = Compound::from_iter([("l".into(), List::Compound(vec![compound]).into())]);
+        }
+
+        compound
+    }
+
+    to_vec(&nested(255)).unwrap();
+
+    let err = to_vec(&nested(2000)).unwrap_err();
+    assert!(err
+        .to_string()
+        .ends_with(": NBT exceeds the maximum nesting depth of 512"));
+    assert_eq!(err.trace().len(), 256);
+
+    let mut buf = Vec::new();
+    let mut ser = Serializer::new(&mut buf, "");
+    ser.set_max_depth(4);
+    nested(1).serialize(&mut ser).unwrap();
+    assert!(nested(2).serialize(&mut ser).is_err());
+}
+
+#[test]
+fn tag_body_round_trip() {
+    let Value::Compound(compound) = Struct::value() else {
+        unreachable!()
+    };
+
+    let values = compound
+        .into_iter()
+        .map(|(_, v)| v)
+        .chain([Struct::value()]);
+
+    for value in values {
+        let mut buf = Vec::new();
+        write_tag_body(&mut buf, &value).unwrap();
+
+        let mut reader = buf.as_slice();
+        let read = read_tag_body(&mut reader, value.tag_type() as u8).unwrap();
+
+        assert_eq!(read, value);
+        assert!(reader.is_empty());
+    }
+
+    assert!(read_tag_body([0_u8; 8].as_slice(), 0).is_err());
+    assert!(read_tag_body([0_u8; 8].as_slice(), 13).is_err());
+}
+
+#[test]
+fn to_vec_matches_to_writer() {
+    let mut buf = Vec::new();
+    to_writer(&mut buf, &Struct::new()).unwrap();
+
+    assert_eq!(to_vec(&Struct::new()).unwrap(), buf);
+
+    let mut buffered = Vec::new();
+    to_writer_buffered(&mut buffered, &Struct::new()).unwrap();
+
+    assert_eq!(buffered, buf);
+
+    let mut counted = Vec::new();
+    let len = to_writer_counted(&mut counted, &Struct::new()).unwrap();
+
+    assert_eq!(counted, buf);
+    assert_eq!(len, buf.len());
+
+    let buf = to_vec_named(ROOT_NAME, &Struct::new()).unwrap();
+
+    let mut de = Deserializer::new(buf.as_slice(), true);
+    assert_eq!(Struct::deserialize(&mut de).unwrap(), Struct::new());
+    assert_eq!(de.root_name, ROOT_NAME);
+
+    let mut named = Vec::new();
+    to_writer_named(&mut named, ROOT_NAME, &Struct::new()).unwrap();
+
+    assert_eq!(named, buf);
+}
+
+#[test]
+fn slice_remainder() {
+    let mut buf = to_vec(&Struct::new()).unwrap();
+    let len = buf.len();
+    buf.extend([1, 2, 3]);
+
+    let (struct_, remainder) = from_slice_with_remainder::<Struct>(&buf).unwrap();
+
+    assert_eq!(struct_, Struct::new());
+    assert_eq!(remainder, [1, 2, 3]);
+    assert_eq!(remainder.as_ptr(), buf[len..].as_ptr());
+}
+
+#[test]
+fn deserializer_into_inner() {
+    use std::io::Read;
+
+    let mut buf = to_vec(&Struct::new()).unwrap();
+    buf.extend(to_vec(&Compound::new()).unwrap());
+    buf.extend([42]);
+
+    let mut de = Deserializer::new(buf.as_slice(), false);
+    assert_eq!(Struct::deserialize(&mut de).unwrap(), Struct::new());
+
+    let mut reader = de.into_inner();
+    assert_eq!(
+        from_reader::<_, Compound>(&mut reader).unwrap(),
+        Compound::new()
+    );
+
+    let mut rest = vec![];
+    reader.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, [42]);
+}
+
+#[test]
+fn trailing_bytes() {
+    let mut buf = to_vec(&Struct::new()).unwrap();
+
+    assert_eq!(from_slice_exact::<Struct>(&buf).unwrap(), Struct::new());
+    assert_eq!(
+        from_reader_exact::<_, Struct>(buf.as_slice()).unwrap(),
+        Struct::new()
+    );
+
+    buf.push(0);
+
+    assert!(from_slice_exact::<Struct>(&buf).is_err());
+    assert!(from_reader_exact::<_, Struct>(buf.as_slice()).is_err());
+    assert!(from_reader::<_, Struct>(buf.as_slice()).is_ok());
+}
+
+#[test]
+fn lenient_partial_tree() {
+    let value = Value::Compound(Compound::from_iter([
+        ("first".to_owned(), Value::Int(1)),
+        (
+            "list".to_owned(),
+            List::Compound(vec![
+                Compound::from_iter([("a".to_owned(), Value::Byte(2))]),
+                Compound::from_iter([
+                    ("b".to_owned(), Value::Byte(3)),
+                    ("c".to_owned(), Value::Long(4)),
+                ]),
+            ])
+            .into(),
+        ),
+        ("last".to_owned(), Value::Int(5)),
+    ]));
+
+    let buf = to_vec(&value).unwrap();
+
+    let (read, err) = from_reader_lenient(buf.as_slice());
+    assert_eq!(read, value);
+    assert!(err.is_none());
+
+    // Cut the data off in the middle of the long "c".
+    let end = buf.windows(2).position(|w| w == b"c\0").unwrap() + 4;
+
+    let (read, err) = from_reader_lenient(&buf[..end]);
+
+    assert_eq!(
+        read,
+        Value::Compound(Compound::from_iter([
+            ("first".to_owned(), Value::Int(1)),
+            (
+                "list".to_owned(),
+                List::Compound(vec![
+                    Compound::from_iter([("a".to_owned(), Value::Byte(2))]),
+                    Compound::from_iter([("b".to_owned(), Value::Byte(3))]),
+                ])
+                .into(),
+            ),
+        ]))
+    );
+    let err = err.unwrap();
+    assert_eq!(err.path(), "list[1].c");
+    assert!(err
+        .to_string()
+        .ends_with("list declared 2 element(s) but the data ended after 1"));
+
+    let (read, err) = from_reader_lenient([8, 0, 0].as_slice());
+    assert_eq!(read, Value::Compound(Compound::new()));
+    assert!(err.is_some());
+}
+
+#[test]
+fn lenient_options() {
+    // An empty list of ints named "e".
+    let bytes = [10, 0, 0, 9, 0, 1, b'e', 3, 0, 0, 0, 0, 0];
+    let (read, err) = from_reader_lenient(bytes.as_slice());
+    assert_eq!(
+        read,
+        Value::Compound(Compound::from_iter([(
+            "e".to_owned(),
+            List::Int(vec![]).into()
+        )]))
+    );
+    assert!(err.is_none());
+
+    // A list of bytes named "l" which claims `i32::MAX` elements.
+    let bytes = [
+        &[10, 0, 0, 9, 0, 1, b'l', 1][..],
+        &i32::MAX.to_be_bytes(),
+        &[1, 2, 0],
+    ]
+    .concat();
+
+    let (read, err) = from_reader_lenient(bytes.as_slice());
+    assert_eq!(
+        read.pointer("/l"),
+        Some(ValueRef::List(&List::Byte(vec![1, 2, 0])))
+    );
+    assert_eq!(err.unwrap().path(), "l[3]");
+
+    let mut de = Deserializer::new(bytes.as_slice(), false);
+    de.set_max_total_elements(1000);
+    let (read, err) = de.deserialize_lenient();
+    assert_eq!(
+        read.pointer("/l"),
+        Some(ValueRef::List(&List::Byte(vec![])))
+    );
+    assert!(err
+        .unwrap()
+        .to_string()
+        .ends_with("document exceeds the maximum of 1000 total elements"));
+
+    let bytes = [10, 0, 0, 9, 0, 1, b'e', 3, 0, 0, 0, 0, 0];
+    let mut de = Deserializer::new(bytes.as_slice(), false);
+    de.set_strict_empty_lists(true);
+    let (read, err) = de.deserialize_lenient();
+    assert_eq!(read.pointer("/e"), Some(ValueRef::List(&List::default())));
+    assert_eq!(err.unwrap().path(), "e");
+
+    // A byte entry whose key is "a\n", followed by a byte entry "b".
+    let bytes = [10, 0, 0, 1, 0, 1, b'b', 4, 1, 0, 2, b'a', b'\n', 5, 0];
+    let mut de = Deserializer::new(bytes.as_slice(), false);
+    de.set_strict_keys(true);
+    let (read, err) = de.deserialize_lenient();
+    assert_eq!(
+        read,
+        Value::Compound(Compound::from_iter([("b".to_owned(), Value::Byte(4))]))
+    );
+    assert!(err.is_some());
+}
+
+#[test]
+fn truncated_list() {
+    #[derive(Serialize)]
+    struct Lists {
+        ints: Vec<i32>,
+        nested: Vec<Vec<i16>>,
+    }
+
+    let buf = to_vec(&Lists {
+        ints: vec![1, 2, 3],
+        nested: vec![vec![4], vec![5, 6]],
+    })
+    .unwrap();
+
+    // End the data in the middle of the third int.
+    let end = buf.windows(4).position(|w| w == b"ints").unwrap() + 4 + 5 + 4 * 2 + 1;
+    let err = from_reader::<_, Value>(&buf[..end]).unwrap_err();
+
+    assert_eq!(err.path(), "ints[2]");
+    assert!(err
+        .to_string()
+        .ends_with("list declared 3 element(s) but the data ended after 2"));
+
+    // The innermost list that was cut short is reported.
+    let err = from_reader::<_, Value>(&buf[..buf.len() - 3]).unwrap_err();
+
+    assert_eq!(err.path(), "nested[1][1]");
+    assert!(err
+        .to_string()
+        .ends_with("list declared 2 element(s) but the data ended after 1"));
+
+    // The location inside of the truncated element is kept.
+    let value = Value::Compound(Compound::from_iter([(
+        "b".to_owned(),
+        List::Compound(vec![
+            Compound::new(),
+            Compound::new(),
+            Compound::from_iter([("c".to_owned(), Value::Long(7))]),
+        ])
+        .into(),
+    )]));
+    let buf = to_vec(&Compound::from_iter([("a".to_owned(), value)])).unwrap();
+    let err = from_reader::<_, Value>(&buf[..buf.len() - 6]).unwrap_err();
+
+    assert_eq!(err.path(), "a.b[2].c");
+    assert!(err
+        .to_string()
+        .ends_with("list declared 3 element(s) but the data ended after 2"));
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn gzip_multi_member() {
+    use std::io::Write;
+
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    use crate::binary::{from_gzip_reader, to_gzip_writer};
+
+    let mut compressed = Vec::new();
+    to_gzip_writer(&mut compressed, &Struct::new()).unwrap();
+    assert_eq!(
+        from_gzip_reader::<_, Struct>(compressed.as_slice()).unwrap(),
+        Struct::new()
+    );
+
+    // Split the data across two gzip members.
+    let bytes = to_vec(&Struct::new()).unwrap();
+    let (first, second) = bytes.split_at(bytes.len() / 2);
+
+    let mut stream = Vec::new();
+    for part in [first, second] {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(part).unwrap();
+        stream.extend(encoder.finish().unwrap());
+    }
+
+    assert_eq!(
+        from_gzip_reader::<_, Struct>(stream.as_slice()).unwrap(),
+        Struct::new()
+    );
+}
+
+#[test]
+fn string_length_limit() {
+    let max = "a".repeat(u16::MAX as usize);
+    let compound = Compound::from_iter([(max.clone(), Value::String(max.clone()))]);
+    assert_eq!(
+        from_slice_exact::<Compound>(&to_vec(&compound).unwrap()).unwrap(),
+        compound
+    );
+
+    // The limit applies to the Modified UTF-8 encoding, where `é` takes two
+    // bytes.
+    let long = "é".repeat(40_000);
+
+    let compound = Compound::from_iter([(long.clone(), Value::Byte(0))]);
+    let err = to_vec(&compound).unwrap_err();
+    assert!(err
+        .to_string()
+        .ends_with("key of 80000 bytes exceeds the maximum length of 65535 bytes"));
+
+    let compound = Compound::from_iter([("s".to_owned(), Value::String(long.clone()))]);
+    let err = to_vec(&compound).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "at s: string of 80000 bytes exceeds the maximum length of 65535 bytes"
+    );
+
+    let err = to_vec_named(&long, &Compound::new()).unwrap_err();
+    assert!(err.to_string().starts_with("key of 80000 bytes"));
+}
+
+#[test]
+fn non_string_map_keys() {
+    use std::collections::HashMap;
+
+    #[derive(Serialize)]
+    struct Scores {
+        by_id: HashMap<i32, i32>,
+    }
+
+    let err = to_vec(&HashMap::from([(1, 2)])).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "NBT compound keys must be strings (got i32)"
+    );
+
+    let err = to_vec(&Scores {
+        by_id: HashMap::from([(1, 2)]),
+    })
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "at by_id: NBT compound keys must be strings (got i32)"
+    );
+
+    // String keys of any type are fine.
+    let map = BTreeMap::from([("1".to_owned(), 2), ("3".to_owned(), 4)]);
+    assert_eq!(
+        from_slice_exact::<BTreeMap<String, i32>>(&to_vec(&map).unwrap()).unwrap(),
+        map
+    );
+}
+
+#[test]
+fn empty_compound_into_map() {
+    use std::collections::HashMap;
+
+    // The root compound is closed by `TAG_End` right after its name.
+    let empty = [Tag::Compound as u8, 0, 0, Tag::End as u8];
+    let map: HashMap<String, i32> = from_slice_exact(&empty).unwrap();
+    assert!(map.is_empty());
+
+    let nested = [
+        Tag::Compound as u8,
+        0,
+        0,
+        Tag::Compound as u8,
+        0,
+        1,
+        b'a',
+        Tag::End as u8,
+        Tag::Int as u8,
+        0,
+        1,
+        b'b',
+        0,
+        0,
+        0,
+        5,
+        Tag::End as u8,
+    ];
+
+    #[derive(PartialEq, Debug, Deserialize)]
+    struct Nested {
+        a: HashMap<String, i32>,
+        b: i32,
+    }
+
+    assert_eq!(
+        from_slice_exact::<Nested>(&nested).unwrap(),
+        Nested {
+            a: HashMap::new(),
+            b: 5
+        }
+    );
+}
+
+#[test]
+fn optional_root() {
+    let buf = to_vec(&Struct::new()).unwrap();
+
+    // A buffered reader with a tiny buffer still peeks the tag correctly.
+    let mut stream = vec![Tag::End as u8];
+    stream.extend(&buf);
+    stream.push(Tag::End as u8);
+    let mut reader = BufReader::with_capacity(1, stream.as_slice());
+
+    assert_eq!(peek_tag(&mut reader).unwrap(), Some(Tag::End as u8));
+    assert_eq!(
+        from_reader_optional::<_, Struct>(&mut reader).unwrap(),
+        None
+    );
+    assert_eq!(peek_tag(&mut reader).unwrap(), Some(Tag::Compound as u8));
+    assert_eq!(
+        from_reader_optional::<_, Struct>(&mut reader).unwrap(),
+        Some(Struct::new())
+    );
+    assert_eq!(
+        from_reader_optional::<_, Struct>(&mut reader).unwrap(),
+        None
+    );
+    assert_eq!(peek_tag(&mut reader).unwrap(), None);
+    assert!(from_reader_optional::<_, Struct>(&mut reader).is_err());
+}
+
+#[test]
+fn reader_at_offset() {
+    use std::io::{Cursor, Seek};
+
+    let mut file = b"container header".to_vec();
+    let offset = file.len() as u64;
+    file.extend(to_vec(&Struct::new()).unwrap());
+    file.extend(b"trailer");
+
+    let mut cursor = Cursor::new(file);
+    assert_eq!(
+        from_reader_at::<_, Struct>(&mut cursor, offset).unwrap(),
+        Struct::new()
+    );
+    assert_eq!(
+        cursor.stream_position().unwrap(),
+        cursor.get_ref().len() as u64 - 7
+    );
+
+    // Errors describe the location in the NBT data, not in the file.
+    let mut file = vec![0xff; 3];
+    file.extend(
+        to_vec(&Compound::from_iter([(
+            "a".into(),
+            List::Int(vec![1]).into(),
+        )]))
+        .unwrap(),
+    );
+    file.truncate(file.len() - 3);
+    let err = from_reader_at::<_, Value>(Cursor::new(file), 3).unwrap_err();
+    assert_eq!(err.trace().collect::<Vec<_>>(), ["a", "[0]"]);
+}
+
+#[test]
+fn reordered_fields() {
+    #[derive(PartialEq, Debug, Deserialize)]
+    struct Player {
+        name: String,
+        health: f32,
+        #[serde(with = "int_array")]
+        pos: Vec<i32>,
+    }
+
+    // TAG_Compound "" { pos: [I;1,2,3], unknown: 7b, health: 20f, name: "Steve" }
+    #[rustfmt::skip]
+    let buf = [
+        10, 0, 0,
+        11, 0, 3, b'p', b'o', b's', 0, 0, 0, 3, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 3,
+        1, 0, 7, b'u', b'n', b'k', b'n', b'o', b'w', b'n', 7,
+        5, 0, 6, b'h', b'e', b'a', b'l', b't', b'h', 0x41, 0xa0, 0, 0,
+        8, 0, 4, b'n', b'a', b'm', b'e', 0, 5, b'S', b't', b'e', b'v', b'e',
+        0,
+    ];
+
+    let player: Player = from_slice_exact(&buf).unwrap();
+
+    assert_eq!(
+        player,
+        Player {
+            name: "Steve".to_owned(),
+            health: 20.0,
+            pos: vec![1, 2, 3],
+        }
+    );
+}
+
+#[test]
+fn missing_field() {
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Player {
+        name: String,
+        health: f32,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Root {
+        player: Player,
+    }
+
+    let value = Compound::from_iter([(
+        "player".to_owned(),
+        Compound::from_iter([("name".to_owned(), "Steve".into())]).into(),
+    )]);
+
+    let buf = to_vec(&value).unwrap();
+    let err = from_slice_exact::<Root>(&buf).unwrap_err();
+
+    assert_eq!(err.path(), "player");
+    assert_eq!(err.to_string(), "at player: missing field `health`");
+}
+
+#[test]
+fn error_path() {
+    #[derive(Deserialize, Debug)]
+    #[serde(rename_all = "PascalCase")]
+    #[allow(dead_code)]
+    struct Root {
+        level: Level,
+    }
+
+    #[derive(Deserialize, Debug)]
+    #[serde(rename_all = "PascalCase")]
+    #[allow(dead_code)]
+    struct Level {
+        sections: Vec<Section>,
+    }
+
+    #[derive(Deserialize, Debug)]
+    #[serde(rename_all = "PascalCase")]
+    #[allow(dead_code)]
+    struct Section {
+        palette: Vec<Block>,
+    }
+
+    #[derive(Deserialize, Debug)]
+    #[serde(rename_all = "PascalCase")]
+    #[allow(dead_code)]
+    struct Block {
+        name: String,
+    }
+
+    let block = |name: Value| Compound::from_iter([("Name".into(), name)]);
+    let section =
+        |palette| Compound::from_iter([("Palette".into(), List::Compound(palette).into())]);
+
+    let value = Compound::from_iter([(
+        "Level".into(),
+        Compound::from_iter([(
+            "Sections".into(),
+            List::Compound(vec![
+                section(vec![block("minecraft:air".into())]),
+                section(vec![block("minecraft:stone".into()), block(123.into())]),
+            ])
+            .into(),
+        )])
+        .into(),
+    )]);
+
+    let buf = to_vec(&value).unwrap();
+
+    let err = from_reader::<_, Root>(buf.as_slice()).unwrap_err();
+
+    assert_eq!(err.path(), "Level.Sections[1].Palette[1].Name");
+    assert!(err
+        .to_string()
+        .starts_with("at Level.Sections[1].Palette[1].Name: "));
+}
+
+#[test]
+fn error_path_quotes_keys() {
+    let value = Compound::from_iter([(
+        "a.b".to_owned(),
+        Compound::from_iter([
+            ("".to_owned(), Value::String("ok".into())),
+            ("[0]".to_owned(), Value::Int(5)),
+        ])
+        .into(),
+    )]);
+    let buf = to_vec(&value).unwrap();
+
+    let err =
+        from_reader::<_, BTreeMap<String, BTreeMap<String, String>>>(buf.as_slice()).unwrap_err();
+    assert_eq!(err.trace().collect::<Vec<_>>(), ["a.b", "[0]"]);
+    assert_eq!(err.path(), r#""a.b"."[0]""#);
+
+    let value = Compound::from_iter([(r#"say "hi\""#.to_owned(), Value::Int(5))]);
+    let buf = to_vec(&value).unwrap();
+
+    let err = from_reader::<_, BTreeMap<String, String>>(buf.as_slice()).unwrap_err();
+    assert_eq!(err.path(), r#""say \"hi\\\"""#);
+}
+
+#[test]
+fn enum_like_strings() {
+    #[derive(PartialEq, Debug, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Facing {
+        North,
+        South,
+    }
+
+    #[derive(PartialEq, Debug, Serialize, Deserialize)]
+    struct Properties {
+        facing: Facing,
+        known: OrUnknown<Facing>,
+        unknown: OrUnknown<Facing>,
+    }
+
+    let props = Properties {
+        facing: Facing::North,
+        known: OrUnknown::Known(Facing::South),
+        unknown: OrUnknown::Unknown("up".into()),
+    };
+
+    let buf = to_vec(&props).unwrap();
+
+    let value: Value = from_reader(&mut buf.as_slice()).unwrap();
+
+    assert_eq!(
+        value,
+        Value::Compound(Compound::from_iter([
+            ("facing".into(), "north".into()),
+            ("known".into(), "south".into()),
+            ("unknown".into(), "up".into()),
+        ]))
+    );
+
+    let props_de: Properties = from_reader(&mut buf.as_slice()).unwrap();
+
+    assert_eq!(props, props_de);
+}
+
+#[test]
+fn list_of_arrays() {
+    let value = Value::Compound(Compound::from_iter([
+        (
+            "byte_arrays".into(),
+            List::ByteArray(vec![vec![1, 2], vec![], vec![-3]]).into(),
+        ),
+        (
+            "int_arrays".into(),
+            List::IntArray(vec![vec![i32::MIN, 0], vec![i32::MAX]]).into(),
+        ),
+        (
+            "long_arrays".into(),
+            List::LongArray(vec![vec![], vec![i64::MAX]]).into(),
+        ),
+    ]));
+
+    let buf = to_vec(&value).unwrap();
+
+    let value_de: Value = from_reader(&mut buf.as_slice()).unwrap();
+    assert_eq!(value, value_de);
+
+    // The list element type of "int_arrays" is TAG_Int_Array.
+    let pos = buf.windows(10).position(|w| w == b"int_arrays").unwrap();
+    assert_eq!(buf[pos + 10], 11);
+}
+
+#[test]
+fn long_array_vs_list() {
+    #[derive(Serialize)]
+    struct Section {
+        #[serde(with = "long_array")]
+        block_states: Vec<i64>,
+        heights: Vec<i64>,
+    }
+
+    let buf = to_vec(&Section {
+        block_states: vec![1, 2],
+        heights: vec![3, 4],
+    })
+    .unwrap();
+
+    // The tag byte comes right before the name length.
+    let pos = buf.windows(12).position(|w| w == b"block_states").unwrap();
+    assert_eq!(buf[pos - 3], 12);
+
+    let pos = buf.windows(7).position(|w| w == b"heights").unwrap();
+    assert_eq!(buf[pos - 3], 9);
+    // The element type of the list is TAG_Long.
+    assert_eq!(buf[pos + 7], 4);
+
+    let Value::Compound(value) = from_slice_exact::<Value>(&buf).unwrap() else {
+        unreachable!()
+    };
+    assert_eq!(value["block_states"], Value::LongArray(vec![1, 2]));
+    assert_eq!(value["heights"], List::Long(vec![3, 4]).into());
+}
+
+#[test]
+fn array_wrappers() {
+    #[derive(PartialEq, Debug, Serialize, Deserialize)]
+    struct Wrappers {
+        bytes: crate::ByteArray,
+        ints: Option<crate::IntArray>,
+        longs: Vec<crate::LongArray>,
+    }
+
+    let wrappers = Wrappers {
+        bytes: crate::ByteArray(vec![-1, 0, 1]),
+        ints: Some(vec![i32::MIN, i32::MAX].into()),
+        longs: vec![crate::LongArray(vec![1]), crate::LongArray(vec![])],
+    };
+
+    let buf = to_vec(&wrappers).unwrap();
+
+    assert_eq!(from_slice_exact::<Wrappers>(&buf).unwrap(), wrappers);
+
+    let Value::Compound(value) = from_slice_exact::<Value>(&buf).unwrap() else {
+        unreachable!()
+    };
+
+    assert_eq!(value["bytes"], Value::ByteArray(vec![-1, 0, 1]));
+    assert_eq!(value["ints"], Value::IntArray(vec![i32::MIN, i32::MAX]));
+    assert_eq!(
+        value["longs"],
+        List::LongArray(vec![vec![1], vec![]]).into()
+    );
+}
+
+#[test]
+fn semantic_eq() {
+    let a = Value::Compound(Compound::from_iter([
+        ("bytes".to_owned(), Value::ByteArray(vec![1, 2])),
+        ("longs".to_owned(), List::Long(vec![3]).into()),
+        (
+            "nested".to_owned(),
+            List::IntArray(vec![vec![4], vec![]]).into(),
+        ),
+        ("empty".to_owned(), List::Byte(vec![]).into()),
+    ]));
+
+    let b = Value::Compound(Compound::from_iter([
+        ("longs".to_owned(), Value::LongArray(vec![3])),
+        ("bytes".to_owned(), List::Byte(vec![1, 2]).into()),
+        (
+            "nested".to_owned(),
+            List::List(vec![List::Int(vec![4]), List::String(vec![])]).into(),
+        ),
+        ("empty".to_owned(), Value::IntArray(vec![])),
+    ]));
+
+    assert_ne!(a, b);
+    assert!(a.semantic_eq(&b));
+    assert!(b.semantic_eq(&a));
+
+    assert!(!Value::IntArray(vec![1]).semantic_eq(&List::Long(vec![1]).into()));
+    assert!(!Value::IntArray(vec![1]).semantic_eq(&List::Int(vec![1, 2]).into()));
+    assert!(!Value::Int(1).semantic_eq(&Value::Long(1)));
+}
+#[test]
+fn optional_containers() {
+    #[derive(PartialEq, Debug, Serialize, Deserialize)]
+    struct Optionals {
+        list: Option<Vec<i32>>,
+        compound: Option<Compound>,
+        string: Option<String>,
+    }
+
+    let some = Optionals {
+        list: Some(vec![1, 2, 3]),
+        compound: Some(Compound::from_iter([("a".into(), 1.into())])),
+        string: Some("abc".into()),
+    };
+
+    let none = Optionals {
+        list: None,
+        compound: None,
+        string: None,
+    };
+
+    for optionals in [some, none] {
+        let buf = to_vec(&optionals).unwrap();
+
+        let Value::Compound(c) = from_reader(&mut buf.as_slice()).unwrap() else {
+            unreachable!()
+        };
+
+        assert_eq!(c.contains_key("list"), optionals.list.is_some());
+        assert_eq!(c.contains_key("compound"), optionals.compound.is_some());
+        assert_eq!(c.contains_key("string"), optionals.string.is_some());
+
+        let optionals_de: Optionals = from_reader(&mut buf.as_slice()).unwrap();
+        assert_eq!(optionals, optionals_de);
+    }
+}
+
+#[test]
+fn none_in_list() {
+    let value = BTreeMap::from([("list", vec![Some(1), None])]);
+    assert!(to_vec(&value).is_err());
+
+    let value = BTreeMap::from([("list", vec![None, Some(1)])]);
+    assert!(to_vec(&value).is_err());
+}
+
+#[test]
+fn present_vs_absent() {
+    #[derive(PartialEq, Debug, Deserialize)]
+    struct Optionals {
+        present: Option<i32>,
+        absent: Option<i32>,
+        #[serde(default = "forty_two")]
+        present_default: Option<i32>,
+        #[serde(default = "forty_two")]
+        absent_default: Option<i32>,
+    }
+
+    fn forty_two() -> Option<i32> {
+        Some(42)
+    }
+
+    let value = Compound::from_iter([
+        ("present".into(), 0.into()),
+        ("present_default".into(), 0.into()),
+    ]);
+
+    let buf = to_vec(&value).unwrap();
+
+    assert_eq!(
+        from_reader::<_, Optionals>(buf.as_slice()).unwrap(),
+        Optionals {
+            present: Some(0),
+            absent: None,
+            present_default: Some(0),
+            absent_default: Some(42),
+        }
+    );
+}
+
+#[test]
+fn default_fields() {
+    #[derive(PartialEq, Debug, Deserialize)]
+    struct Item {
+        id: String,
+        #[serde(default)]
+        count: i32,
+    }
+
+    let absent = Compound::from_iter([("id".into(), "minecraft:stone".into())]);
+    let mut present = absent.clone();
+    present.insert("count".into(), 3.into());
+
+    assert_eq!(
+        from_slice_exact::<Item>(&to_vec(&absent).unwrap()).unwrap(),
+        Item {
+            id: "minecraft:stone".into(),
+            count: 0,
+        }
+    );
+    assert_eq!(
+        from_slice_exact::<Item>(&to_vec(&present).unwrap()).unwrap(),
+        Item {
+            id: "minecraft:stone".into(),
+            count: 3,
+        }
+    );
+}
+
+#[test]
+fn numeric_widening() {
+    #[derive(PartialEq, Debug, Deserialize)]
+    struct Entity {
+        health: f32,
+        x: f64,
+        age: i64,
+        level: i8,
+    }
+
+    let bytes = to_vec(&Compound::from_iter([
+        ("health".to_owned(), Value::Short(20)),
+        ("x".to_owned(), Value::Int(-3)),
+        ("age".to_owned(), Value::Byte(7)),
+        ("level".to_owned(), Value::Int(100)),
+    ]))
+    .unwrap();
+
+    assert_eq!(
+        from_slice_exact::<Entity>(&bytes).unwrap(),
+        Entity {
+            health: 20.0,
+            x: -3.0,
+            age: 7,
+            level: 100,
+        }
+    );
+
+    // Narrowing fails if the value does not fit, and floats never become
+    // integers.
+    let bytes = to_vec(&Compound::from_iter([
+        ("health".to_owned(), Value::Float(20.0)),
+        ("x".to_owned(), Value::Double(0.5)),
+        ("age".to_owned(), Value::Long(0)),
+        ("level".to_owned(), Value::Int(1000)),
+    ]))
+    .unwrap();
+    assert!(from_slice_exact::<Entity>(&bytes).is_err());
+
+    let bytes = to_vec(&Compound::from_iter([
+        ("health".to_owned(), Value::Float(20.0)),
+        ("x".to_owned(), Value::Double(0.5)),
+        ("age".to_owned(), Value::Double(0.0)),
+        ("level".to_owned(), Value::Byte(1)),
+    ]))
+    .unwrap();
+    assert!(from_slice_exact::<Entity>(&bytes).is_err());
+}
+
+#[test]
+fn newtype_struct_transparency() {
+    #[derive(PartialEq, Debug, Serialize, Deserialize)]
+    struct Meters(f64);
+
+    #[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+    struct Key(String);
+
+    #[derive(PartialEq, Debug, Serialize, Deserialize)]
+    struct Wrapped {
+        distance: Meters,
+        distances: Vec<Meters>,
+        map: BTreeMap<String, Meters>,
+    }
+
+    #[derive(Serialize)]
+    struct Bare {
+        distance: f64,
+        distances: Vec<f64>,
+        map: BTreeMap<String, f64>,
+    }
+
+    let wrapped = Wrapped {
+        distance: Meters(1.5),
+        distances: vec![Meters(2.0), Meters(-3.0)],
+        map: BTreeMap::from([("a".to_owned(), Meters(4.0))]),
+    };
+
+    let bare = Bare {
+        distance: 1.5,
+        distances: vec![2.0, -3.0],
+        map: BTreeMap::from([("a".to_owned(), 4.0)]),
+    };
+
+    let buf = to_vec(&wrapped).unwrap();
+    assert_eq!(buf, to_vec(&bare).unwrap());
+    assert_eq!(from_slice_exact::<Wrapped>(&buf).unwrap(), wrapped);
+
+    // Newtypes around the root compound and keys are transparent too.
+    #[derive(PartialEq, Debug, Serialize, Deserialize)]
+    struct Root(BTreeMap<String, i32>);
+
+    let root = Root(BTreeMap::from([("k".to_owned(), 5)]));
+    let buf = to_vec(&root).unwrap();
+
+    assert_eq!(buf, to_vec(&root.0).unwrap());
+    assert_eq!(
+        buf,
+        to_vec(&BTreeMap::from([(Key("k".to_owned()), 5)])).unwrap()
+    );
+    assert_eq!(from_slice_exact::<Root>(&buf).unwrap(), root);
+}
+
+#[test]
+fn bool_tag() {
+    #[derive(PartialEq, Debug, Serialize, Deserialize)]
+    struct Flags {
+        on: bool,
+        off: bool,
+        list: Vec<bool>,
+    }
+
+    let flags = Flags {
+        on: true,
+        off: false,
+        list: vec![false, true],
+    };
+
+    let bytes = to_vec(&flags).unwrap();
+    assert_eq!(
+        from_slice_exact::<Value>(&bytes).unwrap(),
+        Value::Compound(Compound::from_iter([
+            ("on".into(), Value::Byte(1)),
+            ("off".into(), Value::Byte(0)),
+            ("list".into(), List::Byte(vec![0, 1]).into()),
+        ]))
+    );
+    assert_eq!(from_slice_exact::<Flags>(&bytes).unwrap(), flags);
+
+    let mut bytes = Vec::new();
+    let mut ser = Serializer::new(&mut bytes, "");
+    ser.set_bool_tag(BoolTag::Int);
+    flags.serialize(&mut ser).unwrap();
+
+    assert_eq!(
+        from_slice_exact::<Value>(&bytes).unwrap(),
+        Value::Compound(Compound::from_iter([
+            ("on".into(), Value::Int(1)),
+            ("off".into(), Value::Int(0)),
+            ("list".into(), List::Int(vec![0, 1]).into()),
+        ]))
+    );
+    assert_eq!(from_slice_exact::<Flags>(&bytes).unwrap(), flags);
+
+    let bytes = to_vec(&Compound::from_iter([
+        ("on".into(), Value::Int(2)),
+        ("off".into(), Value::Int(0)),
+        ("list".into(), List::Int(vec![]).into()),
+    ]))
+    .unwrap();
+    assert!(from_slice_exact::<Flags>(&bytes).is_err());
+}
+
+#[test]
+fn transcode_round_trip() {
+    let mut value = Struct::value();
+
+    if let Value::Compound(c) = &mut value {
+        c.insert(
+            "nested".into(),
+            List::List(vec![
+                List::Compound(vec![Compound::from_iter([("a b".into(), 1.5_f32.into())])]),
+                List::Double(vec![f64::NAN, -0.0]),
+                List::Byte(vec![]),
+            ])
+            .into(),
+        );
+    }
+
+    let binary = to_vec(&value).unwrap();
+
+    let mut snbt = Vec::new();
+    transcode::binary_to_snbt(binary.as_slice(), &mut snbt).unwrap();
+
+    let mut binary_again = Vec::new();
+    transcode::snbt_to_binary(snbt.as_slice(), &mut binary_again).unwrap();
+
+    assert_eq!(binary, binary_again);
+}
+
+#[test]
+fn snbt_to_binary_types() {
+    let snbt = r#"{ b: true, s: 3s, i: 7, l: -1L, f: 2.5f, d: 1e3, str: 'it\'s', w: hello }"#;
+
+    let mut binary = Vec::new();
+    transcode::snbt_to_binary(snbt.as_bytes(), &mut binary).unwrap();
+
+    let value: Value = from_slice_exact(&binary).unwrap();
+
+    let expected = Value::Compound(Compound::from_iter([
+        ("b".into(), 1_i8.into()),
+        ("s".into(), 3_i16.into()),
+        ("i".into(), 7.into()),
+        ("l".into(), (-1_i64).into()),
+        ("f".into(), 2.5_f32.into()),
+        ("d".into(), 1e3.into()),
+        ("str".into(), "it's".into()),
+        ("w".into(), "hello".into()),
+    ]));
+
+    assert_eq!(value, expected);
+
+    let err = transcode::snbt_to_binary("{a:[1,2b]}".as_bytes(), &mut Vec::new()).unwrap_err();
+    assert_eq!(err.path(), "a[1]");
+
+    let snbt = format!("{{a:{{{}:1b}}}}", "k".repeat(80_000));
+    let err = transcode::snbt_to_binary(snbt.as_bytes(), &mut Vec::new()).unwrap_err();
+    assert!(err
+        .to_string()
+        .ends_with("key of 80000 bytes exceeds the maximum length of 65535 bytes"));
+}
+
+#[test]
+fn snbt_from_reader() {
+    let snbt = snbt::to_string(&Struct::new()).unwrap();
+
+    assert_eq!(snbt::from_str::<Struct>(&snbt).unwrap(), Struct::new());
+    assert_eq!(
+        snbt::from_reader::<_, Value>(std::io::BufReader::with_capacity(4, snbt.as_bytes()))
+            .unwrap(),
+        Struct::value()
+    );
+
+    assert!(snbt::from_str::<Value>("{a:1b").is_err());
+    assert!(snbt::from_str::<Value>("[1b]").is_err());
+}
+
+#[test]
+fn snbt_display() {
+    let value = Struct::value();
+    let Value::Compound(compound) = &value else {
+        unreachable!()
+    };
+
+    assert_eq!(value.to_string(), snbt::to_string(&value).unwrap());
+    assert_eq!(compound.to_string(), value.to_string());
+    assert_eq!(
+        format!("{value:#}"),
+        snbt::to_string_pretty(&value).unwrap()
+    );
+    assert_eq!(format!("{compound:#}"), format!("{value:#}"));
+
+    let list = List::Compound(vec![Compound::new(), Compound::new()]);
+    assert_eq!(list.to_string(), "[{},{}]");
+    assert_eq!(format!("{list:#}"), "[{}, {}]");
+    assert_eq!(Value::Long(-1).to_string(), "-1L");
+}
+
+#[test]
+fn type_tagged_json() {
+    let value = Value::Compound(Compound::from_iter([
+        ("byte".into(), Value::Byte(-1)),
+        ("short".into(), Value::Short(2)),
+        ("int".into(), Value::Int(3)),
+        ("long".into(), Value::Long(i64::MIN)),
+        ("float".into(), Value::Float(0.1)),
+        ("double".into(), Value::Double(0.1)),
+        ("string".into(), Value::String("abc".into())),
+        ("byte_array".into(), Value::ByteArray(vec![1])),
+        ("int_array".into(), Value::IntArray(vec![])),
+        ("long_array".into(), Value::LongArray(vec![3])),
+        ("empty".into(), List::Compound(vec![]).into()),
+        (
+            "nested".into(),
+            List::List(vec![List::Double(vec![0.5]), List::Int(vec![])]).into(),
+        ),
+        (
+            "compounds".into(),
+            List::Compound(vec![Compound::from_iter([(
+                "a".into(),
+                List::LongArray(vec![vec![1]]).into(),
+            )])])
+            .into(),
+        ),
+    ]));
+
+    let mut json = Vec::new();
+    type_tagged::serialize(&value, &mut serde_json::Serializer::new(&mut json)).unwrap();
+    let read_back =
+        type_tagged::deserialize(&mut serde_json::Deserializer::from_slice(&json)).unwrap();
+    assert_eq!(read_back, value);
+
+    #[derive(PartialEq, Debug, Serialize, Deserialize)]
+    struct Message {
+        #[serde(with = "type_tagged")]
+        data: Value,
+    }
+
+    let message: Message =
+        serde_json::from_str(r#"{"data": {"value": [1, 2], "type": "byte_array"}}"#).unwrap();
+    assert_eq!(message.data, Value::ByteArray(vec![1, 2]));
+    assert_eq!(
+        serde_json::to_string(&message).unwrap(),
+        r#"{"data":{"type":"byte_array","value":[1,2]}}"#
+    );
+
+    assert!(
+        serde_json::from_str::<Message>(r#"{"data": {"type": "byte", "value": 128}}"#).is_err()
+    );
+    assert!(serde_json::from_str::<Message>(r#"{"data": {"type": "end", "value": 0}}"#).is_err());
+}
+
+#[test]
+fn snbt_pretty() {
+    let value = Value::Compound(Compound::from_iter([
+        ("pos".into(), List::Int(vec![1, 2, 3]).into()),
+        (
+            "items".into(),
+            List::Compound(vec![
+                Compound::from_iter([("id".into(), "minecraft:stone".into())]),
+                Compound::from_iter([
+                    ("id".into(), "minecraft:diamond_sword".into()),
+                    ("damage".into(), 3_i16.into()),
+                ]),
+            ])
+            .into(),
+        ),
+        ("heights".into(), Value::LongArray((0..20).collect())),
+        ("empty".into(), Compound::new().into()),
+    ]));
+
+    let expected = r#"{
+    pos: [1, 2, 3],
+    items: [
+        {id: "minecraft:stone"},
+        {
+            id: "minecraft:diamond_sword",
+            damage: 3s
+        }
+    ],
+    heights: [L;
+        0L, 1L, 2L, 3L, 4L, 5L, 6L, 7L, 8L, 9L, 10L, 11L, 12L, 13L, 14L, 15L,
+        16L, 17L, 18L, 19L
+    ],
+    empty: {}
+}"#;
+
+    assert_eq!(snbt::to_string_pretty(&value).unwrap(), expected);
+
+    let compact = snbt::SnbtFormatter::new()
+        .wrap_arrays(None)
+        .max_inline_width(Some(usize::MAX))
+        .to_string(&value)
+        .unwrap();
+
+    assert!(!compact.contains('\n'));
+    assert_eq!(compact.replace(' ', ""), snbt::to_string(&value).unwrap());
+}
+
+#[test]
+fn merge_list_by_key() {
+    let modifier = |name: &str, amount: f64| {
+        Compound::from_iter([
+            ("Name".to_owned(), Value::from(name)),
+            ("Amount".to_owned(), Value::from(amount)),
+        ])
+    };
+
+    let mut value = Value::Compound(Compound::from_iter([(
+        "AttributeModifiers".into(),
+        List::Compound(vec![modifier("speed", 0.1), modifier("armor", 2.0)]).into(),
+    )]));
+
+    value
+        .merge_list_by_key(
+            "AttributeModifiers",
+            "Name",
+            &[modifier("armor", 4.0), modifier("luck", 1.0)],
+        )
+        .unwrap();
+
+    let expected = Value::Compound(Compound::from_iter([(
+        "AttributeModifiers".into(),
+        List::Compound(vec![
+            modifier("speed", 0.1),
+            modifier("armor", 4.0),
+            modifier("luck", 1.0),
+        ])
+        .into(),
+    )]));
+
+    assert_eq!(value, expected);
+
+    // A missing list is created.
+    value
+        .merge_list_by_key("Other", "Name", &[modifier("luck", 1.0)])
+        .unwrap();
+
+    // Paths through non-compounds fail without modifying the value.
+    let before = value.clone();
+    let err = value
+        .merge_list_by_key("AttributeModifiers.x", "Name", &[])
+        .unwrap_err();
+    assert_eq!(err.path(), "AttributeModifiers");
+    assert_eq!(value, before);
+
+    assert!(value
+        .merge_list_by_key("Other", "Name", &[Compound::new()])
+        .is_err());
+}
+
+#[test]
+fn schema_validation() {
+    let schema = Schema::new()
+        .required("DataVersion", TagType::Int)
+        .required("Level.xPos", TagType::Int)
+        .required("Level.Sections", TagType::List)
+        .optional("Level.Biomes", TagType::IntArray)
+        .optional("Level.Status.Name", TagType::String);
+
+    // Schemas are data and can be stored as NBT themselves.
+    let schema: Schema = from_slice_exact(&to_vec(&schema).unwrap()).unwrap();
+
+    let value = Value::Compound(Compound::from_iter([
+        ("DataVersion".into(), 2586.into()),
+        (
+            "Level".into(),
+            Compound::from_iter([
+                ("xPos".into(), 4_i64.into()),
+                ("Status".into(), "full".into()),
+            ])
+            .into(),
+        ),
+    ]));
+
+    assert_eq!(
+        schema.validate(&value),
+        [
+            Mismatch {
+                path: "Level.xPos".into(),
+                expected: TagType::Int,
+                found: Some(TagType::Long),
+            },
+            Mismatch {
+                path: "Level.Sections".into(),
+                expected: TagType::List,
+                found: None,
+            },
+            Mismatch {
+                path: "Level.Status".into(),
+                expected: TagType::Compound,
+                found: Some(TagType::String),
+            },
+        ]
+    );
+
+    assert!(Schema::new()
+        .required("DataVersion", TagType::Int)
+        .validate(&value)
+        .is_empty());
+}
+
+#[test]
+fn bedrock_leveldat() {
+    #[rustfmt::skip]
+    let body = [
+        10, 0, 0,
+            3, 14, 0, b'S', b't', b'o', b'r', b'a', b'g', b'e', b'V', b'e', b'r', b's', b'i', b'o', b'n', 10, 0, 0, 0,
+            8, 9, 0, b'L', b'e', b'v', b'e', b'l', b'N', b'a', b'm', b'e', 3, 0, 0xe6, 0x97, 0xa5,
+            9, 1, 0, b'l', 4, 2, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x80,
+        0,
+    ];
+
+    let mut file = vec![10, 0, 0, 0];
+    file.extend((body.len() as u32).to_le_bytes());
+    file.extend(body);
+
+    let (version, value) = le::from_bedrock_leveldat::<_, Value>(file.as_slice()).unwrap();
+
+    assert_eq!(version, 10);
+    assert_eq!(
+        value,
+        Value::Compound(Compound::from_iter([
+            ("StorageVersion".into(), 10.into()),
+            ("LevelName".into(), "日".into()),
+            ("l".into(), List::Long(vec![1, i64::MIN]).into()),
+        ]))
+    );
+
+    let mut written = Vec::new();
+    le::to_bedrock_leveldat(&mut written, version, &value).unwrap();
+    assert_eq!(written, file);
+
+    // The length in the header must match the body.
+    file[4] -= 1;
+    assert!(le::from_bedrock_leveldat::<_, Value>(file.as_slice()).is_err());
+    file[4] += 2;
+    file.push(0);
+    assert!(le::from_bedrock_leveldat::<_, Value>(file.as_slice()).is_err());
+}
+
+#[test]
+fn project() {
+    let value = Struct::value();
+
+    assert_eq!(
+        value.project(&["inner.long", "missing", "byte.nope", "inner.float", "byte"]),
+        Value::Compound(Compound::from_iter([
+            (
+                "inner".into(),
+                Compound::from_iter([
+                    ("long".into(), i64::MAX.into()),
+                    ("float".into(), 1e10_f32.into()),
+                ])
+                .into()
+            ),
+            ("byte".into(), 123_i8.into()),
+        ]))
+    );
+
+    // Selecting a compound and a path inside of it keeps the whole compound.
+    let inner = value.project(&["inner", "inner.int"]);
+    assert_eq!(inner, value.project(&["inner"]));
+
+    assert_eq!(
+        Value::Int(5).project(&["a"]),
+        Value::Compound(Compound::new())
+    );
+}
+
+#[test]
+fn json_pointer() {
+    let mut value = Value::Compound(Compound::from_iter([
+        ("a/b".to_owned(), Value::Int(1)),
+        ("m~n".to_owned(), Value::Int(2)),
+        ("".to_owned(), Value::Int(3)),
+        (
+            "lists".to_owned(),
+            List::List(vec![
+                List::Int(vec![4]),
+                List::Compound(vec![Compound::from_iter([("x".to_owned(), Value::Int(5))])]),
+            ])
+            .into(),
+        ),
+        ("arr".to_owned(), Value::LongArray(vec![6, 7])),
+    ]));
+
+    assert_eq!(value.pointer(""), Some(ValueRef::from(&value)));
+    assert_eq!(value.pointer("/a~1b"), Some(ValueRef::Int(1)));
+    assert_eq!(value.pointer("/m~0n"), Some(ValueRef::Int(2)));
+    assert_eq!(value.pointer("/"), Some(ValueRef::Int(3)));
+    assert_eq!(value.pointer("/lists/1/0/x"), Some(ValueRef::Int(5)));
+
+    // List and array elements of every type can be returned.
+    assert_eq!(
+        value.pointer("/lists/0"),
+        Some(ValueRef::List(&List::Int(vec![4])))
+    );
+    assert_eq!(value.pointer("/lists/0/0"), Some(ValueRef::Int(4)));
+    assert_eq!(value.pointer("/arr/1"), Some(ValueRef::Long(7)));
+    assert_eq!(value.pointer("/lists/0/1"), None);
+    assert_eq!(value.pointer("/lists/0/0/0"), None);
+
+    // Malformed pointers.
+    assert_eq!(value.pointer("a~1b"), None);
+    assert_eq!(value.pointer("/a~2b"), None);
+    assert_eq!(value.pointer("/lists/01/0/x"), None);
+    assert_eq!(value.pointer("/lists/+1/0/x"), None);
+
+    *value.pointer_mut("/lists/1/0/x").unwrap() = Value::Int(6);
+    assert_eq!(value.pointer("/lists/1/0/x"), Some(ValueRef::Int(6)));
+    assert_eq!(value.pointer_mut("/lists/0"), None);
+}
+
+#[test]
+fn type_names() {
+    assert_eq!(Value::Byte(0).type_name(), "TAG_Byte");
+    assert_eq!(Value::IntArray(vec![]).type_name(), "TAG_Int_Array");
+    assert_eq!(Value::Compound(Compound::new()).type_name(), "TAG_Compound");
+    assert_eq!(TagType::End.tag_name(), "TAG_End");
+
+    let list = List::Compound(vec![]);
+    assert_eq!(Value::List(list.clone()).type_name(), "TAG_List");
+    assert_eq!(list.element_type(), TagType::Compound);
+    assert_eq!(list.element_type_name(), "TAG_Compound");
+    assert_eq!(
+        List::LongArray(vec![]).element_type_name(),
+        "TAG_Long_Array"
+    );
+}
+
+#[test]
+fn tag_type_ids() {
+    for id in 0..=12 {
+        let tag_type = TagType::from_u8(id).unwrap();
+
+        assert_eq!(tag_type.as_u8(), id);
+        assert_eq!(Tag::from_id(id).unwrap(), tag_type);
+    }
+
+    assert_eq!(TagType::from_u8(0), Some(TagType::End));
+    assert_eq!(TagType::from_u8(12), Some(TagType::LongArray));
+    assert_eq!(TagType::from_u8(13), None);
+    assert_eq!(TagType::from_u8(u8::MAX), None);
+}
+
+#[test]
+fn tag_histogram() {
+    let value = Value::Compound(Compound::from_iter([
+        ("a".into(), Value::Int(1)),
+        ("b".into(), List::Int(vec![1, 2, 3]).into()),
+        (
+            "c".into(),
+            List::List(vec![List::String(vec!["x".into()]), List::Compound(vec![])]).into(),
+        ),
+        ("d".into(), Value::IntArray(vec![1, 2])),
+    ]));
+
+    let counts = value.tag_histogram();
+
+    assert_eq!(counts[TagType::End as usize], 0);
+    assert_eq!(counts[TagType::Compound as usize], 1);
+    assert_eq!(counts[TagType::Int as usize], 4);
+    assert_eq!(counts[TagType::List as usize], 4);
+    assert_eq!(counts[TagType::String as usize], 1);
+    assert_eq!(counts[TagType::IntArray as usize], 1);
+    assert_eq!(counts.iter().sum::<usize>(), 11);
+}
+
+#[test]
+fn numeric_coercion() {
+    assert_eq!(Value::Long(i64::MAX).as_i64(), Some(i64::MAX));
+    assert_eq!(Value::Long(1 << 53).as_f64(), Some(9007199254740992.0));
+    assert_eq!(Value::Float(-1.5).as_i64(), Some(-1));
+    assert_eq!(Value::Double(1e300).as_i64(), Some(i64::MAX));
+    assert_eq!(Value::Double(f64::NAN).as_i64(), Some(0));
+    assert_eq!(Value::Int(i32::MIN).as_f64(), Some(i32::MIN as f64));
+    assert_eq!(Value::ByteArray(vec![1]).as_f64(), None);
+    assert_eq!(Value::Compound(Compound::new()).as_i64(), None);
+}
+
+#[test]
+fn scalar_paths() {
+    let value = Value::Compound(Compound::from_iter([
+        ("a".to_owned(), Value::String("x".to_owned())),
+        (
+            "b".to_owned(),
+            List::List(vec![List::Short(vec![1, 2]), List::IntArray(vec![vec![3]])]).into(),
+        ),
+        ("c".to_owned(), Value::ByteArray(vec![4])),
+        ("d".to_owned(), Value::Compound(Compound::new())),
+    ]));
+
+    let scalars: Vec<_> = value.scalars().collect();
+
+    assert_eq!(
+        scalars,
+        [
+            ("a".to_owned(), ScalarRef::String("x")),
+            ("b[0][0]".to_owned(), ScalarRef::Short(1)),
+            ("b[0][1]".to_owned(), ScalarRef::Short(2)),
+            ("b[1][0][0]".to_owned(), ScalarRef::Int(3)),
+            ("c[0]".to_owned(), ScalarRef::Byte(4)),
+        ]
+    );
+
+    assert_eq!(
+        value.scalars().nth(3),
+        Some(("b[1][0][0]".to_owned(), ScalarRef::Int(3)))
+    );
+
+    assert_eq!(
+        Value::Int(5).scalars().collect::<Vec<_>>(),
+        [(String::new(), ScalarRef::Int(5))]
+    );
+}
+
+#[test]
+fn flatten_unflatten() {
+    let value = Value::Compound(Compound::from_iter([
+        ("a".to_owned(), Value::String("x".to_owned())),
+        (
+            "b".to_owned(),
+            List::List(vec![List::Short(vec![1, 2]), List::IntArray(vec![vec![3]])]).into(),
+        ),
+        ("c".to_owned(), Value::ByteArray(vec![4])),
+        (
+            "d".to_owned(),
+            List::Compound(vec![Compound::from_iter([("e".to_owned(), Value::Int(5))])]).into(),
+        ),
+    ]));
+
+    let flat = value.flatten();
+
+    assert_eq!(
+        flat,
+        BTreeMap::from([
+            ("a".to_owned(), Value::String("x".to_owned())),
+            ("b[0][0]".to_owned(), Value::Short(1)),
+            ("b[0][1]".to_owned(), Value::Short(2)),
+            ("b[1][0]".to_owned(), Value::IntArray(vec![3])),
+            ("c".to_owned(), Value::ByteArray(vec![4])),
+            ("d[0].e".to_owned(), Value::Int(5)),
+        ])
+    );
+
+    assert_eq!(Value::unflatten(flat).unwrap(), value);
+
+    assert_eq!(
+        Value::Int(1).flatten(),
+        BTreeMap::from([(String::new(), Value::Int(1))])
+    );
+    assert_eq!(
+        Value::unflatten(Value::Int(1).flatten()).unwrap(),
+        Value::Int(1)
+    );
+
+    let unflatten = |entries: &[(&str, Value)]| {
+        Value::unflatten(
+            entries
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect(),
+        )
+    };
+
+    // Index 10 comes before 2 when the paths are sorted as strings, and
+    // indices 3 through 9 are missing.
+    let list = unflatten(&[
+        ("l[10]", Value::Byte(10)),
+        ("l[2]", Value::Byte(2)),
+        ("l[0]", Value::Byte(0)),
+        ("l[1]", Value::Byte(1)),
+    ]);
+    assert!(list.is_err());
+    assert_eq!(list.unwrap_err().path(), "l");
+
+    let list = Value::unflatten(
+        (0..11)
+            .map(|i| (format!("l[{i}]"), Value::Byte(i)))
+            .collect(),
+    )
+    .unwrap();
+    assert_eq!(
+        list.pointer("/l"),
+        Some(ValueRef::List(&List::Byte((0..11).collect())))
+    );
+
+    let err = unflatten(&[("a", Value::Int(1)), ("a.b", Value::Int(2))]).unwrap_err();
+    assert_eq!(err.path(), "a");
+
+    let err = unflatten(&[("a[0]", Value::Int(1)), ("a[1]", Value::Long(2))]).unwrap_err();
+    assert_eq!(err.path(), "a[1]");
+
+    assert!(unflatten(&[("a[x]", Value::Int(1))]).is_err());
+    assert!(unflatten(&[("a[0]b", Value::Int(1))]).is_err());
+}
+
+#[test]
+fn try_from_value() {
+    let compound = Compound::from_iter([("a".to_owned(), Value::Byte(1))]);
+
+    assert_eq!(
+        Compound::try_from(Value::Compound(compound.clone())).unwrap(),
+        compound
+    );
+    assert_eq!(
+        List::try_from(Value::List(List::Int(vec![1, 2]))).unwrap(),
+        List::Int(vec![1, 2])
+    );
+
+    assert_eq!(
+        Compound::try_from(Value::Int(1)).unwrap_err().to_string(),
+        "expected compound, found int"
+    );
+    assert_eq!(
+        List::try_from(Value::IntArray(vec![1]))
+            .unwrap_err()
+            .to_string(),
+        "expected list, found int array"
+    );
+}
+
+#[test]
+fn into_value() {
+    let value =
+        Compound::from_iter([("list".to_owned(), List::Short(vec![1]).into_value())]).into_value();
+
+    assert_eq!(
+        value,
+        Value::Compound(Compound::from_iter([(
+            "list".to_owned(),
+            Value::List(List::Short(vec![1]))
+        )]))
+    );
+}
+
+#[test]
+fn value_from_slices() {
+    let bytes: &[i8] = &[1, -2, 3];
+    let ints: &[i32] = &[4, 5];
+    let longs: &[i64] = &[];
+
+    assert_eq!(Value::from(bytes), Value::ByteArray(vec![1, -2, 3]));
+    assert_eq!(Value::from(ints), Value::IntArray(vec![4, 5]));
+    assert_eq!(Value::from(longs), Value::LongArray(vec![]));
+
+    assert_eq!(Value::from([1_i8, 2]), Value::ByteArray(vec![1, 2]));
+    assert_eq!(Value::from([3_i32; 3]), Value::IntArray(vec![3, 3, 3]));
+    assert_eq!(Value::from([i64::MAX]), Value::LongArray(vec![i64::MAX]));
+}
+
+#[test]
+fn prune_empty() {
+    assert!(Value::Compound(Compound::new()).is_empty());
+    assert!(Value::LongArray(vec![]).is_empty());
+    assert!(Value::List(List::Double(vec![])).is_empty());
+    assert!(!Value::String(String::new()).is_empty());
+    assert!(!Value::Byte(0).is_empty());
+
+    let mut value = Value::Compound(Compound::from_iter([
+        ("a".into(), Value::IntArray(vec![])),
+        (
+            "b".into(),
+            List::Compound(vec![
+                Compound::new(),
+                Compound::from_iter([("x".into(), List::List(vec![]).into())]),
+                Compound::from_iter([("y".into(), 1.into()), ("z".into(), Compound::new().into())]),
+            ])
+            .into(),
+        ),
+        ("c".into(), List::ByteArray(vec![vec![], vec![1]]).into()),
+        (
+            "d".into(),
+            List::List(vec![List::Compound(vec![Compound::new()])]).into(),
+        ),
+    ]));
+
+    value.prune_empty();
+
+    assert_eq!(
+        value,
+        Value::Compound(Compound::from_iter([
+            (
+                "b".into(),
+                List::Compound(vec![Compound::from_iter([("y".into(), 1.into())])]).into()
+            ),
+            ("c".into(), List::ByteArray(vec![vec![1]]).into()),
+        ]))
+    );
+}
+
+#[test]
+fn compound_prune_empty() {
+    let nested = |inner: Value| Value::Compound(Compound::from_iter([("inner".into(), inner)]));
+
+    let mut compound = Compound::from_iter([
+        ("empty".into(), nested(nested(Compound::new().into()))),
+        ("kept".into(), nested(nested(Value::LongArray(vec![1])))),
+        ("zero".into(), Value::Int(0)),
+    ]);
+
+    compound.prune_empty();
+
+    assert_eq!(
+        compound,
+        Compound::from_iter([
+            ("kept".into(), nested(nested(Value::LongArray(vec![1])))),
+            ("zero".into(), Value::Int(0)),
+        ])
+    );
+
+    let mut empty = Compound::from_iter([("a".into(), List::Int(vec![]).into())]);
+    empty.prune_empty();
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn retain_recursive() {
+    let mut value = Value::Compound(Compound::from_iter([
+        ("_a".to_owned(), Value::Int(1)),
+        (
+            "only_hidden".to_owned(),
+            Value::Compound(Compound::from_iter([("_b".to_owned(), Value::Int(2))])),
+        ),
+        (
+            "nested".to_owned(),
+            List::List(vec![List::Compound(vec![Compound::from_iter([
+                ("_c".to_owned(), Value::Int(3)),
+                ("d".to_owned(), Value::Int(4)),
+            ])])])
+            .into(),
+        ),
+    ]));
+
+    // Pruning is bottom-up, so `only_hidden` is already empty when checked.
+    value.retain_recursive(|k, v| {
+        !k.starts_with('_') && !v.as_compound().is_some_and(|c| c.is_empty())
+    });
+
+    assert_eq!(
+        value,
+        Value::Compound(Compound::from_iter([(
+            "nested".to_owned(),
+            List::List(vec![List::Compound(vec![Compound::from_iter([(
+                "d".to_owned(),
+                Value::Int(4)
+            )])])])
+            .into(),
+        )]))
+    );
+}
+
+#[test]
+fn list_iter() {
+    let list = List::String(vec!["a".to_owned(), "b".to_owned(), "c".to_owned()]);
+
+    let mut iter = list.iter();
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.next(), Some(ValueRef::String("a")));
+    assert_eq!(iter.next_back(), Some(ValueRef::String("c")));
+    assert_eq!(iter.next(), Some(ValueRef::String("b")));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+
+    assert_eq!(list.get(1).map(ValueRef::to_value), Some(Value::from("b")));
+    assert_eq!(list.get(3), None);
+
+    let arrays = List::IntArray(vec![vec![1], vec![2, 3]]);
+    let lens: Vec<_> = arrays
+        .iter()
+        .map(|v| match v {
+            ValueRef::IntArray(a) => a.len(),
+            _ => unreachable!(),
+        })
+        .collect();
+    assert_eq!(lens, [1, 2]);
+
+    let value = Value::Double(f64::NAN);
+    assert_eq!(ValueRef::from(&value), ValueRef::Double(f64::NAN));
+    assert_eq!(List::Byte(vec![]).iter().count(), 0);
+}
+
+#[test]
+fn list_sort_dedup() {
+    let mut list = List::Int(vec![3, 1, 2, 3, 1]);
+    list.sort_by(|a, b| b.to_value().as_i64().cmp(&a.to_value().as_i64()));
+    assert_eq!(list, List::Int(vec![3, 3, 2, 1, 1]));
+    list.dedup();
+    assert_eq!(list, List::Int(vec![3, 2, 1]));
+
+    // The sort is stable.
+    let entry = |k: &str, v| {
+        Compound::from_iter([("k".to_owned(), k.into()), ("v".to_owned(), Value::Int(v))])
+    };
+    let mut list = List::Compound(vec![
+        entry("b", 0),
+        entry("a", 1),
+        entry("b", 2),
+        entry("a", 3),
+    ]);
+    let key = |v: ValueRef| format!("{}", v.as_compound().unwrap()["k"]);
+    list.sort_by(|a, b| key(a).cmp(&key(b)));
+    assert_eq!(
+        list,
+        List::Compound(vec![
+            entry("a", 1),
+            entry("a", 3),
+            entry("b", 0),
+            entry("b", 2)
+        ])
+    );
+
+    let mut compounds = list.clone();
+    assert!(compounds.sort_compounds_by(|a, b| b["v"].as_i64().cmp(&a["v"].as_i64())));
+    assert_eq!(
+        compounds,
+        List::Compound(vec![
+            entry("a", 3),
+            entry("b", 2),
+            entry("a", 1),
+            entry("b", 0)
+        ])
+    );
+
+    let mut ints = List::Int(vec![2, 1]);
+    assert!(!ints.sort_compounds_by(|_, _| unreachable!()));
+    assert_eq!(ints, List::Int(vec![2, 1]));
+
+    let mut list = List::List(vec![]);
+    list.sort_by(|_, _| unreachable!());
+    list.dedup();
+    assert_eq!(list, List::List(vec![]));
+
+    // Floats are deduplicated by their bits.
+    let mut list = List::Double(vec![f64::NAN, f64::NAN, 0.0, -0.0, -0.0]);
+    list.dedup();
+    assert_eq!(list, List::Double(vec![f64::NAN, 0.0, -0.0]));
+}
+
+#[test]
+fn list_map() {
+    let list = List::Compound(vec![
+        Compound::from_iter([("Count".to_owned(), Value::Byte(1))]),
+        Compound::from_iter([("Count".to_owned(), Value::Byte(2))]),
+    ]);
+
+    let counts = list
+        .clone()
+        .map(|v| match v {
+            Value::Compound(mut c) => c.remove("Count").unwrap(),
+            _ => unreachable!(),
+        })
+        .unwrap();
+    assert_eq!(counts, List::Byte(vec![1, 2]));
+
+    let err = List::Int(vec![1, 2, 3])
+        .map(|v| match v {
+            Value::Int(2) => Value::Long(2),
+            v => v,
+        })
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "at [1]: list elements must be homogeneous (got long, expected int)"
+    );
+
+    let empty = List::Compound(vec![]);
+    assert_eq!(empty.clone().map(|_| unreachable!()).unwrap(), empty);
+}
+
+#[test]
+fn debug_truncated() {
+    let value: Value = from_slice_exact(&to_vec(&Struct::new()).unwrap()).unwrap();
+
+    // Nothing is cut off with a high enough limit.
+    assert_eq!(
+        format!("{:?}", value.debug_truncated(usize::MAX)),
+        format!("{value:?}")
+    );
+    assert_eq!(
+        format!("{:#?}", value.debug_truncated(usize::MAX)),
+        format!("{value:#?}")
+    );
+
+    let value = Value::Compound(Compound::from_iter([
+        ("name".to_owned(), Value::String("abcdef".to_owned())),
+        (
+            "nested".to_owned(),
+            List::IntArray(vec![vec![1, 2, 3], vec![], vec![4]]).into(),
+        ),
+    ]));
+
+    assert_eq!(
+        format!("{:?}", value.debug_truncated(2)),
+        r#"Compound({"name": String("ab"…(4 more)), "nested": List(IntArray([[1, 2, …(1 more)], [], …(1 more)]))})"#
+    );
+}
+
+#[test]
+fn default_values() {
+    #[derive(Deserialize)]
+    struct WithDefaults {
+        #[serde(default)]
+        value: Value,
+        #[serde(default)]
+        list: List,
+        #[serde(default)]
+        compound: Compound,
+    }
+
+    let de: WithDefaults = from_slice_exact(&to_vec(&Compound::new()).unwrap()).unwrap();
+
+    assert_eq!(de.value, Value::Compound(Compound::new()));
+    assert!(de.list.is_empty());
+    assert!(de.compound.is_empty());
+
+    // The default list is the same as a deserialized empty list.
+    let empty = Compound::from_iter([("list".into(), List::Int(vec![]).into())]);
+    let de: WithDefaults = from_slice_exact(&to_vec(&empty).unwrap()).unwrap();
+    assert_eq!(de.list, List::default());
+}
+
+#[test]
+fn compound_eq_ignores_order() {
+    let a = Compound::from_iter([
+        ("x".into(), 1.into()),
+        (
+            "nested".into(),
+            Compound::from_iter([("p".into(), 1.into()), ("q".into(), 2.into())]).into(),
+        ),
+    ]);
+    let b = Compound::from_iter([
+        (
+            "nested".into(),
+            Compound::from_iter([("q".into(), 2.into()), ("p".into(), 1.into())]).into(),
+        ),
+        ("x".into(), 1.into()),
+    ]);
+
+    assert_eq!(a, b);
+    assert_eq!(Value::Compound(a.clone()), Value::Compound(b));
+
+    let mut c = a.clone();
+    c.insert("x".into(), 2.into());
+    assert_ne!(a, c);
+}
+
+#[test]
+fn value_ordering() {
+    let mut values = vec![
+        Value::String("b".to_owned()),
+        Value::Int(2),
+        Value::Double(f64::NAN),
+        Value::Byte(5),
+        Value::Double(-0.0),
+        Value::Int(-1),
+        Value::Double(0.0),
+        Value::String("a".to_owned()),
+    ];
+
+    values.sort();
+
+    assert_eq!(
+        values,
+        [
+            Value::Byte(5),
+            Value::Int(-1),
+            Value::Int(2),
+            Value::Double(-0.0),
+            Value::Double(0.0),
+            Value::Double(f64::NAN),
+            Value::String("a".to_owned()),
+            Value::String("b".to_owned()),
+        ]
+    );
+
+    assert_eq!(Value::Float(f32::NAN), Value::Float(f32::NAN));
+    assert_ne!(Value::Float(0.0), Value::Float(-0.0));
+    assert!(List::Float(vec![1.0]) < List::Float(vec![1.0, 0.0]));
+    assert!(List::Byte(vec![9]) < List::Short(vec![]));
+
+    // The order of compound entries does not matter.
+    let a = Compound::from_iter([
+        ("x".to_owned(), Value::Int(1)),
+        ("y".to_owned(), Value::Int(2)),
+    ]);
+    let b = Compound::from_iter([
+        ("y".to_owned(), Value::Int(2)),
+        ("x".to_owned(), Value::Int(1)),
+    ]);
+    let c = Compound::from_iter([("x".to_owned(), Value::Int(2))]);
+
+    assert_eq!(a.cmp(&b), std::cmp::Ordering::Equal);
+    assert!(a < c);
+
+    let map = BTreeMap::from([(Value::Compound(a), 1), (Value::Long(0), 2)]);
+    assert_eq!(map[&Value::Compound(b)], 1);
+    assert_eq!(map.keys().next(), Some(&Value::Long(0)));
+}
+
+#[test]
+fn compound_str_lookup() {
+    let mut compound = Compound::from_iter([("a".into(), 1.into()), ("b".into(), 2.into())]);
+    let key: &str = "a";
+
+    assert!(compound.contains_key(key));
+    assert_eq!(compound.get(key), Some(&Value::Int(1)));
+    assert_eq!(compound[key], Value::Int(1));
+
+    *compound.get_mut(key).unwrap() = 10.into();
+    compound["b"] = 20.into();
+
+    assert_eq!(compound.remove(key), Some(Value::Int(10)));
+    assert_eq!(compound.remove("a"), None);
+    assert!(!compound.contains_key("a"));
+    assert_eq!(compound["b"], Value::Int(20));
+}
+
+#[test]
+fn compound_entries_mut() {
+    let mut value = Value::Compound(Compound::from_iter([
+        ("a".into(), 1.into()),
+        ("b".into(), "text".into()),
+        ("c".into(), 3.into()),
+    ]));
+
+    let compound = value.as_compound_mut().unwrap();
+    for (key, v) in compound.entries_mut() {
+        if let Value::Int(n) = v {
+            *n *= 10;
+        } else {
+            *v = Value::String(key.to_owned());
+        }
+    }
+
+    assert_eq!(
+        value.as_compound().unwrap(),
+        &Compound::from_iter([
+            ("a".into(), 10.into()),
+            ("b".into(), "b".into()),
+            ("c".into(), 30.into()),
+        ])
+    );
+    assert!(Value::Int(0).as_compound().is_none());
+    assert!(Value::Int(0).as_compound_mut().is_none());
+}
+
+#[test]
+fn compound_get_or_insert_with() {
+    let mut compound = Compound::from_iter([("a".to_owned(), Value::Int(1))]);
+    let mut calls = 0;
+
+    *compound.get_or_insert_with("b", || {
+        calls += 1;
+        Value::Int(2)
+    }) = Value::Int(3);
+
+    let a = compound.get_or_insert_with("a", || {
+        calls += 1;
+        Value::Int(4)
+    });
+
+    assert_eq!(*a, Value::Int(1));
+    assert_eq!(calls, 1);
+    assert!(compound.keys().eq(["a", "b"]));
+    assert_eq!(compound["b"], Value::Int(3));
+}
+
+#[test]
+fn compound_rename_key() {
+    let keys = |c: &Compound| c.keys().cloned().collect::<Vec<_>>();
+    let mut compound = Compound::from_iter([
+        ("a".into(), 1.into()),
+        ("b".into(), 2.into()),
+        ("c".into(), 3.into()),
+        ("d".into(), 4.into()),
+    ]);
+
+    assert!(compound.rename_key("b", "x"));
+    assert_eq!(keys(&compound), ["a", "x", "c", "d"]);
+    assert_eq!(compound["x"], Value::Int(2));
+
+    assert!(compound.rename_key("x", "x"));
+    assert_eq!(keys(&compound), ["a", "x", "c", "d"]);
+
+    // Renaming over an existing key replaces that entry.
+    assert!(compound.rename_key("c", "a"));
+    assert_eq!(keys(&compound), ["x", "a", "d"]);
+    assert_eq!(compound["a"], Value::Int(3));
+
+    assert!(compound.rename_key("x", "d"));
+    assert_eq!(keys(&compound), ["d", "a"]);
+    assert_eq!(compound["d"], Value::Int(2));
+
+    assert!(!compound.rename_key("missing", "a"));
+    assert_eq!(compound.len(), 2);
+}
+
+#[test]
+fn compound_remove_order() {
+    let compound = Compound::from_iter(
+        ["a", "b", "c", "d"].map(|k| (k.to_owned(), Value::String(k.to_owned()))),
+    );
+
+    let mut shifted = compound.clone();
+    assert_eq!(
+        shifted.shift_remove("b"),
+        Some(Value::String("b".to_owned()))
+    );
+    assert_eq!(shifted.shift_remove("b"), None);
+    assert!(shifted.keys().eq(["a", "c", "d"]));
+
+    let mut swapped = compound.clone();
+    assert_eq!(
+        swapped.swap_remove("b"),
+        Some(Value::String("b".to_owned()))
+    );
+    assert_eq!(swapped.swap_remove("b"), None);
+    assert!(swapped.keys().eq(["a", "d", "c"]));
+
+    // Like `IndexMap::remove`, `remove` swaps.
+    let mut removed = compound;
+    removed.remove("b");
+    assert!(removed.keys().eq(swapped.keys()));
+}
+
+#[test]
+fn compound_derefs_to_index_map() {
+    let mut compound =
+        Compound::from_iter(["c", "a", "b"].map(|k| (k.to_owned(), Value::String(k.to_owned()))));
+
+    compound.sort_keys();
+    assert!(compound.keys().eq(["a", "b", "c"]));
+
+    compound.reverse();
+    assert_eq!(compound.first(), Some((&"c".to_owned(), &Value::from("c"))));
+    assert_eq!(compound.last(), Some((&"a".to_owned(), &Value::from("a"))));
+    assert_eq!(
+        compound.get_index(1),
+        Some((&"b".to_owned(), &Value::from("b")))
+    );
+
+    let drained: Vec<_> = compound.drain(..2).map(|(k, _)| k).collect();
+    assert_eq!(drained, ["c", "b"]);
+
+    let map: IndexMap<String, Value> = compound.clone().into_map();
+    assert_eq!(&map, compound.as_map());
+    assert_eq!(Compound::from(map), compound);
+}
+
+#[test]
+fn compound_extend() {
+    let mut compound = Compound::from_iter([("a".into(), 1.into()), ("b".into(), 2.into())]);
+
+    compound.extend([("b", 20.into()), ("c", 30.into())]);
+    compound.extend([("d".to_owned(), List::Int(vec![4]).into())]);
+    compound.extend(Compound::from_iter([("e".into(), 5.into())]));
+
+    let expected: Vec<(&str, Value)> = vec![
+        ("a", 1.into()),
+        ("b", 20.into()),
+        ("c", 30.into()),
+        ("d", List::Int(vec![4]).into()),
+        ("e", 5.into()),
+    ];
+
+    assert!(compound
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.clone()))
+        .eq(expected));
+}
+
+/// Fixtures which cannot be reproduced byte for byte. Their empty lists have an
+/// element type other than `TAG_End`, which is not preserved by [`List`].
+const NON_CANONICAL_FIXTURES: &[&str] = &["simple_player.dat"];
+
+/// Parses every fixture in `tests/data/`, writes it back out, and checks that
+/// the result is identical to the original file.
+#[test]
+fn golden_files() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data");
+    let mut count = 0;
+
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+
+        if !matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("nbt" | "dat")
+        ) {
+            continue;
+        }
+
+        let bytes = fs::read(&path).unwrap();
+
+        let mut de = Deserializer::new(bytes.as_slice(), true);
+        let value = Value::deserialize(&mut de)
+            .unwrap_or_else(|e| panic!("failed to read {}: {e}", path.display()));
+
+        assert!(de.reader.is_empty(), "trailing bytes in {}", path.display());
+
+        let written = to_vec_named(&de.root_name, &value).unwrap();
+
+        let name = path.file_name().unwrap().to_str().unwrap();
+
+        if NON_CANONICAL_FIXTURES.contains(&name) {
+            let value_again: Value = from_slice_exact(&written).unwrap();
+            assert_eq!(value, value_again, "{name} did not round trip");
+        } else {
+            assert!(written == bytes, "{name} did not round trip byte for byte");
+        }
+
+        count += 1;
+    }
+
+    assert!(count > 0, "no fixtures found");
+}
+
+#[test]
+fn list_length_overflow() {
+    /// A sequence which claims to have more elements than NBT can encode.
+    struct Huge;
+
+    impl Serialize for Huge {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            use serde::ser::SerializeSeq;
+
+            serializer.serialize_seq(Some(i32::MAX as usize + 1))?.end()
+        }
+    }
+
+    #[derive(Serialize)]
+    struct List {
+        list: Huge,
+    }
+
+    #[derive(Serialize)]
+    struct Array {
+        #[serde(with = "int_array")]
+        array: Huge,
+    }
+
+    let err = to_vec(&List { list: Huge }).unwrap_err();
+    assert_eq!(err.path(), "list");
+    assert!(err.to_string().ends_with("length of list exceeds i32::MAX"));
+
+    let err = to_vec(&Array { array: Huge }).unwrap_err();
+    assert_eq!(err.path(), "array");
+    assert!(err
+        .to_string()
+        .ends_with("length of array exceeds i32::MAX"));
+}
+
+#[test]
+fn unknown_length_seq() {
+    /// Serializes the even numbers of its range without a size hint.
+    struct Evens(i32);
+
+    impl Serialize for Evens {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            serializer.collect_seq((0..self.0).filter(|n| n % 2 == 0))
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Unknown {
+        list: Evens,
+        empty: Evens,
+        nested: Vec<Evens>,
+        #[serde(with = "int_array")]
+        array: Evens,
+    }
+
+    #[derive(Serialize)]
+    struct Known {
+        list: Vec<i32>,
+        empty: Vec<i32>,
+        nested: Vec<Vec<i32>>,
+        #[serde(with = "int_array")]
+        array: Vec<i32>,
+    }
+
+    let unknown = Unknown {
+        list: Evens(7),
+        empty: Evens(0),
+        nested: vec![Evens(3), Evens(0), Evens(5)],
+        array: Evens(9),
+    };
+
+    let known = Known {
+        list: vec![0, 2, 4, 6],
+        empty: vec![],
+        nested: vec![vec![0, 2], vec![], vec![0, 2, 4]],
+        array: vec![0, 2, 4, 6, 8],
+    };
+
+    assert_eq!(to_vec(&unknown).unwrap(), to_vec(&known).unwrap());
+}
+
+#[test]
+fn list_of_values() {
+    #[derive(Serialize)]
+    struct Values {
+        values: Vec<Value>,
+    }
+
+    let empty = to_vec(&Values { values: vec![] }).unwrap();
+
+    // The element type of an empty list is `TAG_End`.
+    let expected = [
+        &[Tag::Compound as u8, 0, 0][..],
+        &[Tag::List as u8, 0, 6],
+        b"values",
+        &[Tag::End as u8, 0, 0, 0, 0],
+        &[Tag::End as u8],
+    ]
+    .concat();
+
+    assert_eq!(empty, expected);
+
+    let values = Values {
+        values: vec![Value::Short(1), Value::Short(2), Value::Short(3)],
+    };
+
+    let value: Value = from_reader(&mut to_vec(&values).unwrap().as_slice()).unwrap();
+
+    assert_eq!(
+        value,
+        Value::Compound(Compound::from_iter([(
+            "values".to_owned(),
+            List::Short(vec![1, 2, 3]).into()
+        )]))
+    );
+
+    let mixed = Values {
+        values: vec![Value::Short(1), Value::Int(2)],
+    };
+
+    let err = to_vec(&mixed).unwrap_err();
+    assert_eq!(err.path(), "values");
+    assert!(err
+        .to_string()
+        .ends_with("elements must be homogeneous (got int, expected short)"));
+}
+
+#[test]
+fn list_writer() {
+    let mut buf = vec![];
+    let mut list = ListWriter::new(&mut buf, TagType::Compound, 2).unwrap();
+
+    list.push(&Compound::from_iter([("a".to_owned(), Value::Int(1))]))
+        .unwrap();
+    assert_eq!(list.remaining(), 1);
+
+    let err = list.push(&Value::Int(2)).unwrap_err();
+    assert_eq!(err.path(), "[1]");
+
+    // An element that fails partway through leaves nothing behind.
+    let bad = Compound::from_iter([
+        ("a".to_owned(), Value::Int(2)),
+        ("k".repeat(80_000), Value::Int(3)),
+    ]);
+    assert!(list.push(&bad).is_err());
+
+    list.push(&Compound::new()).unwrap();
+    assert!(list.push(&Compound::new()).is_err());
+    list.finish().unwrap();
+
+    let value = read_tag_body(buf.as_slice(), Tag::List as u8).unwrap();
+    assert_eq!(
+        value,
+        List::Compound(vec![
+            Compound::from_iter([("a".to_owned(), Value::Int(1))]),
+            Compound::new()
+        ])
+        .into()
+    );
+
+    let list = ListWriter::new(vec![], TagType::String, 1).unwrap();
+    assert!(list.finish().is_err());
+
+    assert!(ListWriter::new(vec![], TagType::End, 1).is_err());
+    assert!(ListWriter::new(vec![], TagType::Int, i32::MAX as usize + 1).is_err());
+}
+
+#[cfg(feature = "chunk")]
+#[test]
+fn chunk_block_states() {
+    use crate::chunk::{
+        bits_per_block, decode_block_states, encode_block_states, packed_len, Packing,
+        SECTION_VOLUME,
+    };
+
+    assert_eq!(bits_per_block(1), 4);
+    assert_eq!(bits_per_block(16), 4);
+    assert_eq!(bits_per_block(17), 5);
+    assert_eq!(bits_per_block(4096), 12);
+
+    // 5 bits per block leaves 4 unused bits at the top of every aligned long.
+    assert_eq!(packed_len(17, Packing::Aligned), 342);
+    assert_eq!(packed_len(17, Packing::Spanning), 320);
+
+    // Sixteen 4-bit indices counting up fill a long from the low bits. Both
+    // packings agree when the bits per block divide 64.
+    let indices: Vec<u16> = (0..SECTION_VOLUME).map(|i| (i % 16) as u16).collect();
+    for packing in [Packing::Aligned, Packing::Spanning] {
+        let packed = encode_block_states(&indices, 16, packing).unwrap();
+        assert_eq!(packed.len(), 256);
+        assert!(packed.iter().all(|&l| l == 0xfedcba9876543210_u64 as i64));
+        assert_eq!(decode_block_states(&packed, 16, packing).unwrap(), indices);
+    }
+
+    // With all 5-bit indices set, spanning longs are full and aligned longs
+    // have 4 unused bits.
+    let indices = vec![31; SECTION_VOLUME];
+    let packed = encode_block_states(&indices, 32, Packing::Spanning).unwrap();
+    assert!(packed.iter().all(|&l| l == -1));
+    let packed = encode_block_states(&indices, 32, Packing::Aligned).unwrap();
+    assert_eq!(packed[0], 0x0fff_ffff_ffff_ffff);
+    assert_eq!(packed[341], 0xfffff);
+
+    // The 13th index occupies bits 60 to 64 and is split across two longs
+    // when spanning.
+    let mut indices = vec![0; SECTION_VOLUME];
+    indices[12] = 0b10001;
+    let packed = encode_block_states(&indices, 32, Packing::Spanning).unwrap();
+    assert_eq!(packed[0], 1 << 60);
+    assert_eq!(packed[1], 1);
+    assert_eq!(
+        decode_block_states(&packed, 32, Packing::Spanning).unwrap(),
+        indices
+    );
+    let packed = encode_block_states(&indices, 32, Packing::Aligned).unwrap();
+    assert_eq!(packed[0], 0);
+    assert_eq!(packed[1], 0b10001);
+    assert_eq!(
+        decode_block_states(&packed, 32, Packing::Aligned).unwrap(),
+        indices
+    );
+
+    let indices: Vec<u16> = (0..SECTION_VOLUME).map(|i| (i * 7 % 33) as u16).collect();
+    for packing in [Packing::Aligned, Packing::Spanning] {
+        let packed = encode_block_states(&indices, 33, packing).unwrap();
+        assert_eq!(decode_block_states(&packed, 33, packing).unwrap(), indices);
+
+        assert!(decode_block_states(&packed[1..], 33, packing).is_err());
+        assert!(encode_block_states(&indices, 20, packing).is_err());
+    }
+}
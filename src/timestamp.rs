@@ -0,0 +1,128 @@
+/// Provides (de)serialization support for timestamps stored as a `TAG_Long`
+/// of milliseconds since the Unix epoch.
+///
+/// Minecraft stores timestamps such as `LastPlayed` in `level.dat` in this
+/// form. Times before the epoch are negative. Since NBT only keeps whole
+/// milliseconds, times are rounded down to the nearest millisecond when
+/// serialized.
+///
+/// Any type implementing [`Timestamp`](nbt_timestamp::Timestamp) can be
+/// used. This includes [`SystemTime`] and, with the `chrono` feature,
+/// `chrono::DateTime<Utc>`.
+///
+/// This module is intended to be the target of serde's `#[serde(with =
+/// "module")]` field attribute.
+///
+/// [`SystemTime`]: std::time::SystemTime
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, SystemTime};
+///
+/// use serde::{Deserialize, Serialize};
+/// use serde_nbt::binary::{from_slice_exact, to_vec};
+/// use serde_nbt::{Compound, Value};
+///
+/// #[derive(PartialEq, Debug, Serialize, Deserialize)]
+/// struct Data {
+///     #[serde(rename = "LastPlayed", with = "serde_nbt::nbt_timestamp")]
+///     last_played: SystemTime,
+/// }
+///
+/// let data = Data {
+///     last_played: SystemTime::UNIX_EPOCH + Duration::from_millis(1_660_000_000_123),
+/// };
+///
+/// let buf = to_vec(&data).unwrap();
+///
+/// assert_eq!(
+///     from_slice_exact::<Value>(&buf).unwrap(),
+///     Value::Compound(Compound::from_iter([(
+///         "LastPlayed".to_owned(),
+///         Value::Long(1_660_000_000_123),
+///     )]))
+/// );
+/// assert_eq!(from_slice_exact::<Data>(&buf).unwrap(), data);
+/// ```
+pub mod nbt_timestamp {
+    use std::time::{Duration, SystemTime};
+
+    use serde::de::Error as _;
+    use serde::ser::Error as _;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    /// A point in time which can be stored as milliseconds since the Unix
+    /// epoch.
+    pub trait Timestamp: Sized {
+        /// Returns the number of milliseconds since the epoch, rounded down,
+        /// or `None` if it does not fit in an `i64`.
+        fn to_millis(&self) -> Option<i64>;
+
+        /// Creates a timestamp from a number of milliseconds since the epoch,
+        /// or returns `None` if it cannot be represented.
+        fn from_millis(millis: i64) -> Option<Self>;
+    }
+
+    impl Timestamp for SystemTime {
+        fn to_millis(&self) -> Option<i64> {
+            match self.duration_since(SystemTime::UNIX_EPOCH) {
+                Ok(d) => i64::try_from(d.as_millis()).ok(),
+                Err(e) => {
+                    // Round down, away from the epoch.
+                    let d = e.duration();
+                    let millis = d.as_millis() + u128::from(d.subsec_nanos() % 1_000_000 != 0);
+                    i64::try_from(millis).ok().map(|m| -m)
+                }
+            }
+        }
+
+        fn from_millis(millis: i64) -> Option<Self> {
+            let d = Duration::from_millis(millis.unsigned_abs());
+
+            if millis >= 0 {
+                SystemTime::UNIX_EPOCH.checked_add(d)
+            } else {
+                SystemTime::UNIX_EPOCH.checked_sub(d)
+            }
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    impl Timestamp for chrono::DateTime<chrono::Utc> {
+        fn to_millis(&self) -> Option<i64> {
+            Some(self.timestamp_millis())
+        }
+
+        fn from_millis(millis: i64) -> Option<Self> {
+            chrono::DateTime::from_timestamp_millis(millis)
+        }
+    }
+
+    pub fn serialize<T, S>(time: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Timestamp,
+        S: Serializer,
+    {
+        match time.to_millis() {
+            Some(millis) => serializer.serialize_i64(millis),
+            None => Err(S::Error::custom(
+                "timestamp is out of range for a TAG_Long of milliseconds",
+            )),
+        }
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Timestamp,
+        D: Deserializer<'de>,
+    {
+        let millis = i64::deserialize(deserializer)?;
+
+        T::from_millis(millis).ok_or_else(|| {
+            D::Error::custom(format!(
+                "timestamp of {millis} milliseconds is out of range for this platform"
+            ))
+        })
+    }
+}
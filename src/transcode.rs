@@ -0,0 +1,388 @@
+//! Streaming conversion between binary NBT and SNBT.
+//!
+//! The functions in this module convert one representation to the other
+//! without building an intermediate [`Value`](crate::Value), which makes
+//! them well suited for dumping or patching big files. [`binary_to_snbt`]
+//! writes output as it reads input, so its memory usage stays small even for
+//! very large documents. [`snbt_to_binary`] has to buffer the contents of
+//! each list until the list is complete, so its memory usage grows with the
+//! size of the largest list.
+//!
+//! # Examples
+//!
+//! ```
+//! use serde_nbt::transcode::{binary_to_snbt, snbt_to_binary};
+//!
+//! let mut binary = Vec::new();
+//! snbt_to_binary(r#"{name:"Steve",pos:[I;1,2,3]}"#.as_bytes(), &mut binary).unwrap();
+//!
+//! let mut snbt = Vec::new();
+//! binary_to_snbt(binary.as_slice(), &mut snbt).unwrap();
+//!
+//! assert_eq!(snbt, br#"{name:"Steve",pos:[I;1,2,3]}"#);
+//! ```
+
+use std::fmt;
+use std::io::{Read, Write};
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+
+use crate::binary::{read_string_into, write_key, write_string};
+use crate::snbt::{Emitter, IoWriter, Parser, Scalar, Sink};
+use crate::{check_depth, ArrayType, Error, Result, Tag};
+
+/// Converts an uncompressed binary NBT document to SNBT.
+///
+/// The name of the root compound is discarded. The SNBT is written in compact
+/// form without any whitespace.
+///
+/// Both the reader and the writer are accessed in small pieces, so buffering
+/// them is recommended.
+pub fn binary_to_snbt<R, W>(reader: R, writer: W) -> Result<()>
+where
+    R: Read,
+    W: Write,
+{
+    let mut transcoder = BinaryToSnbt {
+        reader,
+        emitter: Emitter::new(IoWriter::new(writer)),
+        string: String::new(),
+    };
+
+    let tag = Tag::from_id(transcoder.reader.read_u8()?)?;
+
+    if tag != Tag::Compound {
+        return Err(Error::new_owned(format!(
+            "unexpected tag `{tag}` (root value must be a compound)"
+        )));
+    }
+
+    // Skip the root name.
+    read_string_into(&mut transcoder.reader, &mut transcoder.string)?;
+
+    transcoder.payload(Tag::Compound, 0)
+}
+
+/// Converts an SNBT document to uncompressed binary NBT.
+///
+/// The document must contain a single compound, which is written with an
+/// empty root name. Because the binary format puts the length of a list
+/// before its elements, the contents of each list are buffered until the
+/// list is complete. Everything else is written as soon as it is parsed.
+///
+/// Both the reader and the writer are accessed in small pieces, so buffering
+/// them is recommended.
+pub fn snbt_to_binary<R, W>(reader: R, writer: W) -> Result<()>
+where
+    R: Read,
+    W: Write,
+{
+    let mut sink = BinarySink {
+        writer,
+        stack: Vec::new(),
+        key: String::new(),
+    };
+
+    Parser::new(reader).parse_document(&mut sink)
+}
+
+struct BinaryToSnbt<R, W> {
+    reader: R,
+    emitter: Emitter<IoWriter<W>>,
+    /// Scratch space for string values.
+    string: String,
+}
+
+impl<R: Read, W: Write> BinaryToSnbt<R, W> {
+    /// Converts the result of an emitter call, recovering the I/O error if
+    /// there was one.
+    fn check(&mut self, res: fmt::Result) -> Result<()> {
+        res.map_err(|_| match self.emitter.get_mut().error.take() {
+            Some(e) => e.into(),
+            None => Error::new_static("failed to write SNBT"),
+        })
+    }
+
+    fn read_len(&mut self, what: &'static str) -> Result<i32> {
+        let len = self.reader.read_i32::<BigEndian>()?;
+
+        if len < 0 {
+            return Err(Error::new_owned(format!("{what} with negative length")));
+        }
+
+        Ok(len)
+    }
+
+    fn payload(&mut self, tag: Tag, depth: usize) -> Result<()> {
+        let res = match tag {
+            Tag::End => return Err(Error::new_static("unexpected TAG_End")),
+            Tag::Byte => {
+                let v = self.reader.read_i8()?;
+                self.emitter.byte(v)
+            }
+            Tag::Short => {
+                let v = self.reader.read_i16::<BigEndian>()?;
+                self.emitter.short(v)
+            }
+            Tag::Int => {
+                let v = self.reader.read_i32::<BigEndian>()?;
+                self.emitter.int(v)
+            }
+            Tag::Long => {
+                let v = self.reader.read_i64::<BigEndian>()?;
+                self.emitter.long(v)
+            }
+            Tag::Float => {
+                let v = self.reader.read_f32::<BigEndian>()?;
+                self.emitter.float(v)
+            }
+            Tag::Double => {
+                let v = self.reader.read_f64::<BigEndian>()?;
+                self.emitter.double(v)
+            }
+            Tag::String => {
+                read_string_into(&mut self.reader, &mut self.string)?;
+                self.emitter.string(&self.string)
+            }
+            Tag::ByteArray => return self.array(ArrayType::Byte),
+            Tag::IntArray => return self.array(ArrayType::Int),
+            Tag::LongArray => return self.array(ArrayType::Long),
+            Tag::List => return self.list(depth + 1),
+            Tag::Compound => return self.compound(depth + 1),
+        };
+
+        self.check(res)
+    }
+
+    fn array(&mut self, array_type: ArrayType) -> Result<()> {
+        let len = self.read_len("array")?;
+
+        let res = self.emitter.begin_array(array_type);
+        self.check(res)?;
+
+        for _ in 0..len {
+            self.payload(array_type.element_tag(), 0)?;
+        }
+
+        let res = self.emitter.end_list();
+        self.check(res)
+    }
+
+    fn list(&mut self, depth: usize) -> Result<()> {
+        check_depth(depth)?;
+
+        let element_tag = Tag::from_id(self.reader.read_u8()?)?;
+        let len = self.read_len("list")?;
+
+        if element_tag == Tag::End && len != 0 {
+            return Err(Error::new_static(
+                "list with TAG_End element type must have length zero",
+            ));
+        }
+
+        let res = self.emitter.begin_list();
+        self.check(res)?;
+
+        for i in 0..len {
+            self.payload(element_tag, depth)
+                .map_err(|e| e.index(i as usize))?;
+        }
+
+        let res = self.emitter.end_list();
+        self.check(res)
+    }
+
+    fn compound(&mut self, depth: usize) -> Result<()> {
+        check_depth(depth)?;
+
+        let res = self.emitter.begin_compound();
+        self.check(res)?;
+
+        let mut name = String::new();
+
+        loop {
+            let tag = Tag::from_id(self.reader.read_u8()?)?;
+
+            if tag == Tag::End {
+                break;
+            }
+
+            read_string_into(&mut self.reader, &mut name)?;
+
+            let res = self.emitter.key(&name);
+            self.check(res)?;
+
+            self.payload(tag, depth)
+                .map_err(|e| e.field(name.clone()))?;
+        }
+
+        let res = self.emitter.end_compound();
+        self.check(res)
+    }
+}
+
+struct BinarySink<W> {
+    writer: W,
+    stack: Vec<Frame>,
+    /// The key of the compound entry whose value comes next.
+    key: String,
+}
+
+enum Frame {
+    Compound,
+    List {
+        /// `TAG_End` until the type of the first element is known.
+        element_tag: Tag,
+        is_array: bool,
+        len: i32,
+        /// The encoded elements, which are written once the length is known.
+        buf: Vec<u8>,
+    },
+}
+
+/// Returns the writer that the payload of the next value should go to. This
+/// is the buffer of the innermost list, if there is one.
+fn out<'a, W: Write>(stack: &'a mut [Frame], writer: &'a mut W) -> &'a mut dyn Write {
+    for frame in stack.iter_mut().rev() {
+        if let Frame::List { buf, .. } = frame {
+            return buf;
+        }
+    }
+
+    writer
+}
+
+impl<W: Write> BinarySink<W> {
+    /// Writes whatever precedes the payload of a value with the given tag.
+    fn begin_value(&mut self, tag: Tag) -> Result<()> {
+        match self.stack.last_mut() {
+            None => {
+                if tag != Tag::Compound {
+                    return Err(Error::new_owned(format!(
+                        "unexpected {tag} (root value must be a compound)"
+                    )));
+                }
+
+                self.writer.write_u8(tag as u8)?;
+                write_key(&mut self.writer, "")?;
+            }
+            Some(Frame::Compound) => {
+                let out = out(&mut self.stack, &mut self.writer);
+                out.write_u8(tag as u8)?;
+                write_key(out, &self.key)?;
+            }
+            Some(Frame::List {
+                element_tag, len, ..
+            }) => {
+                if *element_tag == Tag::End {
+                    *element_tag = tag;
+                } else if tag != *element_tag {
+                    return Err(Error::new_owned(format!(
+                        "list/array elements must be homogeneous (got {tag}, expected \
+                         {element_tag})"
+                    )));
+                }
+
+                *len = len
+                    .checked_add(1)
+                    .ok_or_else(|| Error::new_static("length of list exceeds i32::MAX"))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<W: Write> Sink for BinarySink<W> {
+    fn begin_compound(&mut self) -> Result<()> {
+        self.begin_value(Tag::Compound)?;
+        self.stack.push(Frame::Compound);
+        Ok(())
+    }
+
+    fn key(&mut self, key: String) -> Result<()> {
+        self.key = key;
+        Ok(())
+    }
+
+    fn end_compound(&mut self) -> Result<()> {
+        self.stack.pop();
+        out(&mut self.stack, &mut self.writer).write_u8(Tag::End as u8)?;
+        Ok(())
+    }
+
+    fn begin_list(&mut self) -> Result<()> {
+        self.begin_value(Tag::List)?;
+        self.stack.push(Frame::List {
+            element_tag: Tag::End,
+            is_array: false,
+            len: 0,
+            buf: Vec::new(),
+        });
+        Ok(())
+    }
+
+    fn begin_array(&mut self, array_type: ArrayType) -> Result<()> {
+        self.begin_value(match array_type {
+            ArrayType::Byte => Tag::ByteArray,
+            ArrayType::Int => Tag::IntArray,
+            ArrayType::Long => Tag::LongArray,
+        })?;
+        self.stack.push(Frame::List {
+            element_tag: array_type.element_tag(),
+            is_array: true,
+            len: 0,
+            buf: Vec::new(),
+        });
+        Ok(())
+    }
+
+    fn end_list(&mut self) -> Result<()> {
+        if let Some(Frame::List {
+            element_tag,
+            is_array,
+            len,
+            buf,
+        }) = self.stack.pop()
+        {
+            let out = out(&mut self.stack, &mut self.writer);
+
+            if !is_array {
+                out.write_u8(element_tag as u8)?;
+            }
+
+            out.write_i32::<BigEndian>(len)?;
+            out.write_all(&buf)?;
+        }
+
+        Ok(())
+    }
+
+    fn scalar(&mut self, scalar: Scalar) -> Result<()> {
+        let tag = match scalar {
+            Scalar::Byte(_) => Tag::Byte,
+            Scalar::Short(_) => Tag::Short,
+            Scalar::Int(_) => Tag::Int,
+            Scalar::Long(_) => Tag::Long,
+            Scalar::Float(_) => Tag::Float,
+            Scalar::Double(_) => Tag::Double,
+            Scalar::String(_) => Tag::String,
+        };
+
+        self.begin_value(tag)?;
+
+        let out = out(&mut self.stack, &mut self.writer);
+
+        match scalar {
+            Scalar::Byte(v) => out.write_i8(v)?,
+            Scalar::Short(v) => out.write_i16::<BigEndian>(v)?,
+            Scalar::Int(v) => out.write_i32::<BigEndian>(v)?,
+            Scalar::Long(v) => out.write_i64::<BigEndian>(v)?,
+            Scalar::Float(v) => out.write_f32::<BigEndian>(v)?,
+            Scalar::Double(v) => out.write_f64::<BigEndian>(v)?,
+            Scalar::String(v) => write_string(out, &v)?,
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+/// Provides (de)serialization support for UUIDs stored as an NBT int array.
+///
+/// NBT has no 128-bit integer type, so `i128` and `u128` cannot be serialized
+/// directly. Since 1.16, Minecraft stores UUIDs as an int array of four
+/// elements, starting with the most significant 32 bits. This module maps a
+/// `u128` to that representation.
+///
+/// This module is intended to be the target of serde's `#[serde(with =
+/// "module")]` field attribute.
+///
+/// # Examples
+///
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use serde_nbt::binary::{from_slice_exact, to_vec};
+/// use serde_nbt::{Compound, Value};
+///
+/// #[derive(PartialEq, Debug, Serialize, Deserialize)]
+/// struct Entity {
+///     #[serde(rename = "UUID", with = "serde_nbt::uuid_int_array")]
+///     uuid: u128,
+/// }
+///
+/// let entity = Entity {
+///     uuid: 0x069a79f4_44e94726_a5befca9_0e38aaf5,
+/// };
+///
+/// let buf = to_vec(&entity).unwrap();
+///
+/// assert_eq!(
+///     from_slice_exact::<Value>(&buf).unwrap(),
+///     Value::Compound(Compound::from_iter([(
+///         "UUID".to_owned(),
+///         Value::IntArray(vec![110787060, 1156138790, -1514210135, 238594805]),
+///     )]))
+/// );
+/// assert_eq!(from_slice_exact::<Entity>(&buf).unwrap(), entity);
+/// ```
+pub mod uuid_int_array {
+    use serde::de::Error;
+    use serde::{Deserializer, Serializer};
+
+    use crate::int_array;
+
+    pub fn serialize<S>(uuid: &u128, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let ints: Vec<i32> = (0..4).map(|i| (uuid >> (96 - 32 * i)) as i32).collect();
+
+        int_array::serialize(&ints, serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<u128, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let ints: Vec<i32> = int_array::deserialize(deserializer)?;
+
+        if ints.len() != 4 {
+            return Err(D::Error::invalid_length(
+                ints.len(),
+                &"an int array of length 4",
+            ));
+        }
+
+        Ok(ints
+            .into_iter()
+            .fold(0, |uuid, i| uuid << 32 | i as u32 as u128))
+    }
+}
@@ -1,11 +1,12 @@
 use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::fmt;
 
-use indexmap::IndexMap;
 use serde::de::{DeserializeSeed, EnumAccess, Error, MapAccess, SeqAccess, VariantAccess, Visitor};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::{byte_array, int_array, long_array, ArrayType};
+use crate::compound::CompoundVisitor;
+use crate::{byte_array, int_array, long_array, ArrayType, Compound, ValueRef, VALUE_NAME};
 
 /// An arbitrary NBT value.
 ///
@@ -13,7 +14,20 @@ use crate::{byte_array, int_array, long_array, ArrayType};
 /// fail to serialize because NBT requires that the root value be a compound.
 ///
 /// At the root level you may want to use [`Compound`] instead.
-#[derive(Clone, PartialEq, Debug)]
+///
+/// # Equality and ordering
+///
+/// `Value` implements [`Eq`] and [`Ord`], so values can be sorted and used as
+/// keys of a [`BTreeMap`](std::collections::BTreeMap). Floats are compared by
+/// their bits using [`f64::total_cmp`]. As a result, NaN is equal to itself,
+/// while `0.0` and `-0.0` are not equal.
+///
+/// Values of the same type are ordered by their contents. Lists and arrays
+/// are compared element by element, and compounds are compared by their
+/// entries sorted by key. Values of different types are ordered by the ID of
+/// their tag type. This order across types is arbitrary, but it is stable and
+/// will not change between versions.
+#[derive(Clone, Debug)]
 pub enum Value {
     Byte(i8),
     Short(i16),
@@ -29,11 +43,6 @@ pub enum Value {
     LongArray(Vec<i64>),
 }
 
-/// An arbitrary NBT compound.
-///
-/// This is a convenient type alias for the [`IndexMap`] type.
-pub type Compound = IndexMap<String, Value>;
-
 /// An NBT list value.
 ///
 /// NBT lists are homogeneous, meaning each list element must be of the same
@@ -46,7 +55,7 @@ pub type Compound = IndexMap<String, Value>;
 ///
 /// Every possible element type has its own variant in this enum. As a result,
 /// heterogeneous lists are unrepresentable.
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, Debug)]
 pub enum List {
     Byte(Vec<i8>),
     Short(Vec<i16>),
@@ -62,6 +71,223 @@ pub enum List {
     LongArray(Vec<Vec<i64>>),
 }
 
+impl Value {
+    /// Returns the value of a numeric tag as an `f64`, or `None` if this is
+    /// not a byte, short, int, long, float, or double.
+    ///
+    /// This is useful when the exact numeric type of a field varies between
+    /// versions of a format. Longs with a magnitude greater than 2<sup>53</sup>
+    /// are rounded to the nearest representable `f64`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::Value;
+    ///
+    /// assert_eq!(Value::Byte(3).as_f64(), Some(3.0));
+    /// assert_eq!(Value::Float(0.5).as_f64(), Some(0.5));
+    /// assert_eq!(Value::String("3".to_owned()).as_f64(), None);
+    /// ```
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Value::Byte(v) => Some(v.into()),
+            Value::Short(v) => Some(v.into()),
+            Value::Int(v) => Some(v.into()),
+            Value::Long(v) => Some(v as f64),
+            Value::Float(v) => Some(v.into()),
+            Value::Double(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a numeric tag as an `i64`, or `None` if this is
+    /// not a byte, short, int, long, float, or double.
+    ///
+    /// Floats and doubles are truncated toward zero and saturate at the
+    /// bounds of `i64`. NaN becomes zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::Value;
+    ///
+    /// assert_eq!(Value::Short(-7).as_i64(), Some(-7));
+    /// assert_eq!(Value::Double(2.9).as_i64(), Some(2));
+    /// assert_eq!(Value::IntArray(vec![1]).as_i64(), None);
+    /// ```
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            Value::Byte(v) => Some(v.into()),
+            Value::Short(v) => Some(v.into()),
+            Value::Int(v) => Some(v.into()),
+            Value::Long(v) => Some(v),
+            Value::Float(v) => Some(v as i64),
+            Value::Double(v) => Some(v as i64),
+            _ => None,
+        }
+    }
+
+    /// Returns a reference to the compound if this is a [`Value::Compound`].
+    pub fn as_compound(&self) -> Option<&Compound> {
+        match self {
+            Value::Compound(c) => Some(c),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the compound if this is a
+    /// [`Value::Compound`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::{Compound, Value};
+    ///
+    /// let mut pos = Value::Compound(Compound::from_iter([
+    ///     ("x".to_owned(), Value::Int(-40_000_000)),
+    ///     ("z".to_owned(), Value::Int(12)),
+    /// ]));
+    ///
+    /// for (_, v) in pos.as_compound_mut().unwrap().entries_mut() {
+    ///     if let Value::Int(n) = v {
+    ///         *n = (*n).clamp(-30_000_000, 30_000_000);
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(pos.as_compound().unwrap()["x"], Value::Int(-30_000_000));
+    /// ```
+    pub fn as_compound_mut(&mut self) -> Option<&mut Compound> {
+        match self {
+            Value::Compound(c) => Some(c),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if this is a compound, list, or array with no elements.
+    /// Scalars, including empty strings, are never empty.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Value::ByteArray(v) => v.is_empty(),
+            Value::List(v) => v.is_empty(),
+            Value::Compound(v) => v.is_empty(),
+            Value::IntArray(v) => v.is_empty(),
+            Value::LongArray(v) => v.is_empty(),
+            _ => false,
+        }
+    }
+
+    /// Recursively removes empty compounds, lists, and arrays.
+    ///
+    /// Compound entries and list elements are removed if they are empty after
+    /// their own contents have been pruned. This value itself is never
+    /// removed, so it may be empty afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::{Compound, List, Value};
+    ///
+    /// let mut value = Value::Compound(Compound::from_iter([
+    ///     ("name".to_owned(), Value::String(String::new())),
+    ///     ("tags".to_owned(), List::String(vec![]).into()),
+    ///     (
+    ///         "display".to_owned(),
+    ///         Value::Compound(Compound::from_iter([(
+    ///             "Lore".to_owned(),
+    ///             List::List(vec![List::Int(vec![])]).into(),
+    ///         )])),
+    ///     ),
+    /// ]));
+    ///
+    /// value.prune_empty();
+    ///
+    /// assert_eq!(
+    ///     value,
+    ///     Value::Compound(Compound::from_iter([(
+    ///         "name".to_owned(),
+    ///         Value::String(String::new())
+    ///     )]))
+    /// );
+    /// ```
+    pub fn prune_empty(&mut self) {
+        match self {
+            Value::List(l) => l.prune_empty(),
+            Value::Compound(c) => c.prune_empty(),
+            _ => {}
+        }
+    }
+
+    /// Recursively removes the compound entries for which `pred` returns
+    /// `false`.
+    ///
+    /// The predicate is called with the key and value of every entry in
+    /// every nested compound, including compounds inside of lists. Pruning
+    /// happens bottom-up, so the value passed to `pred` has already had its
+    /// own entries pruned. List elements have no key and are never removed,
+    /// and neither is this value itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::{Compound, List, Value};
+    ///
+    /// let mut value = Value::Compound(Compound::from_iter([
+    ///     ("_cache".to_owned(), Value::Int(1)),
+    ///     (
+    ///         "Items".to_owned(),
+    ///         List::Compound(vec![Compound::from_iter([
+    ///             ("id".to_owned(), Value::from("minecraft:stone")),
+    ///             ("_slot".to_owned(), Value::Byte(0)),
+    ///         ])])
+    ///         .into(),
+    ///     ),
+    /// ]));
+    ///
+    /// value.retain_recursive(|key, _| !key.starts_with('_'));
+    ///
+    /// assert_eq!(
+    ///     value,
+    ///     Value::Compound(Compound::from_iter([(
+    ///         "Items".to_owned(),
+    ///         List::Compound(vec![Compound::from_iter([(
+    ///             "id".to_owned(),
+    ///             Value::from("minecraft:stone")
+    ///         )])])
+    ///         .into(),
+    ///     )]))
+    /// );
+    /// ```
+    pub fn retain_recursive<F>(&mut self, pred: F)
+    where
+        F: Fn(&str, &Value) -> bool,
+    {
+        retain_value(self, &pred);
+    }
+}
+
+fn retain_value(value: &mut Value, pred: &dyn Fn(&str, &Value) -> bool) {
+    match value {
+        Value::List(l) => retain_list(l, pred),
+        Value::Compound(c) => retain_compound(c, pred),
+        _ => {}
+    }
+}
+
+fn retain_list(list: &mut List, pred: &dyn Fn(&str, &Value) -> bool) {
+    match list {
+        List::List(l) => l.iter_mut().for_each(|l| retain_list(l, pred)),
+        List::Compound(l) => l.iter_mut().for_each(|c| retain_compound(c, pred)),
+        _ => {}
+    }
+}
+
+fn retain_compound(compound: &mut Compound, pred: &dyn Fn(&str, &Value) -> bool) {
+    compound.retain(|k, v| {
+        retain_value(v, pred);
+        pred(k, v)
+    });
+}
+
 impl List {
     pub fn len(&self) -> usize {
         match self {
@@ -83,6 +309,280 @@ impl List {
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Converts this list into a [`Value::List`].
+    ///
+    /// This is the same as `Value::from(list)`, but reads better at the end
+    /// of a chain of method calls.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::{List, Value};
+    ///
+    /// let value = List::Int(vec![1, 2]).into_value();
+    ///
+    /// assert_eq!(value, Value::List(List::Int(vec![1, 2])));
+    /// ```
+    pub fn into_value(self) -> Value {
+        Value::List(self)
+    }
+
+    /// Sorts the elements of the list with a comparator function.
+    ///
+    /// The elements are passed to `compare` as [`ValueRef`]s of the list's
+    /// element type, so one comparator works for every kind of list. This is
+    /// meant for code which handles lists without knowing their element type
+    /// ahead of time. When the list is known to contain compounds,
+    /// [`Self::sort_compounds_by`] takes a comparator of compounds instead.
+    /// The elements are sorted in place, and the sort is stable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::{Compound, List, Value, ValueRef};
+    ///
+    /// let slot = |n| Compound::from_iter([("Slot".to_owned(), Value::Byte(n))]);
+    ///
+    /// let mut items = List::Compound(vec![slot(2), slot(0), slot(1)]);
+    ///
+    /// items.sort_by(|a, b| {
+    ///     let slot = |v: ValueRef| v.as_compound().and_then(|c| c.get("Slot")?.as_i64());
+    ///     slot(a).cmp(&slot(b))
+    /// });
+    ///
+    /// assert_eq!(items, List::Compound(vec![slot(0), slot(1), slot(2)]));
+    /// ```
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(ValueRef<'_>, ValueRef<'_>) -> Ordering,
+    {
+        match self {
+            List::Byte(l) => l.sort_by(|a, b| compare(ValueRef::Byte(*a), ValueRef::Byte(*b))),
+            List::Short(l) => l.sort_by(|a, b| compare(ValueRef::Short(*a), ValueRef::Short(*b))),
+            List::Int(l) => l.sort_by(|a, b| compare(ValueRef::Int(*a), ValueRef::Int(*b))),
+            List::Long(l) => l.sort_by(|a, b| compare(ValueRef::Long(*a), ValueRef::Long(*b))),
+            List::Float(l) => l.sort_by(|a, b| compare(ValueRef::Float(*a), ValueRef::Float(*b))),
+            List::Double(l) => {
+                l.sort_by(|a, b| compare(ValueRef::Double(*a), ValueRef::Double(*b)))
+            }
+            List::ByteArray(l) => {
+                l.sort_by(|a, b| compare(ValueRef::ByteArray(a), ValueRef::ByteArray(b)))
+            }
+            List::String(l) => l.sort_by(|a, b| compare(ValueRef::String(a), ValueRef::String(b))),
+            List::List(l) => l.sort_by(|a, b| compare(ValueRef::List(a), ValueRef::List(b))),
+            List::Compound(l) => {
+                l.sort_by(|a, b| compare(ValueRef::Compound(a), ValueRef::Compound(b)))
+            }
+            List::IntArray(l) => {
+                l.sort_by(|a, b| compare(ValueRef::IntArray(a), ValueRef::IntArray(b)))
+            }
+            List::LongArray(l) => {
+                l.sort_by(|a, b| compare(ValueRef::LongArray(a), ValueRef::LongArray(b)))
+            }
+        }
+    }
+
+    /// Sorts a list of compounds with a comparator function.
+    ///
+    /// Returns `false` without changing the list if it is not a list of
+    /// compounds. The sort is stable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::{Compound, List, Value};
+    ///
+    /// let slot = |n| Compound::from_iter([("Slot".to_owned(), Value::Byte(n))]);
+    ///
+    /// let mut items = List::Compound(vec![slot(2), slot(0), slot(1)]);
+    ///
+    /// let sorted = items.sort_compounds_by(|a, b| {
+    ///     let slot = |c: &Compound| c.get("Slot").and_then(Value::as_i64);
+    ///     slot(a).cmp(&slot(b))
+    /// });
+    ///
+    /// assert!(sorted);
+    /// assert_eq!(items, List::Compound(vec![slot(0), slot(1), slot(2)]));
+    /// ```
+    #[must_use = "the list is not sorted if it does not contain compounds"]
+    pub fn sort_compounds_by<F>(&mut self, compare: F) -> bool
+    where
+        F: FnMut(&Compound, &Compound) -> Ordering,
+    {
+        match self {
+            List::Compound(l) => {
+                l.sort_by(compare);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Applies `f` to every element of the list, returning a list of the
+    /// results.
+    ///
+    /// The elements are passed to `f` as [`Value`]s. The results may be of a
+    /// different type than the original elements, but they must all have the
+    /// same type as each other. An error is returned otherwise. Mapping an
+    /// empty list returns it unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::{List, Value};
+    ///
+    /// let list = List::Int(vec![1, 2, 3]);
+    ///
+    /// let incremented = list
+    ///     .clone()
+    ///     .map(|v| match v {
+    ///         Value::Int(n) => Value::Int(n + 1),
+    ///         v => v,
+    ///     })
+    ///     .unwrap();
+    ///
+    /// assert_eq!(incremented, List::Int(vec![2, 3, 4]));
+    ///
+    /// let as_strings = list.map(|v| Value::String(v.to_string())).unwrap();
+    ///
+    /// assert_eq!(as_strings, List::String(vec!["1".into(), "2".into(), "3".into()]));
+    /// ```
+    pub fn map<F>(self, f: F) -> crate::Result<List>
+    where
+        F: FnMut(Value) -> Value,
+    {
+        if self.is_empty() {
+            return Ok(self);
+        }
+
+        let mut out: Option<List> = None;
+
+        for (i, value) in self.into_values().into_iter().map(f).enumerate() {
+            match &mut out {
+                None => out = Some(List::singleton(value)),
+                Some(list) => {
+                    if let Err(value) = list.push(value) {
+                        return Err(crate::Error::new_owned(format!(
+                            "list elements must be homogeneous (got {}, expected {})",
+                            value.tag_type(),
+                            list.element_type()
+                        ))
+                        .index(i));
+                    }
+                }
+            }
+        }
+
+        Ok(out.expect("list is not empty"))
+    }
+
+    /// Converts the elements of the list into [`Value`]s.
+    pub(crate) fn into_values(self) -> Vec<Value> {
+        macro_rules! into_values {
+            ($($variant:ident),*) => {
+                match self {
+                    $(List::$variant(l) => l.into_iter().map(Value::$variant).collect(),)*
+                }
+            };
+        }
+
+        into_values!(
+            Byte, Short, Int, Long, Float, Double, ByteArray, String, List, Compound, IntArray,
+            LongArray
+        )
+    }
+
+    /// Creates a list containing a single value.
+    pub(crate) fn singleton(value: Value) -> List {
+        macro_rules! singleton {
+            ($($variant:ident),*) => {
+                match value {
+                    $(Value::$variant(v) => List::$variant(vec![v]),)*
+                }
+            };
+        }
+
+        singleton!(
+            Byte, Short, Int, Long, Float, Double, ByteArray, String, List, Compound, IntArray,
+            LongArray
+        )
+    }
+
+    /// Appends a value to the list, or returns it if it has the wrong type.
+    pub(crate) fn push(&mut self, value: Value) -> Result<(), Value> {
+        macro_rules! push {
+            ($($variant:ident),*) => {
+                match (self, value) {
+                    $((List::$variant(l), Value::$variant(v)) => l.push(v),)*
+                    (_, value) => return Err(value),
+                }
+            };
+        }
+
+        push!(
+            Byte, Short, Int, Long, Float, Double, ByteArray, String, List, Compound, IntArray,
+            LongArray
+        );
+
+        Ok(())
+    }
+
+    /// Removes consecutive equal elements from the list.
+    ///
+    /// If the list is sorted, this removes all duplicates.
+    pub fn dedup(&mut self) {
+        match self {
+            List::Byte(l) => l.dedup(),
+            List::Short(l) => l.dedup(),
+            List::Int(l) => l.dedup(),
+            List::Long(l) => l.dedup(),
+            // Floats are compared by their bits, like `PartialEq` of `List`.
+            List::Float(l) => l.dedup_by(|a, b| a.to_bits() == b.to_bits()),
+            List::Double(l) => l.dedup_by(|a, b| a.to_bits() == b.to_bits()),
+            List::ByteArray(l) => l.dedup(),
+            List::String(l) => l.dedup(),
+            List::List(l) => l.dedup(),
+            List::Compound(l) => l.dedup(),
+            List::IntArray(l) => l.dedup(),
+            List::LongArray(l) => l.dedup(),
+        }
+    }
+
+    /// Removes empty containers from the elements of this list. See
+    /// [`Value::prune_empty`].
+    fn prune_empty(&mut self) {
+        match self {
+            List::ByteArray(l) => l.retain(|a| !a.is_empty()),
+            List::List(l) => l.retain_mut(|l| {
+                l.prune_empty();
+                !l.is_empty()
+            }),
+            List::Compound(l) => l.retain_mut(|c| {
+                c.prune_empty();
+                !c.is_empty()
+            }),
+            List::IntArray(l) => l.retain(|a| !a.is_empty()),
+            List::LongArray(l) => l.retain(|a| !a.is_empty()),
+            _ => {}
+        }
+    }
+}
+
+/// The default value is an empty compound, since compounds are the only
+/// values allowed at the root of an NBT document.
+impl Default for Value {
+    fn default() -> Self {
+        Value::Compound(Compound::new())
+    }
+}
+
+/// The default list is empty. Its element type is byte, which is the same
+/// type that deserializing an empty list produces.
+impl Default for List {
+    fn default() -> Self {
+        List::Byte(Vec::new())
+    }
 }
 
 impl From<i8> for Value {
@@ -169,6 +669,73 @@ impl From<Vec<i64>> for Value {
     }
 }
 
+impl<'a> From<&'a [i8]> for Value {
+    fn from(v: &'a [i8]) -> Self {
+        Self::ByteArray(v.to_vec())
+    }
+}
+
+impl<const N: usize> From<[i8; N]> for Value {
+    fn from(v: [i8; N]) -> Self {
+        Self::ByteArray(v.to_vec())
+    }
+}
+
+impl<'a> From<&'a [i32]> for Value {
+    fn from(v: &'a [i32]) -> Self {
+        Self::IntArray(v.to_vec())
+    }
+}
+
+impl<const N: usize> From<[i32; N]> for Value {
+    fn from(v: [i32; N]) -> Self {
+        Self::IntArray(v.to_vec())
+    }
+}
+
+impl<'a> From<&'a [i64]> for Value {
+    fn from(v: &'a [i64]) -> Self {
+        Self::LongArray(v.to_vec())
+    }
+}
+
+impl<const N: usize> From<[i64; N]> for Value {
+    fn from(v: [i64; N]) -> Self {
+        Self::LongArray(v.to_vec())
+    }
+}
+
+/// Extracts the compound from a [`Value::Compound`]. Any other variant is an
+/// error.
+impl TryFrom<Value> for Compound {
+    type Error = crate::Error;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Compound(c) => Ok(c),
+            v => Err(crate::Error::new_owned(format!(
+                "expected compound, found {}",
+                v.tag_type()
+            ))),
+        }
+    }
+}
+
+/// Extracts the list from a [`Value::List`]. Any other variant is an error.
+impl TryFrom<Value> for List {
+    type Error = crate::Error;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::List(l) => Ok(l),
+            v => Err(crate::Error::new_owned(format!(
+                "expected list, found {}",
+                v.tag_type()
+            ))),
+        }
+    }
+}
+
 impl From<Vec<i8>> for List {
     fn from(v: Vec<i8>) -> Self {
         List::Byte(v)
@@ -275,12 +842,33 @@ impl Serialize for List {
             List::Long(l) => l.serialize(serializer),
             List::Float(l) => l.serialize(serializer),
             List::Double(l) => l.serialize(serializer),
-            List::ByteArray(l) => l.serialize(serializer),
+            List::ByteArray(l) => serializer.collect_seq(l.iter().map(ArrayRef::Byte)),
             List::String(l) => l.serialize(serializer),
             List::List(l) => l.serialize(serializer),
             List::Compound(l) => l.serialize(serializer),
-            List::IntArray(l) => l.serialize(serializer),
-            List::LongArray(l) => l.serialize(serializer),
+            List::IntArray(l) => serializer.collect_seq(l.iter().map(ArrayRef::Int)),
+            List::LongArray(l) => serializer.collect_seq(l.iter().map(ArrayRef::Long)),
+        }
+    }
+}
+
+/// Serializes a borrowed array with the appropriate array tag. Without this,
+/// the arrays in a list of arrays would be serialized as lists.
+enum ArrayRef<'a> {
+    Byte(&'a Vec<i8>),
+    Int(&'a Vec<i32>),
+    Long(&'a Vec<i64>),
+}
+
+impl Serialize for ArrayRef<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            ArrayRef::Byte(a) => byte_array::serialize(*a, serializer),
+            ArrayRef::Int(a) => int_array::serialize(*a, serializer),
+            ArrayRef::Long(a) => long_array::serialize(*a, serializer),
         }
     }
 }
@@ -290,7 +878,11 @@ impl<'de> Deserialize<'de> for Value {
     where
         D: Deserializer<'de>,
     {
-        deserializer.deserialize_any(ValueVisitor)
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(ValueVisitor)
+        } else {
+            deserializer.deserialize_newtype_struct(VALUE_NAME, ValueVisitor)
+        }
     }
 }
 
@@ -370,7 +962,14 @@ impl<'de> Visitor<'de> for ValueVisitor {
     where
         A: MapAccess<'de>,
     {
-        visit_map(map).map(Value::Compound)
+        CompoundVisitor.visit_map(map).map(Value::Compound)
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
     }
 
     fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
@@ -429,7 +1028,11 @@ impl<'de, 'a> DeserializeSeed<'de> for DeserializeListElement<'a> {
     where
         D: Deserializer<'de>,
     {
-        deserializer.deserialize_any(self)
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(self)
+        } else {
+            deserializer.deserialize_newtype_struct(VALUE_NAME, self)
+        }
     }
 }
 
@@ -521,19 +1124,26 @@ impl<'de, 'a> Visitor<'de> for DeserializeListElement<'a> {
     where
         A: MapAccess<'de>,
     {
-        visit!(self, Compound, visit_map(map)?, A::Error)
+        visit!(self, Compound, CompoundVisitor.visit_map(map)?, A::Error)
     }
-}
-
-fn visit_map<'de, A>(mut map: A) -> Result<Compound, A::Error>
-where
-    A: MapAccess<'de>,
-{
-    let mut compound = Compound::new();
 
-    while let Some((k, v)) = map.next_entry()? {
-        compound.insert(k, v);
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
     }
 
-    Ok(compound)
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: EnumAccess<'de>,
+    {
+        let (array_type, variant) = data.variant()?;
+
+        match array_type {
+            ArrayType::Byte => visit!(self, ByteArray, variant.newtype_variant()?, A::Error),
+            ArrayType::Int => visit!(self, IntArray, variant.newtype_variant()?, A::Error),
+            ArrayType::Long => visit!(self, LongArray, variant.newtype_variant()?, A::Error),
+        }
+    }
 }
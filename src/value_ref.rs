@@ -0,0 +1,199 @@
+use std::iter::FusedIterator;
+
+use crate::{Compound, List, Value};
+
+/// A borrowed view of a [`Value`].
+///
+/// Lists store their elements directly rather than as `Value`s, so a list
+/// element cannot be borrowed as a `&Value`. A `ValueRef` can refer to either
+/// without cloning anything. It is returned by [`List::get`] and
+/// [`List::iter`], and can be created from a `&Value` with [`From`].
+///
+/// Like `Value`, floats are compared by their bits.
+#[derive(Clone, Copy, Debug)]
+pub enum ValueRef<'a> {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(&'a [i8]),
+    String(&'a str),
+    List(&'a List),
+    Compound(&'a Compound),
+    IntArray(&'a [i32]),
+    LongArray(&'a [i64]),
+}
+
+impl<'a> ValueRef<'a> {
+    /// Clones the referenced data into an owned [`Value`].
+    pub fn to_value(self) -> Value {
+        match self {
+            ValueRef::Byte(v) => Value::Byte(v),
+            ValueRef::Short(v) => Value::Short(v),
+            ValueRef::Int(v) => Value::Int(v),
+            ValueRef::Long(v) => Value::Long(v),
+            ValueRef::Float(v) => Value::Float(v),
+            ValueRef::Double(v) => Value::Double(v),
+            ValueRef::ByteArray(v) => Value::ByteArray(v.to_vec()),
+            ValueRef::String(v) => Value::String(v.to_owned()),
+            ValueRef::List(v) => Value::List(v.clone()),
+            ValueRef::Compound(v) => Value::Compound(v.clone()),
+            ValueRef::IntArray(v) => Value::IntArray(v.to_vec()),
+            ValueRef::LongArray(v) => Value::LongArray(v.to_vec()),
+        }
+    }
+
+    /// Returns the referenced compound, or `None` if this is not a compound.
+    pub fn as_compound(self) -> Option<&'a Compound> {
+        match self {
+            ValueRef::Compound(c) => Some(c),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> From<&'a Value> for ValueRef<'a> {
+    fn from(value: &'a Value) -> Self {
+        match value {
+            Value::Byte(v) => ValueRef::Byte(*v),
+            Value::Short(v) => ValueRef::Short(*v),
+            Value::Int(v) => ValueRef::Int(*v),
+            Value::Long(v) => ValueRef::Long(*v),
+            Value::Float(v) => ValueRef::Float(*v),
+            Value::Double(v) => ValueRef::Double(*v),
+            Value::ByteArray(v) => ValueRef::ByteArray(v),
+            Value::String(v) => ValueRef::String(v),
+            Value::List(v) => ValueRef::List(v),
+            Value::Compound(v) => ValueRef::Compound(v),
+            Value::IntArray(v) => ValueRef::IntArray(v),
+            Value::LongArray(v) => ValueRef::LongArray(v),
+        }
+    }
+}
+
+impl PartialEq for ValueRef<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ValueRef::Byte(a), ValueRef::Byte(b)) => a == b,
+            (ValueRef::Short(a), ValueRef::Short(b)) => a == b,
+            (ValueRef::Int(a), ValueRef::Int(b)) => a == b,
+            (ValueRef::Long(a), ValueRef::Long(b)) => a == b,
+            (ValueRef::Float(a), ValueRef::Float(b)) => a.to_bits() == b.to_bits(),
+            (ValueRef::Double(a), ValueRef::Double(b)) => a.to_bits() == b.to_bits(),
+            (ValueRef::ByteArray(a), ValueRef::ByteArray(b)) => a == b,
+            (ValueRef::String(a), ValueRef::String(b)) => a == b,
+            (ValueRef::List(a), ValueRef::List(b)) => a == b,
+            (ValueRef::Compound(a), ValueRef::Compound(b)) => a == b,
+            (ValueRef::IntArray(a), ValueRef::IntArray(b)) => a == b,
+            (ValueRef::LongArray(a), ValueRef::LongArray(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for ValueRef<'_> {}
+
+impl List {
+    /// Returns a reference to the element at `index`, or `None` if the index
+    /// is out of bounds.
+    pub fn get(&self, index: usize) -> Option<ValueRef<'_>> {
+        Some(match self {
+            List::Byte(v) => ValueRef::Byte(*v.get(index)?),
+            List::Short(v) => ValueRef::Short(*v.get(index)?),
+            List::Int(v) => ValueRef::Int(*v.get(index)?),
+            List::Long(v) => ValueRef::Long(*v.get(index)?),
+            List::Float(v) => ValueRef::Float(*v.get(index)?),
+            List::Double(v) => ValueRef::Double(*v.get(index)?),
+            List::ByteArray(v) => ValueRef::ByteArray(v.get(index)?),
+            List::String(v) => ValueRef::String(v.get(index)?),
+            List::List(v) => ValueRef::List(v.get(index)?),
+            List::Compound(v) => ValueRef::Compound(v.get(index)?),
+            List::IntArray(v) => ValueRef::IntArray(v.get(index)?),
+            List::LongArray(v) => ValueRef::LongArray(v.get(index)?),
+        })
+    }
+
+    /// Returns an iterator over references to the elements of this list.
+    ///
+    /// No elements are cloned, so read-only traversal of a list does not
+    /// allocate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_nbt::{Compound, List, Value, ValueRef};
+    ///
+    /// let sections = List::Compound(vec![
+    ///     Compound::from_iter([("Y".to_owned(), Value::Byte(0))]),
+    ///     Compound::from_iter([("Y".to_owned(), Value::Byte(1))]),
+    /// ]);
+    ///
+    /// let ys: Vec<_> = sections
+    ///     .iter()
+    ///     .filter_map(ValueRef::as_compound)
+    ///     .filter_map(|c| c.get("Y"))
+    ///     .collect();
+    ///
+    /// assert_eq!(ys, [&Value::Byte(0), &Value::Byte(1)]);
+    /// ```
+    pub fn iter(&self) -> ListIter<'_> {
+        ListIter {
+            list: self,
+            front: 0,
+            back: self.len(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a List {
+    type Item = ValueRef<'a>;
+    type IntoIter = ListIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the elements of a [`List`], as returned by
+/// [`List::iter`].
+#[derive(Clone, Debug)]
+pub struct ListIter<'a> {
+    list: &'a List,
+    front: usize,
+    back: usize,
+}
+
+impl<'a> Iterator for ListIter<'a> {
+    type Item = ValueRef<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+
+        self.front += 1;
+        self.list.get(self.front - 1)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for ListIter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+
+        self.back -= 1;
+        self.list.get(self.back)
+    }
+}
+
+impl ExactSizeIterator for ListIter<'_> {}
+
+impl FusedIterator for ListIter<'_> {}